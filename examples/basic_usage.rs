@@ -6,7 +6,7 @@ use tokio::signal;
 #[tokio::main]
 async fn main() {
     // Initialize Logger
-    let logger = Logger::new("config/config.yaml", b"anexampleverysecurekey123456789012")
+    let logger = Logger::new(Some("config/config.yaml"), b"anexampleverysecurekey123456789012")
         .await
         .expect("Failed to initialize logger");
 
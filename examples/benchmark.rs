@@ -6,7 +6,7 @@ use tokio::time::{sleep, Duration};
 #[tokio::main]
 async fn main() {
     // Initialize Logger with a benchmark-specific configuration
-    let logger = Logger::new("config/benchmark_config.yaml", b"anexampleverysecurekey123456789012")
+    let logger = Logger::new(Some("config/benchmark_config.yaml"), b"anexampleverysecurekey123456789012")
         .await
         .expect("Failed to initialize logger");
 
@@ -61,9 +61,10 @@ async fn main() {
     let errors = logger.metrics.errors.load(std::sync::atomic::Ordering::SeqCst);
     let queue_size = logger.metrics.queue_size.load(std::sync::atomic::Ordering::SeqCst);
 
-    // Calculate throughput and latency
+    // Calculate throughput and read the end-to-end latency distribution
     let throughput = logs_processed as f64 / elapsed.as_secs_f64();
-    let average_latency = elapsed.as_secs_f64() / logs_processed as f64 * 1_000_000.0; // in microseconds
+    let latency = logger.metrics.delivery_latency();
+    let micros = |q: f64| latency.quantile(q).unwrap_or_default() * 1_000_000.0;
 
     println!("Benchmark Results:");
     println!("Total Logs Sent: {}", total_logs);
@@ -72,7 +73,12 @@ async fn main() {
     println!("Final Queue Size: {}", queue_size);
     println!("Elapsed Time: {:.2} seconds", elapsed.as_secs_f64());
     println!("Throughput: {:.2} logs/sec", throughput);
-    println!("Average Latency: {:.2} µs/log", average_latency);
+    println!(
+        "Latency p50/p95/p99: {:.2} / {:.2} / {:.2} µs",
+        micros(0.5),
+        micros(0.95),
+        micros(0.99)
+    );
 
     let metrics_logger = logger.metrics.clone();
     tokio::spawn(async move {
@@ -0,0 +1,46 @@
+/* C API of log-engine-v1, built with the `ffi` feature. See src/ffi.rs. */
+#ifndef LOGENGINE_H
+#define LOGENGINE_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define LOGENGINE_OK 0
+#define LOGENGINE_ERR_INVALID_ARGUMENT (-1)
+#define LOGENGINE_ERR_NOT_INITIALIZED (-2)
+#define LOGENGINE_ERR_ALREADY_INITIALIZED (-3)
+#define LOGENGINE_ERR_INIT_FAILED (-4)
+#define LOGENGINE_ERR_TIMEOUT (-5)
+#define LOGENGINE_ERR_PANIC (-6)
+
+/* Severities of the built-in levels; registered custom levels use their own. */
+#define LOGENGINE_TRACE 100
+#define LOGENGINE_DEBUG 200
+#define LOGENGINE_INFO 300
+#define LOGENGINE_WARN 400
+#define LOGENGINE_ERROR 500
+#define LOGENGINE_FATAL 600
+
+/* Starts the logger. config_path may be NULL for the default configuration; key is a
+ * key descriptor such as "env:LOG_KEY" or "file:/run/secrets/logkey". */
+int logengine_init(const char *config_path, const char *key);
+
+/* Queues a record. level is the severity of a built-in or registered custom level,
+ * otherwise LOGENGINE_ERR_INVALID_ARGUMENT is returned. json_metadata may be NULL or a
+ * JSON object. */
+int logengine_log(int level, const char *message, const char *json_metadata);
+
+/* Waits up to timeout_ms for every record logged so far to be written. */
+int logengine_flush(uint32_t timeout_ms);
+
+/* Writes the queued records and stops the logger. */
+int logengine_shutdown(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LOGENGINE_H */
@@ -0,0 +1,263 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+const BUILTIN_LEVELS: [&str; 6] = ["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"];
+
+/// Derives `log_engine_v1::events::LogEvent` for a struct.
+///
+/// Container attributes: `#[log(name = "...", level = "...")]`; the name defaults to the
+/// struct name and the level to INFO. Field attribute: `#[log(redact)]` masks the field's
+/// value in the logged metadata. Fields renamed with `#[serde(rename = "...")]` are
+/// redacted under their serialized name.
+#[proc_macro_derive(LogEvent, attributes(log))]
+pub fn derive_log_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let mut name = input.ident.to_string();
+    let mut level = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("log")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else if meta.path.is_ident("level") {
+                level = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `name` or `level`"))
+            }
+        })?;
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "LogEvent can only be derived for structs",
+            ))
+        }
+    };
+    let mut redacted = Vec::new();
+    if let Fields::Named(named) = fields {
+        for field in &named.named {
+            let mut redact = false;
+            let mut serialized = field.ident.as_ref().map(|i| i.to_string()).unwrap_or_default();
+            for attr in &field.attrs {
+                if attr.path().is_ident("log") {
+                    attr.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("redact") {
+                            redact = true;
+                            Ok(())
+                        } else {
+                            Err(meta.error("expected `redact`"))
+                        }
+                    })?;
+                } else if attr.path().is_ident("serde") {
+                    // Only `rename` matters here; skip over every other serde option
+                    let _ = attr.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("rename") {
+                            serialized = meta.value()?.parse::<LitStr>()?.value();
+                        } else if meta.input.peek(syn::Token![=]) {
+                            meta.value()?.parse::<syn::Expr>()?;
+                        }
+                        Ok(())
+                    });
+                }
+            }
+            if redact {
+                redacted.push(serialized);
+            }
+        }
+    }
+
+    let level = match level {
+        None => quote!(::log_engine_v1::utils::LogLevel::INFO),
+        Some(lit) => {
+            let value = lit.value().to_uppercase();
+            if BUILTIN_LEVELS.contains(&value.as_str()) {
+                let ident = syn::Ident::new(&value, lit.span());
+                quote!(::log_engine_v1::utils::LogLevel::#ident)
+            } else {
+                // Custom levels are registered at runtime
+                quote!(::log_engine_v1::utils::LogLevel::from_str(#value)
+                    .unwrap_or(::log_engine_v1::utils::LogLevel::INFO))
+            }
+        }
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::log_engine_v1::events::LogEvent for #ident #ty_generics #where_clause {
+            fn event_name(&self) -> &'static str {
+                #name
+            }
+
+            fn level(&self) -> ::log_engine_v1::utils::LogLevel {
+                #level
+            }
+
+            fn redacted_fields(&self) -> &'static [&'static str] {
+                &[#(#redacted),*]
+            }
+        }
+    })
+}
+
+/// Logs entry to and exit from a function through a Logger: a record when it is
+/// called, one with its duration when it returns, and one at ERROR when a function
+/// returning a `Result` returns an `Err`. Works on `async fn` too.
+///
+/// Options, all optional: `#[log_instrument(logger = self.logger, level = "INFO",
+/// name = "...", fields(user_id, order))]`. The logger defaults to the global logger,
+/// the level to DEBUG and the name to the function's. Arguments are only recorded when
+/// listed in `fields(...)`, as serialized metadata.
+#[proc_macro_attribute]
+pub fn log_instrument(args: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as syn::ItemFn);
+    let mut options = InstrumentOptions::default();
+    let parser = syn::meta::parser(|meta| options.parse(meta));
+    parse_macro_input!(args with parser);
+    instrument(options, item).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+#[derive(Default)]
+struct InstrumentOptions {
+    logger: Option<syn::Expr>,
+    level: Option<LitStr>,
+    name: Option<LitStr>,
+    fields: Vec<syn::Ident>,
+}
+
+impl InstrumentOptions {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("logger") {
+            self.logger = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("level") {
+            self.level = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("fields") {
+            meta.parse_nested_meta(|field| {
+                let ident = field
+                    .path
+                    .get_ident()
+                    .ok_or_else(|| field.error("expected an argument name"))?;
+                self.fields.push(ident.clone());
+                Ok(())
+            })?;
+        } else {
+            return Err(meta.error("expected `logger`, `level`, `name` or `fields`"));
+        }
+        Ok(())
+    }
+}
+
+fn instrument(options: InstrumentOptions, item: syn::ItemFn) -> syn::Result<TokenStream2> {
+    let syn::ItemFn { attrs, vis, sig, block } = item;
+    let name = options.name.map(|n| n.value()).unwrap_or_else(|| sig.ident.to_string());
+    let level = match options.level {
+        None => quote!(::log_engine_v1::utils::LogLevel::DEBUG),
+        Some(lit) => {
+            let value = lit.value().to_uppercase();
+            if !BUILTIN_LEVELS.contains(&value.as_str()) {
+                return Err(syn::Error::new(lit.span(), "expected a built-in level"));
+            }
+            let ident = syn::Ident::new(&value, lit.span());
+            quote!(::log_engine_v1::utils::LogLevel::#ident)
+        }
+    };
+    let logger = match options.logger {
+        Some(expr) => quote!(::std::option::Option::Some({
+            let logger: &::log_engine_v1::logger::Logger = &#expr;
+            logger
+        })),
+        None => quote!(::log_engine_v1::registry::global_ref().map(|logger| &**logger)),
+    };
+    for field in &options.fields {
+        let is_argument = sig.inputs.iter().any(|input| match input {
+            syn::FnArg::Typed(typed) => matches!(&*typed.pat, syn::Pat::Ident(pat) if pat.ident == *field),
+            syn::FnArg::Receiver(_) => false,
+        });
+        if !is_argument {
+            return Err(syn::Error::new(field.span(), "not an argument of this function"));
+        }
+    }
+    let field_names = options.fields.iter().map(|field| field.to_string());
+    let field_values = &options.fields;
+
+    let output = match &sig.output {
+        syn::ReturnType::Default => quote!(()),
+        syn::ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    let returns_result = match &sig.output {
+        syn::ReturnType::Type(_, ty) => match &**ty {
+            syn::Type::Path(path) => path.path.segments.last().is_some_and(|s| s.ident == "Result"),
+            _ => false,
+        },
+        syn::ReturnType::Default => false,
+    };
+    // A `Result` is caught in a closure or async block so `return` and `?` still reach the
+    // error check; other bodies are inlined and their exit logged when the timer drops
+    let body = match (returns_result, sig.asyncness.is_some()) {
+        (false, _) => quote!(#block),
+        (true, true) => quote!(async #block.await),
+        (true, false) => quote!((|| -> #output #block)()),
+    };
+    let on_error = if returns_result {
+        quote! {
+            if let (::std::option::Option::Some(logger), ::std::result::Result::Err(error)) =
+                (__instrument_logger, &__instrument_result)
+            {
+                logger.log_at(
+                    ::log_engine_v1::utils::LogLevel::ERROR,
+                    &::std::format!("{} failed: {}", #name, error),
+                    ::std::option::Option::Some(::serde_json::json!({
+                        "function": #name,
+                        "error": error.to_string(),
+                    })),
+                    ::std::option::Option::Some(__instrument_location),
+                );
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis #sig {
+            let __instrument_logger: ::std::option::Option<&::log_engine_v1::logger::Logger> = #logger;
+            let __instrument_location = ::log_engine_v1::source_location!();
+            let __instrument_enabled = __instrument_logger.filter(|logger| logger.enabled(#level));
+            let __instrument_timer = __instrument_enabled.map(|__instrument_log| {
+                let mut __instrument_fields = ::serde_json::Map::new();
+                __instrument_fields.insert("function".to_string(), ::serde_json::json!(#name));
+                #(__instrument_fields.insert(#field_names.to_string(), ::serde_json::json!(&#field_values));)*
+                let __instrument_fields = ::serde_json::Value::Object(__instrument_fields);
+                __instrument_log.log_at(
+                    #level,
+                    &::std::format!("{} started", #name),
+                    ::std::option::Option::Some(__instrument_fields.clone()),
+                    ::std::option::Option::Some(__instrument_location),
+                );
+                __instrument_log
+                    .time(#name)
+                    .level(#level)
+                    .with_metadata(__instrument_fields)
+                    .with_location(__instrument_location)
+            });
+            #[allow(clippy::redundant_closure_call)]
+            let __instrument_result: #output = #body;
+            #on_error
+            ::std::mem::drop(__instrument_timer);
+            __instrument_result
+        }
+    })
+}
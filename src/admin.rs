@@ -0,0 +1,257 @@
+use crate::config::{ConfigError, ConfigurationManager, HandlerConfig};
+use crate::metrics::{respond_busy, write_response};
+use serde::Deserialize;
+use serde_json::json;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::{watch, Semaphore};
+use tokio::task::JoinHandle;
+
+const JSON: &str = "application/json";
+
+/// Longest request head read before the request is rejected.
+const MAX_REQUEST_HEAD: u64 = 8 * 1024;
+
+/// Largest request body accepted; longer ones are answered with status 413.
+const MAX_BODY: u64 = 64 * 1024;
+
+#[derive(Error, Debug)]
+pub enum AdminError {
+    #[error("Failed to bind to address: {0}")]
+    BindError(String),
+    #[error("IO error: {0}")]
+    IoError(String),
+}
+
+/// HTTP endpoint reconfiguring a live Logger through its [`ConfigurationManager`].
+///
+/// Every request must carry the token as `Authorization: Bearer <token>`, and is
+/// answered with the resulting [`ConfigurationManager::effective_config`]:
+///
+/// - `PUT /admin/level` with `{"level": "DEBUG"}` sets the global level, or with
+///   `{"level": "TRACE", "module": "my_app::db"}` the level of a module,
+/// - `POST /admin/handlers` with a handler entry as written in the configuration, e.g.
+///   `{"type_": "file", "config": {"file_path": "logs/debug.log"}}`, adds a handler,
+/// - `POST /admin/reload` reads the configuration file or remote source again,
+///   discarding the changes above.
+///
+/// Invalid changes are rejected with status 400 and leave the configuration untouched.
+/// As with the [`MetricsServer`](crate::metrics::MetricsServer), connections beyond the
+/// limit are answered with 503 and a client that does not send its request within the
+/// read timeout is disconnected. The server speaks plain HTTP, so bind it to a loopback
+/// or otherwise trusted address.
+///
+/// ```no_run
+/// # async fn run(logger: std::sync::Arc<log_engine_v1::logger::Logger>) {
+/// use log_engine_v1::admin::AdminServer;
+///
+/// let admin = AdminServer::new("127.0.0.1:9101", std::env::var("LOG_ADMIN_TOKEN").unwrap())
+///     .spawn(logger.config_manager())
+///     .await
+///     .unwrap();
+/// // ...
+/// admin.shutdown().await;
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct AdminServer {
+    addr: String,
+    token: Arc<str>,
+    max_connections: usize,
+    read_timeout: Duration,
+}
+
+/// A running [`AdminServer`], stopped with [`shutdown`](Self::shutdown).
+pub struct AdminServerHandle {
+    local_addr: SocketAddr,
+    stop: watch::Sender<bool>,
+    task: JoinHandle<Result<(), AdminError>>,
+}
+
+#[derive(Deserialize)]
+struct LevelChange {
+    level: String,
+    module: Option<String>,
+}
+
+impl AdminServer {
+    /// Creates a server listening on `addr` and accepting requests bearing `token`, with
+    /// at most 16 concurrent connections and a 5 second read timeout.
+    pub fn new(addr: impl Into<String>, token: impl Into<String>) -> Self {
+        AdminServer {
+            addr: addr.into(),
+            token: token.into().into(),
+            max_connections: 16,
+            read_timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Sets how many connections are served at once.
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections.max(1);
+        self
+    }
+
+    /// Sets how long a client may take to send its request.
+    pub fn read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
+    /// Binds the listener and serves requests against `config` in a background task.
+    pub async fn spawn(self, config: Arc<ConfigurationManager>) -> Result<AdminServerHandle, AdminError> {
+        let listener = TcpListener::bind(&self.addr)
+            .await
+            .map_err(|e| AdminError::BindError(e.to_string()))?;
+        let local_addr = listener.local_addr().map_err(|e| AdminError::IoError(e.to_string()))?;
+        let (stop, mut stopped) = watch::channel(false);
+        let task = tokio::spawn(async move {
+            let connections = Arc::new(Semaphore::new(self.max_connections));
+            loop {
+                let (socket, _) = tokio::select! {
+                    _ = stopped.wait_for(|stopped| *stopped) => return Ok(()),
+                    accepted = listener.accept() => accepted.map_err(|e| AdminError::IoError(e.to_string()))?,
+                };
+                let Ok(permit) = connections.clone().try_acquire_owned() else {
+                    tokio::spawn(respond_busy(socket));
+                    continue;
+                };
+                let server = self.clone();
+                let config = config.clone();
+                tokio::spawn(async move {
+                    server.handle_connection(socket, &config).await;
+                    drop(permit);
+                });
+            }
+        });
+        Ok(AdminServerHandle { local_addr, stop, task })
+    }
+
+    async fn handle_connection<S: AsyncRead + AsyncWrite + Unpin>(&self, mut stream: S, config: &ConfigurationManager) {
+        let read = {
+            let reader = BufReader::new((&mut stream).take(MAX_REQUEST_HEAD + MAX_BODY));
+            tokio::time::timeout(self.read_timeout, read_request(reader)).await
+        };
+        let (status, body) = match read {
+            Ok(Ok(request)) if !self.authorized(request.authorization.as_deref()) => {
+                ("401 Unauthorized", json!({ "error": "Missing or invalid token" }))
+            }
+            Ok(Ok(request)) => route(config, &request).await,
+            Ok(Err(RequestError::TooLarge)) => ("413 Payload Too Large", json!({ "error": "Request body too large" })),
+            Ok(Err(RequestError::Malformed)) => ("400 Bad Request", json!({ "error": "Bad request" })),
+            // Timed out: nothing useful can be sent back
+            Err(_) => return,
+        };
+        write_response(&mut stream, status, JSON, &body.to_string()).await;
+    }
+
+    /// Compares the presented token in constant time.
+    fn authorized(&self, authorization: Option<&str>) -> bool {
+        let Some(token) = authorization.and_then(|value| value.strip_prefix("Bearer ")) else {
+            return false;
+        };
+        let expected = self.token.as_bytes();
+        token.len() == expected.len()
+            && !expected.is_empty()
+            && token.bytes().zip(expected).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+    }
+}
+
+impl AdminServerHandle {
+    /// Returns the address the server is listening on, e.g. to find a port picked by the OS.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops accepting connections and waits for the accept loop to exit.
+    pub async fn shutdown(self) -> Result<(), AdminError> {
+        let _ = self.stop.send(true);
+        self.task.await.map_err(|e| AdminError::IoError(e.to_string()))?
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+enum RequestError {
+    Malformed,
+    /// The `Content-Length` exceeds [`MAX_BODY`].
+    TooLarge,
+}
+
+/// Reads the request line, the headers and a `Content-Length` body, rejecting a body
+/// longer than [`MAX_BODY`] before reading it.
+async fn read_request<R: AsyncRead + Unpin>(mut reader: BufReader<R>) -> Result<Request, RequestError> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.map_err(|_| RequestError::Malformed)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().ok_or(RequestError::Malformed)?.to_string();
+    let target = parts.next().ok_or(RequestError::Malformed)?;
+    let path = target.split('?').next().unwrap_or(target).to_string();
+
+    let (mut authorization, mut length) = (None, 0);
+    loop {
+        line.clear();
+        reader.read_line(&mut line).await.map_err(|_| RequestError::Malformed)?;
+        if !line.ends_with('\n') {
+            return Err(RequestError::Malformed);
+        }
+        let Some((name, value)) = line.trim_end().split_once(':') else {
+            break;
+        };
+        if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.trim().to_string());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            length = value.trim().parse::<u64>().map_err(|_| RequestError::Malformed)?;
+            if length > MAX_BODY {
+                return Err(RequestError::TooLarge);
+            }
+        }
+    }
+    let mut body = Vec::with_capacity(length as usize);
+    (&mut reader)
+        .take(MAX_BODY.min(length))
+        .read_to_end(&mut body)
+        .await
+        .map_err(|_| RequestError::Malformed)?;
+    if body.len() as u64 != length {
+        return Err(RequestError::Malformed);
+    }
+    Ok(Request {
+        method,
+        path,
+        authorization,
+        body,
+    })
+}
+
+/// Applies a request, returning the status line and the body answering it.
+async fn route(config: &ConfigurationManager, request: &Request) -> (&'static str, serde_json::Value) {
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("PUT", "/admin/level") => match serde_json::from_slice::<LevelChange>(&request.body) {
+            Ok(change) => config.set_level(change.module.as_deref(), &change.level).await,
+            Err(e) => Err(ConfigError::LoadError(e.to_string())),
+        },
+        ("POST", "/admin/handlers") => match serde_json::from_slice::<HandlerConfig>(&request.body) {
+            Ok(handler) => config.add_handler(handler).await,
+            Err(e) => Err(ConfigError::LoadError(e.to_string())),
+        },
+        ("POST", "/admin/reload") => config.reload().await,
+        (_, "/admin/level" | "/admin/handlers" | "/admin/reload") => {
+            return ("405 Method Not Allowed", json!({ "error": "Method not allowed" }))
+        }
+        _ => return ("404 Not Found", json!({ "error": "Not found" })),
+    };
+    match result {
+        Ok(()) => ("200 OK", config.effective_config()),
+        Err(e) => ("400 Bad Request", json!({ "error": e.to_string() })),
+    }
+}
@@ -0,0 +1,84 @@
+use crate::formatters::{Formatter, JsonFormatter};
+use crate::handlers::LogHandler;
+use crate::logger::{LogMessage, LoggerError};
+use crate::security::SecurityManager;
+use crate::utils::LogLevel;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Append-only log for security-relevant events that must never be dropped.
+///
+/// Unlike [`Logger`](crate::logger::Logger), an `AuditLogger` writes each record to its
+/// own handler before returning, bypassing the queue and every filter, so nothing is
+/// sampled or rate limited and a failed write is reported to the caller. Records are
+/// JSON, MAC-chained, and numbered without gaps in the order they are written, so the
+/// file can be checked with [`SecurityManager::verify_chain`].
+pub struct AuditLogger {
+    handler: Arc<dyn LogHandler>,
+    security: SecurityManager,
+    formatter: JsonFormatter,
+    /// Sequence number of the last record; held while a record is written so that
+    /// numbering, chaining and writing happen in one order.
+    last: Mutex<u64>,
+}
+
+impl AuditLogger {
+    /// Creates an audit log writing to `handler`. Hash chaining is enabled on `security`,
+    /// which should not be shared with another log so the chain stays unbroken.
+    ///
+    /// Fails if `security` writes plaintext, since plaintext records carry no MAC to chain.
+    pub fn new(handler: Arc<dyn LogHandler>, security: SecurityManager) -> Result<Self, LoggerError> {
+        if security.is_plaintext() {
+            return Err(LoggerError::SecurityError(
+                "An audit log needs MACs, which plaintext records do not carry".to_string(),
+            ));
+        }
+        Ok(AuditLogger {
+            handler,
+            security: security.with_hash_chain(),
+            formatter: JsonFormatter::new(),
+            last: Mutex::new(0),
+        })
+    }
+
+    /// Records that `actor` performed `action` on `resource`, returning the record's
+    /// sequence number once it has been written.
+    ///
+    /// A record whose write fails still takes its place in the chain, so the gap shows up
+    /// when the chain is verified.
+    pub async fn record(
+        &self,
+        actor: &str,
+        action: &str,
+        resource: &str,
+        details: Option<Value>,
+    ) -> Result<u64, LoggerError> {
+        let mut metadata = json!({
+            "audit": { "actor": actor, "action": action, "resource": resource },
+        });
+        if let Some(details) = details {
+            metadata["details"] = details;
+        }
+        let mut log = LogMessage::new(LogLevel::INFO, &format!("{} {} {}", actor, action, resource), metadata);
+
+        let mut last = self.last.lock().await;
+        log.sequence = *last + 1;
+        let (message, metadata) = self
+            .security
+            .seal(&mut log)
+            .map_err(|e| LoggerError::SecurityError(e.to_string()))?;
+        *last = log.sequence;
+        let formatted = self.formatter.format_record(&log, &message, &metadata).await;
+        self.handler
+            .emit(&formatted)
+            .await
+            .map_err(|e| LoggerError::HandlerError(e.to_string()))?;
+        Ok(log.sequence)
+    }
+
+    /// Returns the security manager, e.g. to verify the written chain.
+    pub fn security(&self) -> &SecurityManager {
+        &self.security
+    }
+}
@@ -0,0 +1,22 @@
+//! `logctl`: inspect, decrypt and verify log files and their rotation archives. The
+//! commands are in [`log_engine_v1::cli`].
+
+use clap::Parser;
+use log_engine_v1::cli::{run, Cli};
+use std::io;
+use std::process::ExitCode;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse().command, &mut io::stdout().lock()).await {
+        Ok(code) => code,
+        // The reader of the output, e.g. `head`, has seen enough
+        Err(e) if e.downcast_ref::<io::Error>().is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) => {
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("logctl: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,339 @@
+//! The commands of `logctl`, which inspects, decrypts and verifies log files and their
+//! rotation archives, built with the `cli` feature:
+//!
+//! ```text
+//! logctl tail -n 20 -f logs/app.log --key env:LOG_KEY
+//! logctl decrypt logs/app.log --key file:/run/secrets/logkey
+//! logctl verify logs/app.log --key env:LOG_KEY --hash-chain
+//! logctl convert --to json logs/app.log
+//! logctl grep --level error --since 1h -e timeout logs/app.log --key env:LOG_KEY
+//! ```
+
+use crate::keys::KeySource;
+use crate::reader::{open_file, rotated_files, LogReader, LogRecord, ReaderError};
+use crate::security::SecurityManager;
+use crate::utils::LogLevel;
+use chrono::{DateTime, Duration, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use regex::Regex;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// The arguments of `logctl`.
+#[derive(Parser)]
+#[command(name = "logctl", about = "Inspect, decrypt and verify log-engine log files")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// A `logctl` command and its arguments.
+#[derive(Subcommand)]
+pub enum Command {
+    /// Print the last records, optionally following new ones
+    Tail {
+        /// Number of records to print
+        #[arg(short = 'n', long, default_value_t = 10)]
+        lines: usize,
+        /// Keep printing records as they are written
+        #[arg(short, long)]
+        follow: bool,
+        #[command(flatten)]
+        input: Input,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Print records with their messages decrypted
+    Decrypt {
+        #[command(flatten)]
+        input: Input,
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+    },
+    /// Check the MAC of every record, and the hash chain with --hash-chain
+    Verify {
+        #[command(flatten)]
+        input: Input,
+        /// The records were written with hash chaining enabled
+        #[arg(long)]
+        hash_chain: bool,
+    },
+    /// Rewrite records as JSON or text
+    Convert {
+        #[arg(long, value_enum)]
+        to: Format,
+        #[command(flatten)]
+        input: Input,
+    },
+    /// Print the records matching a level, time range and pattern
+    Grep {
+        /// Print records at this level or above
+        #[arg(long)]
+        level: Option<String>,
+        /// Print records logged since a time (RFC 3339) or for a duration such as 30m, 1h or 2d
+        #[arg(long)]
+        since: Option<String>,
+        /// Print records logged until a time or duration ago
+        #[arg(long)]
+        until: Option<String>,
+        /// Regular expression matched against the message and metadata
+        #[arg(short = 'e', long)]
+        pattern: Option<String>,
+        #[command(flatten)]
+        input: Input,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+}
+
+/// The log files a command reads and the key to decrypt them with.
+#[derive(Args)]
+pub struct Input {
+    /// Log files; the rotation archives of each are read first, oldest first
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+    /// Key descriptor such as env:LOG_KEY or file:/run/secrets/logkey, to decrypt records
+    #[arg(long)]
+    pub key: Option<String>,
+}
+
+/// Output format of the printed records.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Json,
+    Text,
+}
+
+/// Runs a command, writing its output to `out` and reporting skipped records on stderr.
+/// `verify` fails when a record was tampered with.
+pub async fn run(command: Command, out: &mut dyn Write) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    match command {
+        Command::Tail {
+            lines,
+            follow,
+            input,
+            format,
+        } => {
+            let security = security(&input, false).await?;
+            let mut last = VecDeque::with_capacity(lines);
+            for record in records(&input.files, None, None)? {
+                if last.len() == lines {
+                    last.pop_front();
+                }
+                last.push_back(record);
+            }
+            for record in last {
+                print_record(out, &record, security.as_ref(), format)?;
+            }
+            if follow {
+                let path = input.files.last().ok_or("no file to follow")?;
+                follow_file(out, path, security.as_ref(), format)?;
+            }
+        }
+        Command::Decrypt { input, format } => {
+            let security = security(&input, false).await?.ok_or("decrypt needs --key")?;
+            for record in records(&input.files, None, None)? {
+                print_record(out, &record, Some(&security), format)?;
+            }
+        }
+        Command::Verify { input, hash_chain } => {
+            let security = security(&input, hash_chain).await?.ok_or("verify needs --key")?;
+            return verify(out, &input.files, &security);
+        }
+        Command::Convert { to, input } => {
+            let security = security(&input, false).await?;
+            for record in records(&input.files, None, None)? {
+                print_record(out, &record, security.as_ref(), to)?;
+            }
+        }
+        Command::Grep {
+            level,
+            since,
+            until,
+            pattern,
+            input,
+            format,
+        } => {
+            let security = security(&input, false).await?;
+            let level = match level {
+                Some(level) => Some(LogLevel::from_str(&level).ok_or_else(|| format!("unknown level: {}", level))?),
+                None => None,
+            };
+            let since = since.as_deref().map(parse_time).transpose()?;
+            let until = until.as_deref().map(parse_time).transpose()?;
+            let pattern = pattern.as_deref().map(Regex::new).transpose()?;
+            for record in records(&input.files, since, until)? {
+                if level.is_some_and(|level| record.level < level) {
+                    continue;
+                }
+                let record = decrypted(&record, security.as_ref());
+                if let Some(pattern) = &pattern {
+                    if !pattern.is_match(&record.message) && !pattern.is_match(&record.metadata.to_string()) {
+                        continue;
+                    }
+                }
+                print_record(out, &record, None, format)?;
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Loads the key given with `--key`, if any.
+async fn security(input: &Input, hash_chain: bool) -> Result<Option<SecurityManager>, Box<dyn std::error::Error>> {
+    let Some(key) = &input.key else {
+        return Ok(None);
+    };
+    let security = SecurityManager::from_key_source(&KeySource::from(key.as_str()), None).await?;
+    Ok(Some(if hash_chain { security.with_hash_chain() } else { security }))
+}
+
+/// The files to read for each given file: its rotation archives, then the file itself.
+fn rotation_sets(files: &[PathBuf]) -> Result<Vec<PathBuf>, ReaderError> {
+    let mut all = Vec::new();
+    for file in files {
+        all.extend(rotated_files(file)?);
+        all.push(file.clone());
+    }
+    Ok(all)
+}
+
+/// Reads the records of `files`, reporting malformed lines on stderr.
+fn records(
+    files: &[PathBuf],
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> Result<impl Iterator<Item = LogRecord>, Box<dyn std::error::Error>> {
+    let mut reader = LogReader::open_all(rotation_sets(files)?)?;
+    if let Some(since) = since {
+        reader = reader.since(since);
+    }
+    if let Some(until) = until {
+        reader = reader.until(until);
+    }
+    Ok(reader.filter_map(|record| match record {
+        Ok(record) => Some(record),
+        Err(e) => {
+            eprintln!("logctl: skipping {}", e);
+            None
+        }
+    }))
+}
+
+/// Returns the record decrypted, or as it is when it cannot be.
+fn decrypted(record: &LogRecord, security: Option<&SecurityManager>) -> LogRecord {
+    match security.map(|security| record.decrypt(security)) {
+        Some(Ok(record)) => record,
+        Some(Err(e)) => {
+            eprintln!("logctl: cannot decrypt record at {}: {}", record.timestamp, e);
+            record.clone()
+        }
+        None => record.clone(),
+    }
+}
+
+fn print_record(
+    out: &mut dyn Write,
+    record: &LogRecord,
+    security: Option<&SecurityManager>,
+    format: Format,
+) -> io::Result<()> {
+    let record = decrypted(record, security);
+    match format {
+        Format::Json => writeln!(out, "{}", serde_json::to_string(&record)?),
+        Format::Text => writeln!(
+            out,
+            "{} [{}] - {} - {}",
+            record.timestamp, record.level, record.message, record.metadata
+        ),
+    }
+}
+
+/// Verifies the files of each rotation set as one stream, so hash chains continue across
+/// archives, and reports tampered records by file and line.
+fn verify(
+    out: &mut dyn Write,
+    files: &[PathBuf],
+    security: &SecurityManager,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let mut intact = true;
+    for file in files {
+        let mut set = rotated_files(file)?;
+        set.push(file.clone());
+        let mut stream: Box<dyn BufRead> = Box::new(io::empty());
+        let mut offsets = Vec::new();
+        let mut lines = 0;
+        for path in &set {
+            offsets.push((lines, path));
+            lines += open_file(path)?.lines().count();
+            stream = Box::new(stream.chain(open_file(path)?));
+        }
+        let report = security.verify_reader(stream)?;
+        for line in &report.tampered {
+            let (offset, path) = offsets.iter().rev().find(|(offset, _)| offset < line).ok_or("line out of range")?;
+            writeln!(out, "{}:{}: tampered", path.display(), line - offset)?;
+        }
+        writeln!(out, "{}: {} records, {} tampered", file.display(), report.records, report.tampered.len())?;
+        intact &= report.is_intact();
+    }
+    Ok(if intact { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+/// Prints records appended to `path` until interrupted, starting over when it is rotated.
+fn follow_file(
+    out: &mut dyn Write,
+    path: &Path,
+    security: Option<&SecurityManager>,
+    format: Format,
+) -> io::Result<()> {
+    let mut position = std::fs::metadata(path)?.len();
+    let mut partial = String::new();
+    loop {
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        let Ok(metadata) = std::fs::metadata(path) else {
+            continue;
+        };
+        if metadata.len() < position {
+            position = 0;
+            partial.clear();
+        }
+        if metadata.len() == position {
+            continue;
+        }
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(position))?;
+        let mut appended = String::new();
+        position += BufReader::new(file).read_to_string(&mut appended)? as u64;
+        partial.push_str(&appended);
+        // Keep an incomplete last line for the next round
+        let complete = partial.rfind('\n').map_or(0, |end| end + 1);
+        for line in partial[..complete].lines().filter(|line| !line.trim().is_empty()) {
+            match LogRecord::parse(line) {
+                Ok(record) => print_record(out, &record, security, format)?,
+                Err(e) => eprintln!("logctl: skipping {}: {}", path.display(), e),
+            }
+        }
+        partial.drain(..complete);
+    }
+}
+
+/// Parses an RFC 3339 time, or a duration before now such as `90s`, `30m`, `1h` or `2d`.
+fn parse_time(text: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let invalid = || format!("invalid time or duration: {}", text);
+    let split = text.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let amount: i64 = text[..split].parse().map_err(|_| invalid())?;
+    let duration = match &text[split..] {
+        "s" => Duration::seconds(amount),
+        "m" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        _ => return Err(invalid()),
+    };
+    Ok(Utc::now() - duration)
+}
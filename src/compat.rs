@@ -0,0 +1,62 @@
+use crate::config::{FilterSpec, LogConfig};
+use crate::logger::{Logger, LoggerError};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Environment variable read by [`init_env_logger_style`], as by env_logger.
+pub const RUST_LOG_VAR: &str = "RUST_LOG";
+
+#[derive(Error, Debug)]
+pub enum CompatError {
+    #[error("Invalid RUST_LOG filter: {0}")]
+    InvalidFilter(String),
+    #[error("Logger error: {0}")]
+    LoggerError(#[from] LoggerError),
+    #[error("A global logger has already been initialized")]
+    AlreadyInitialized,
+}
+
+/// Sets up logging the way `env_logger::init()` does, as a first step in migrating from
+/// it: text records on the console, at the levels given by `RUST_LOG` (ERROR only when it
+/// is unset), e.g. `RUST_LOG=info,my_app::db=trace`.
+///
+/// The Logger is installed as the global logger for [`info!`](crate::info) and friends
+/// and, with the `log` feature, as the `log` facade's logger, so existing `log::info!`
+/// calls keep working. Records are written unencrypted. Returns the Logger, whose
+/// handlers and security can then be extended.
+pub async fn init_env_logger_style() -> Result<Arc<Logger>, CompatError> {
+    let logger = env_logger_style(std::env::var(RUST_LOG_VAR).ok().as_deref()).await?;
+    crate::init(logger.clone()).map_err(|_| CompatError::AlreadyInitialized)?;
+    #[cfg(feature = "log")]
+    crate::integrations::LogBridge::init(logger.clone()).map_err(|_| CompatError::AlreadyInitialized)?;
+    Ok(logger)
+}
+
+/// Builds the Logger of [`init_env_logger_style`] for a `RUST_LOG` filter, without
+/// installing it.
+pub(crate) async fn env_logger_style(filter: Option<&str>) -> Result<Arc<Logger>, CompatError> {
+    let config = env_logger_config(filter);
+    config.validate().map_err(|e| CompatError::InvalidFilter(e.to_string()))?;
+
+    // Records are not encrypted, but the Logger still needs a key
+    let mut key = vec![0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    let logger = Logger::builder(None, key).plaintext(true).build().await?;
+    logger.config_manager().update_config(config.clone()).await;
+    logger.apply_config(&config)?;
+    Ok(logger)
+}
+
+/// The default configuration with the levels of a `RUST_LOG` filter.
+pub(crate) fn env_logger_config(filter: Option<&str>) -> LogConfig {
+    let mut config = LogConfig {
+        level: "ERROR".to_string(),
+        ..LogConfig::default()
+    };
+    if let Some(filter) = filter {
+        FilterSpec::parse(filter).apply(&mut config);
+    }
+    config
+}
@@ -1,66 +1,606 @@
-use config::{Config as ConfigLoader, Environment, File};
-use serde::Deserialize;
+mod effective;
+mod env_filter;
+mod remote;
+mod schema;
+
+pub use env_filter::{FilterSpec, FILTER_VAR, LEVEL_OFF};
+pub use remote::{RemoteSource, REMOTE_FETCH_TIMEOUT};
+
+use crate::security::{Classification, CompliancePreset, MaskStrategy};
+use crate::utils::LogLevel;
+use config::{Config as ConfigLoader, Environment, File, FileFormat};
+#[cfg(not(target_arch = "wasm32"))]
+use notify::{RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
-use tokio::sync::RwLock;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::sync::mpsc;
+use tokio::sync::{watch, RwLock};
+
+/// How long a watched configuration file must stay unchanged before it is reloaded, so
+/// an editor's burst of writes results in a single reload.
+pub const CONFIG_DEBOUNCE: Duration = Duration::from_millis(250);
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct LogConfig {
     pub level: String,
+    /// Level overrides by module/target prefix, e.g. `my_app::network: DEBUG` or
+    /// `hyper: WARN`; the longest matching prefix wins over `level`. Both are replaced by
+    /// an env_logger-style [`FILTER_VAR`] filter when set.
     pub filters: Option<HashMap<String, String>>,
     pub handlers: Vec<HandlerConfig>,
-    pub formatter: Option<String>,
+    pub formatter: Option<FormatterConfig>,
     pub plugins: Option<Vec<PluginConfig>>,
+    pub global_filters: Option<Vec<FilterConfig>>,
+    pub enrichers: Option<Vec<EnricherConfig>>,
+    pub custom_levels: Option<HashMap<String, u16>>,
+    pub security: Option<SecurityConfig>,
+    /// Attaches a resolved backtrace to ERROR and FATAL records under the `backtrace`
+    /// metadata key. A boolean `backtrace` field on the call turns capture on or off for
+    /// that record, at any level.
+    pub backtrace: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// An entry of the `handlers:` section. `type_` selects the handler and the settings
+/// accepted in its `config:` block; unknown types and settings are rejected.
+///
+/// ```yaml
+/// handlers:
+///   - type_: "file"
+///     level: "WARN"
+///     config:
+///       file_path: "logs/app.log"
+///       max_size: 10485760
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(try_from = "RawHandlerConfig", into = "RawHandlerConfig")]
 pub struct HandlerConfig {
-    pub type_: String,
+    pub kind: HandlerKind,
     pub level: Option<String>,
+    /// Most sensitive classification the handler may receive, `internal` by default.
+    pub clearance: Option<Classification>,
+    pub filters: Option<Vec<FilterConfig>>,
+}
+
+impl HandlerConfig {
+    /// A handler of `kind` without a level, clearance or filters of its own.
+    pub fn new(kind: HandlerKind) -> Self {
+        HandlerConfig {
+            kind,
+            level: None,
+            clearance: None,
+            filters: None,
+        }
+    }
+}
+
+/// A handler entry as written, before its `config:` block is checked against `type_`.
+#[derive(Serialize, Deserialize)]
+struct RawHandlerConfig {
+    type_: String,
+    level: Option<String>,
+    clearance: Option<Classification>,
+    config: Option<serde_json::Value>,
+    filters: Option<Vec<FilterConfig>>,
+}
+
+impl From<HandlerConfig> for RawHandlerConfig {
+    fn from(handler: HandlerConfig) -> Self {
+        let config = match &handler.kind {
+            HandlerKind::Console { config } => serde_json::to_value(config),
+            HandlerKind::File { config } => serde_json::to_value(config),
+            HandlerKind::Remote { config } => serde_json::to_value(config),
+            HandlerKind::Memory { config } => serde_json::to_value(config),
+        };
+        RawHandlerConfig {
+            type_: handler.kind.type_name().to_string(),
+            level: handler.level,
+            clearance: handler.clearance,
+            config: config.ok(),
+            filters: handler.filters,
+        }
+    }
+}
+
+impl TryFrom<RawHandlerConfig> for HandlerConfig {
+    type Error = String;
+
+    /// Deserializes the settings according to `type_` so that errors name the offending
+    /// setting, e.g. `config.max_size: invalid type: string "big", expected u64`.
+    fn try_from(raw: RawHandlerConfig) -> Result<Self, String> {
+        fn settings<T>(config: Option<serde_json::Value>) -> Result<T, String>
+        where
+            T: serde::de::DeserializeOwned + Default,
+        {
+            match config {
+                Some(config) => serde_path_to_error::deserialize(config)
+                    .map_err(|e| format!("config.{}: {}", e.path(), e.inner())),
+                None => Ok(T::default()),
+            }
+        }
+        let kind = match raw.type_.as_str() {
+            "console" => HandlerKind::Console { config: settings(raw.config)? },
+            "file" => HandlerKind::File { config: settings(raw.config)? },
+            "remote" => HandlerKind::Remote { config: settings(raw.config)? },
+            "memory" => HandlerKind::Memory { config: settings(raw.config)? },
+            other => {
+                return Err(format!(
+                    "type_: unknown handler type `{}`, expected one of `console`, `file`, `remote`, `memory`",
+                    other
+                ))
+            }
+        };
+        Ok(HandlerConfig {
+            kind,
+            level: raw.level,
+            clearance: raw.clearance,
+            filters: raw.filters,
+        })
+    }
+}
+
+/// The handler types that can be declared in the configuration, tagged by `type_`, with
+/// their settings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HandlerKind {
+    Console { config: ConsoleHandlerConfig },
+    File { config: FileHandlerConfig },
+    Remote { config: RemoteHandlerConfig },
+    Memory { config: MemoryHandlerConfig },
+}
+
+impl HandlerKind {
+    /// The `type_` naming this kind in the configuration.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            HandlerKind::Console { .. } => "console",
+            HandlerKind::File { .. } => "file",
+            HandlerKind::Remote { .. } => "remote",
+            HandlerKind::Memory { .. } => "memory",
+        }
+    }
+}
+
+/// Settings of a `console` handler; it has none.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ConsoleHandlerConfig {}
+
+/// Settings of a `file` handler.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileHandlerConfig {
+    /// Defaults to `logs/app.log`.
+    pub file_path: String,
+    /// Size in bytes at which the file is rotated; defaults to 10 MiB.
+    pub max_size: u64,
+}
+
+impl Default for FileHandlerConfig {
+    fn default() -> Self {
+        FileHandlerConfig {
+            file_path: "logs/app.log".to_string(),
+            max_size: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// Settings of a `remote` handler.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RemoteHandlerConfig {
+    /// Defaults to `127.0.0.1`.
+    pub address: String,
+    /// Defaults to 9000.
+    pub port: u16,
+    /// Attempts per record; the handler's default when not given.
+    pub retries: Option<usize>,
+}
+
+impl Default for RemoteHandlerConfig {
+    fn default() -> Self {
+        RemoteHandlerConfig {
+            address: "127.0.0.1".to_string(),
+            port: 9000,
+            retries: None,
+        }
+    }
+}
+
+/// Settings of a `memory` handler.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct MemoryHandlerConfig {
+    /// Records kept; defaults to 1000.
+    pub capacity: usize,
+}
+
+impl Default for MemoryHandlerConfig {
+    fn default() -> Self {
+        MemoryHandlerConfig { capacity: 1000 }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FilterConfig {
+    pub type_: String,
     pub config: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EnricherConfig {
+    pub type_: String,
+    pub config: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct PluginConfig {
     pub name: String,
     pub config: Option<serde_json::Value>,
 }
 
+/// The `formatter:` entry, either the formatter's name or an object with its options:
+///
+/// ```yaml
+/// formatter: json
+/// # or
+/// formatter:
+///   type: text
+///   pattern: "{timestamp} [{level}] {module}: {message}"
+///   timestamp: "%H:%M:%S%.3f"
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(from = "RawFormatterConfig")]
+pub struct FormatterConfig {
+    /// `text` or `json`.
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// [`TextFormatter`](crate::formatters::TextFormatter) pattern; the default layout
+    /// when not given.
+    pub pattern: Option<String>,
+    /// strftime-style format of the timestamp, e.g. `%Y-%m-%d %H:%M:%S`; RFC 3339 when
+    /// not given.
+    pub timestamp: Option<String>,
+}
+
+impl From<&str> for FormatterConfig {
+    /// A formatter with its default options.
+    fn from(type_: &str) -> Self {
+        FormatterConfig {
+            type_: type_.to_string(),
+            pattern: None,
+            timestamp: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawFormatterConfig {
+    Name(String),
+    Options {
+        #[serde(rename = "type", alias = "type_")]
+        type_: String,
+        pattern: Option<String>,
+        timestamp: Option<String>,
+    },
+}
+
+impl From<RawFormatterConfig> for FormatterConfig {
+    fn from(raw: RawFormatterConfig) -> Self {
+        match raw {
+            RawFormatterConfig::Name(type_) => FormatterConfig::from(type_.as_str()),
+            RawFormatterConfig::Options {
+                type_,
+                pattern,
+                timestamp,
+            } => FormatterConfig {
+                type_,
+                pattern,
+                timestamp,
+            },
+        }
+    }
+}
+
+/// The `security:` section, applied when the Logger is built.
+///
+/// ```yaml
+/// security:
+///   preset: pci                                    # pci, hipaa or gdpr
+///   patterns: ["(?i)[a-z0-9._%+-]+@[a-z0-9.-]+"]   # replaces the default email rule
+///   detectors: ["credit_card", "aws_access_key"]
+///   rules:
+///     - pattern: "user-\\d+"
+///       mask: pseudonymize
+///     - pattern: "TICKET-\\d+"
+///       replacement: "<ticket>"
+///   encrypted_fields: ["user.email", "payment.card"]   # leaves the message readable
+///   # encryption: false                            # plaintext mode, e.g. for local development
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SecurityConfig {
+    /// Compliance preset applied before the settings below.
+    pub preset: Option<CompliancePreset>,
+    /// Patterns redacted in messages, replacing the default rules when given.
+    pub patterns: Option<Vec<String>>,
+    /// Metadata keys whose values are redacted; replaces the default deny-list.
+    pub denied_keys: Option<Vec<String>>,
+    /// Built-in detectors to enable by name, e.g. `credit_card` or `iban`.
+    pub detectors: Option<Vec<String>>,
+    /// Extra sanitization rules applied to messages.
+    pub rules: Option<Vec<SanitizationRuleConfig>>,
+    /// Dotted metadata paths to encrypt instead of the whole message.
+    pub encrypted_fields: Option<Vec<String>>,
+    /// `false` writes sanitized records in plaintext, without MACs. Defaults to `true`.
+    pub encryption: Option<bool>,
+}
+
+/// A sanitization pattern and how its matches are masked (`redact` by default).
+/// A `replacement` string takes precedence over `mask`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SanitizationRuleConfig {
+    pub pattern: String,
+    pub mask: Option<MaskStrategy>,
+    pub replacement: Option<String>,
+}
+
+impl Default for LogConfig {
+    /// Text records at INFO and above on the console.
+    fn default() -> Self {
+        LogConfig {
+            level: "INFO".to_string(),
+            filters: None,
+            handlers: vec![HandlerConfig::new(HandlerKind::Console {
+                config: ConsoleHandlerConfig::default(),
+            })],
+            formatter: Some(FormatterConfig::from("text")),
+            plugins: None,
+            global_filters: None,
+            enrichers: None,
+            custom_levels: None,
+            security: None,
+            backtrace: None,
+        }
+    }
+}
+
+impl LogConfig {
+    /// Checks what deserialization cannot, returning every problem found: that levels name
+    /// a built-in level or one declared in `custom_levels`, that filters, enrichers and
+    /// detectors exist and are well-formed, that patterns are valid regular expressions,
+    /// and that handler settings are in range.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut issues = Vec::new();
+        let mut issue = |path: String, message: String| issues.push(ConfigIssue { path, message });
+
+        let known = |level: &str| {
+            LogLevel::from_str(level).is_some()
+                || level.eq_ignore_ascii_case(LEVEL_OFF)
+                || self
+                    .custom_levels
+                    .iter()
+                    .flatten()
+                    .any(|(name, _)| name.eq_ignore_ascii_case(level))
+        };
+        let mut check_level = |path: String, level: &str| {
+            if !known(level) {
+                issue(path, format!("unknown level `{}`", level));
+            }
+        };
+        check_level("level".into(), &self.level);
+        for (prefix, level) in self.filters.iter().flatten() {
+            check_level(format!("filters.{}", prefix), level);
+        }
+        for (index, handler) in self.handlers.iter().enumerate() {
+            if let Some(level) = &handler.level {
+                check_level(format!("handlers[{}].level", index), level);
+            }
+        }
+
+        for (index, handler) in self.handlers.iter().enumerate() {
+            let path = format!("handlers[{}].config", index);
+            match &handler.kind {
+                HandlerKind::File { config } if config.max_size == 0 => {
+                    issue(format!("{}.max_size", path), "must be greater than 0".into())
+                }
+                HandlerKind::Remote { config } if config.port == 0 => {
+                    issue(format!("{}.port", path), "must be between 1 and 65535".into())
+                }
+                HandlerKind::Remote { config } if config.address.is_empty() => {
+                    issue(format!("{}.address", path), "must not be empty".into())
+                }
+                HandlerKind::Memory { config } if config.capacity == 0 => {
+                    issue(format!("{}.capacity", path), "must be greater than 0".into())
+                }
+                _ => {}
+            }
+            for (filter_index, filter) in handler.filters.iter().flatten().enumerate() {
+                if let Err(e) = crate::filters::build_filter(filter) {
+                    issue(format!("handlers[{}].filters[{}]", index, filter_index), e.to_string());
+                }
+            }
+        }
+        if let Some(formatter) = &self.formatter {
+            if !matches!(formatter.type_.as_str(), "text" | "json") {
+                let message = format!("unknown formatter `{}`, expected `text` or `json`", formatter.type_);
+                issue("formatter.type".into(), message);
+            }
+            if formatter.pattern.is_some() && formatter.type_ != "text" {
+                issue("formatter.pattern".into(), "only applies to the `text` formatter".into());
+            }
+            if let Some(timestamp) = &formatter.timestamp {
+                if let Err(e) = chrono::format::StrftimeItems::new(timestamp).parse() {
+                    issue("formatter.timestamp".into(), format!("invalid timestamp format: {}", e));
+                }
+            }
+        }
+        for (index, filter) in self.global_filters.iter().flatten().enumerate() {
+            if let Err(e) = crate::filters::build_filter(filter) {
+                issue(format!("global_filters[{}]", index), e.to_string());
+            }
+        }
+        for (index, enricher) in self.enrichers.iter().flatten().enumerate() {
+            if let Err(e) = crate::enrichers::build_enricher(enricher) {
+                issue(format!("enrichers[{}]", index), e.to_string());
+            }
+        }
+
+        if let Some(security) = &self.security {
+            for (index, pattern) in security.patterns.iter().flatten().enumerate() {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    issue(format!("security.patterns[{}]", index), e.to_string());
+                }
+            }
+            for (index, rule) in security.rules.iter().flatten().enumerate() {
+                if let Err(e) = regex::Regex::new(&rule.pattern) {
+                    issue(format!("security.rules[{}].pattern", index), e.to_string());
+                }
+            }
+            for (index, name) in security.detectors.iter().flatten().enumerate() {
+                if let Err(e) = crate::security::detector(name, None) {
+                    issue(format!("security.detectors[{}]", index), e.to_string());
+                }
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::ValidationError(issues))
+        }
+    }
+}
+
+/// A problem found by [`LogConfig::validate`], with the path of the offending setting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    /// Dotted path such as `handlers[2].config.port`.
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+fn join_issues(issues: &[ConfigIssue]) -> String {
+    issues.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
 #[derive(Error, Debug)]
 pub enum ConfigError {
     #[error("Configuration load error: {0}")]
     LoadError(String),
+    #[error("Invalid configuration: {}", join_issues(.0))]
+    ValidationError(Vec<ConfigIssue>),
+    #[error("Configuration watch error: {0}")]
+    WatchError(String),
+    #[error("Remote configuration error: {0}")]
+    RemoteError(String),
 }
 
 #[derive(Clone)]
 pub struct ConfigurationManager {
     config: Arc<RwLock<LogConfig>>,
+    updates: Arc<watch::Sender<LogConfig>>,
+    /// Where [`reload`](Self::reload) reads the configuration from.
+    origin: Option<ConfigOrigin>,
+}
+
+#[derive(Clone)]
+enum ConfigOrigin {
+    File(String),
+    Remote(RemoteSource),
 }
 
 impl ConfigurationManager {
     /// Initializes the ConfigurationManager with a configuration file.
     pub async fn new(config_file: &str) -> Result<Self, ConfigError> {
+        let mut manager = Self::with_config(Self::load(config_file)?);
+        manager.origin = Some(ConfigOrigin::File(config_file.to_string()));
+        Ok(manager)
+    }
+
+    /// Initializes the ConfigurationManager from YAML or JSON text, with the same
+    /// includes, profiles and `LOGENGINE_*` environment overrides as a file. Includes are
+    /// resolved relative to the working directory.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(source: &str) -> Result<Self, ConfigError> {
+        Ok(Self::with_config(Self::parse(source)?))
+    }
+
+    /// Parses and validates YAML or JSON text.
+    fn parse(source: &str) -> Result<LogConfig, ConfigError> {
+        let format = if source.trim_start().starts_with('{') {
+            FileFormat::Json
+        } else {
+            FileFormat::Yaml
+        };
+        let value = read_value(File::from_str(source, format))?;
+        Self::deserialize(value, Path::new("."), &mut Vec::new())
+    }
+
+    /// Initializes the ConfigurationManager with [`LogConfig::default`].
+    pub fn with_defaults() -> Self {
+        Self::with_config(LogConfig::default())
+    }
+
+    fn with_config(config: LogConfig) -> Self {
+        let (updates, _) = watch::channel(config.clone());
+        ConfigurationManager {
+            config: Arc::new(RwLock::new(config)),
+            updates: Arc::new(updates),
+            origin: None,
+        }
+    }
+
+    /// Reads, parses and validates a configuration file, with its includes, the selected
+    /// profile and `LOGENGINE_*` environment overrides.
+    fn load(config_file: &str) -> Result<LogConfig, ConfigError> {
         if !Path::new(config_file).exists() {
             return Err(ConfigError::LoadError(format!("Configuration file not found: {}", config_file)));
         }
 
-        let builder = ConfigLoader::builder()
-            .add_source(File::with_name(config_file))
-            .add_source(Environment::with_prefix("LOGENGINE"));
-
-        let settings = builder
-            .build()
-            .map_err(|e| ConfigError::LoadError(e.to_string()))?;
+        let value = read_value(File::with_name(config_file))?;
+        let directory = Path::new(config_file).parent().unwrap_or(Path::new("."));
+        let canonical = Path::new(config_file).canonicalize().unwrap_or_else(|_| PathBuf::from(config_file));
+        Self::deserialize(value, directory, &mut vec![canonical])
+    }
 
-        let config: LogConfig = settings
-            .try_deserialize()
-            .map_err(|e| ConfigError::LoadError(format!("Failed to parse configuration: {}", e)))?;
+    /// Merges the includes, profile and environment overrides into a parsed file, then
+    /// deserializes and validates the result.
+    fn deserialize(
+        mut value: serde_json::Value,
+        directory: &Path,
+        including: &mut Vec<PathBuf>,
+    ) -> Result<LogConfig, ConfigError> {
+        resolve_includes(&mut value, directory, including)?;
+        apply_profile(&mut value, std::env::var(PROFILE_VAR).ok().as_deref())?;
+        merge(&mut value, read_value(Environment::with_prefix("LOGENGINE"))?);
+        if let serde_json::Value::Object(fields) = &mut value {
+            // Read from LOGENGINE_PROFILE and LOGENGINE_FILTER rather than settings
+            fields.remove("profile");
+            fields.remove("filter");
+        }
+        interpolate(&mut value, "")?;
 
-        Ok(ConfigurationManager {
-            config: Arc::new(RwLock::new(config)),
-        })
+        // Deserialize through serde_json to report the path of an invalid setting,
+        // e.g. `handlers[2].config.max_size`
+        let mut config: LogConfig = serde_path_to_error::deserialize(value).map_err(|e| {
+            ConfigError::LoadError(format!("Failed to parse configuration: {}: {}", e.path(), e.inner()))
+        })?;
+        if let Some(filter) = FilterSpec::from_env() {
+            filter.apply(&mut config);
+        }
+        config.validate()?;
+        Ok(config)
     }
 
     /// Retrieves the current configuration.
@@ -68,16 +608,313 @@ impl ConfigurationManager {
         self.config.read().await.clone()
     }
 
-    /// Updates the current configuration.
+    /// Updates the current configuration and notifies subscribers.
     pub async fn update_config(&self, new_config: LogConfig) {
         let mut cfg = self.config.write().await;
-        *cfg = new_config;
+        *cfg = new_config.clone();
+        self.updates.send_replace(new_config);
+    }
+
+    /// Sets the global level, or the level of the `module` prefix, and notifies
+    /// subscribers. The level is validated as in a configuration file.
+    pub async fn set_level(&self, module: Option<&str>, level: &str) -> Result<(), ConfigError> {
+        let mut config = self.get_config().await;
+        match module {
+            Some(module) => {
+                config
+                    .filters
+                    .get_or_insert_with(HashMap::new)
+                    .insert(module.to_string(), level.to_string());
+            }
+            None => config.level = level.to_string(),
+        }
+        config.validate()?;
+        Self::publish(&self.config, &self.updates, config).await;
+        Ok(())
+    }
+
+    /// Appends `handler` to the configured handlers and notifies subscribers, so a Logger
+    /// following this manager starts writing to it.
+    pub async fn add_handler(&self, handler: HandlerConfig) -> Result<(), ConfigError> {
+        let mut config = self.get_config().await;
+        config.handlers.push(handler);
+        config.validate()?;
+        Self::publish(&self.config, &self.updates, config).await;
+        Ok(())
+    }
+
+    /// Reads the configuration again from the file or remote source it was loaded from,
+    /// discarding changes made at runtime. The current configuration is kept if the new
+    /// one cannot be read or is invalid.
+    pub async fn reload(&self) -> Result<(), ConfigError> {
+        let config = match &self.origin {
+            Some(ConfigOrigin::File(config_file)) => Self::load(config_file)?,
+            Some(ConfigOrigin::Remote(source)) => Self::parse(&source.fetch().await?)?,
+            None => return Err(ConfigError::LoadError("No configuration source to reload from".into())),
+        };
+        Self::publish(&self.config, &self.updates, config).await;
+        Ok(())
+    }
+
+    /// Replaces the configuration with `new_config` and notifies subscribers, unless it is
+    /// unchanged.
+    async fn publish(config: &RwLock<LogConfig>, updates: &watch::Sender<LogConfig>, new_config: LogConfig) {
+        let mut current = config.write().await;
+        if *current != new_config {
+            *current = new_config.clone();
+            updates.send_replace(new_config);
+        }
     }
 
-    /// Watches the configuration file for changes and updates dynamically.
+    /// Subscribes to configuration updates.
+    pub fn subscribe(&self) -> watch::Receiver<LogConfig> {
+        self.updates.subscribe()
+    }
+
+    /// Watches the configuration file for changes, reloading it once writes have settled
+    /// for [`CONFIG_DEBOUNCE`]. A reloaded configuration replaces the current one and is
+    /// published to subscribers only if it parses and validates; otherwise the error is
+    /// reported and the current configuration kept.
+    ///
+    /// The file's directory is watched rather than the file, so editors that save by
+    /// replacing the file keep being followed. Watching stops once every clone of the
+    /// manager has been dropped.
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn watch_config(&self, config_file: &str) -> Result<(), ConfigError> {
-        // Implementation for watching the config file using tokio's file watcher or notify crate.
-        // Placeholder for brevity.
+        let path = PathBuf::from(config_file);
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| ConfigError::WatchError(format!("Not a file: {}", config_file)))?
+            .to_owned();
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let (events, mut changes) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            let relevant = event.kind.is_create() || event.kind.is_modify();
+            if relevant && event.paths.iter().any(|p| p.file_name() == Some(file_name.as_os_str())) {
+                let _ = events.send(());
+            }
+        })
+        .map_err(|e| ConfigError::WatchError(e.to_string()))?;
+        watcher
+            .watch(&directory, RecursiveMode::NonRecursive)
+            .map_err(|e| ConfigError::WatchError(format!("{}: {}", directory.display(), e)))?;
+
+        let config = Arc::downgrade(&self.config);
+        let updates = Arc::downgrade(&self.updates);
+        let config_file = config_file.to_string();
+        tokio::spawn(async move {
+            // Dropping the watcher stops it
+            let _watcher = watcher;
+            loop {
+                tokio::select! {
+                    change = changes.recv() => {
+                        if change.is_none() {
+                            break;
+                        }
+                    }
+                    _ = tokio::time::sleep(Duration::from_secs(1)) => {
+                        if updates.strong_count() == 0 {
+                            break;
+                        }
+                        continue;
+                    }
+                }
+                // Debounce: wait until no change has been seen for CONFIG_DEBOUNCE
+                while let Ok(Some(())) = tokio::time::timeout(CONFIG_DEBOUNCE, changes.recv()).await {}
+
+                let (Some(config), Some(updates)) = (config.upgrade(), updates.upgrade()) else {
+                    break;
+                };
+                match Self::load(&config_file) {
+                    Ok(new_config) => Self::publish(&config, &updates, new_config).await,
+                    Err(e) => eprintln!("Ignoring invalid configuration {}: {}", config_file, e),
+                }
+            }
+        });
         Ok(())
     }
+
+    /// Fails: there are no file system notifications to watch with on `wasm32`.
+    #[cfg(target_arch = "wasm32")]
+    pub async fn watch_config(&self, config_file: &str) -> Result<(), ConfigError> {
+        Err(ConfigError::WatchError(format!("Cannot watch {} on wasm32", config_file)))
+    }
+}
+
+/// Environment variable selecting the entry of `profiles:` merged over the configuration.
+pub const PROFILE_VAR: &str = "LOGENGINE_PROFILE";
+
+/// Reads a configuration source into a JSON value.
+fn read_value<S>(source: S) -> Result<serde_json::Value, ConfigError>
+where
+    S: config::Source + Send + Sync + 'static,
+{
+    ConfigLoader::builder()
+        .add_source(source)
+        .build()
+        .map_err(|e| ConfigError::LoadError(e.to_string()))?
+        .try_deserialize()
+        .map_err(|e| ConfigError::LoadError(format!("Failed to parse configuration: {}", e)))
+}
+
+/// Merges `overlay` into `base`: objects are merged key by key, any other value in
+/// `overlay` replaces the one in `base`.
+fn merge(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Replaces the `include:` directive, a path or list of paths relative to `directory`,
+/// with the contents of the named fragments. Later fragments override earlier ones and
+/// the including file overrides them all; fragments may include others in turn.
+fn resolve_includes(
+    value: &mut serde_json::Value,
+    directory: &Path,
+    including: &mut Vec<PathBuf>,
+) -> Result<(), ConfigError> {
+    let include = match value {
+        serde_json::Value::Object(fields) => fields.remove("include"),
+        _ => None,
+    };
+    let paths = match include {
+        None => return Ok(()),
+        Some(serde_json::Value::String(path)) => vec![path],
+        Some(serde_json::Value::Array(paths)) => paths
+            .into_iter()
+            .map(|path| match path {
+                serde_json::Value::String(path) => Ok(path),
+                other => Err(ConfigError::LoadError(format!("include: expected a path, found {}", other))),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(other) => {
+            return Err(ConfigError::LoadError(format!(
+                "include: expected a path or list of paths, found {}",
+                other
+            )))
+        }
+    };
+
+    let mut merged = serde_json::Value::Object(Default::default());
+    for path in paths {
+        let path = directory.join(path);
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| ConfigError::LoadError(format!("Cannot include {}: {}", path.display(), e)))?;
+        if including.contains(&canonical) {
+            return Err(ConfigError::LoadError(format!("Circular include: {}", path.display())));
+        }
+        let mut fragment = read_value(File::from(path.as_path()))?;
+        including.push(canonical);
+        resolve_includes(&mut fragment, path.parent().unwrap_or(Path::new(".")), including)?;
+        including.pop();
+        merge(&mut merged, fragment);
+    }
+    merge(&mut merged, value.take());
+    *value = merged;
+    Ok(())
+}
+
+/// Merges the profile `name`, selected by [`PROFILE_VAR`], from the `profiles:` section
+/// over the rest of the configuration, and removes the section.
+pub(crate) fn apply_profile(value: &mut serde_json::Value, name: Option<&str>) -> Result<(), ConfigError> {
+    let profiles = match value {
+        serde_json::Value::Object(fields) => fields.remove("profiles"),
+        _ => None,
+    };
+    let Some(name) = name else {
+        return Ok(());
+    };
+    match profiles.as_ref().and_then(|profiles| profiles.get(name)) {
+        Some(profile) => {
+            merge(value, profile.clone());
+            Ok(())
+        }
+        None => Err(ConfigError::LoadError(format!("Unknown profile: {}", name))),
+    }
+}
+
+/// Substitutes environment variables into every string value: `${VAR}` is replaced by the
+/// variable, which must be set, and `${VAR:-default}` by the variable or, when it is
+/// unset or empty, `default`. `$${` is kept as a literal `${`.
+///
+/// A value consisting of a single placeholder that expands to a number or boolean, such
+/// as `port: ${LOG_PORT:-9000}`, takes that type.
+fn interpolate(value: &mut serde_json::Value, path: &str) -> Result<(), ConfigError> {
+    use serde_json::Value;
+    match value {
+        Value::String(text) if text.contains('$') => {
+            let expanded = interpolate_str(text).map_err(|e| {
+                ConfigError::LoadError(format!("Failed to parse configuration: {}: {}", path, e))
+            })?;
+            let single = text.starts_with("${") && text.find('}') == Some(text.len() - 1);
+            *value = match serde_json::from_str(&expanded) {
+                Ok(typed @ (Value::Number(_) | Value::Bool(_))) if single => typed,
+                _ => Value::String(expanded),
+            };
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter_mut().enumerate() {
+                interpolate(value, &format!("{}[{}]", path, index))?;
+            }
+        }
+        Value::Object(fields) => {
+            for (key, value) in fields.iter_mut() {
+                let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                interpolate(value, &path)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn interpolate_str(text: &str) -> Result<String, String> {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        if let Some(escaped) = after.strip_prefix("${") {
+            expanded.push_str("${");
+            rest = escaped;
+        } else if let Some(placeholder) = after.strip_prefix('{') {
+            let end = placeholder
+                .find('}')
+                .ok_or_else(|| format!("unterminated placeholder in {:?}", text))?;
+            let (name, default) = match placeholder[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&placeholder[..end], None),
+            };
+            match (std::env::var(name), default) {
+                (Ok(value), _) if !value.is_empty() => expanded.push_str(&value),
+                (_, Some(default)) => expanded.push_str(default),
+                (Ok(value), None) => expanded.push_str(&value),
+                (Err(_), None) => return Err(format!("environment variable {} is not set", name)),
+            }
+            rest = &placeholder[end + 1..];
+        } else {
+            expanded.push('$');
+            rest = after;
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
 }
@@ -0,0 +1,41 @@
+use super::ConfigurationManager;
+use crate::security::{DEFAULT_DENIED_KEYS, REDACTED};
+use serde_json::Value;
+
+/// Key suffixes treated as secrets in addition to the denied keys, e.g. `db_password`
+/// or `signing_key`.
+const SECRET_SUFFIXES: &[&str] = &["_password", "_secret", "_token", "_key"];
+
+impl ConfigurationManager {
+    /// Returns the configuration in effect, after includes, the profile, environment
+    /// overrides and interpolation, as JSON for inspection. Values under keys that look
+    /// like secrets, the default and configured `denied_keys` or names ending in
+    /// `_password`, `_secret`, `_token` or `_key`, are replaced by `[REDACTED]`.
+    pub fn effective_config(&self) -> Value {
+        let config = self.updates.borrow().clone();
+        let mut denied: Vec<String> = DEFAULT_DENIED_KEYS.iter().map(|key| key.to_string()).collect();
+        denied.extend(config.security.iter().flat_map(|s| s.denied_keys.iter().flatten()).cloned());
+        let mut value = serde_json::to_value(config).unwrap_or(Value::Null);
+        redact_secrets(&mut value, &denied);
+        value
+    }
+}
+
+fn redact_secrets(value: &mut Value, denied: &[String]) {
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields.iter_mut() {
+                let lower = key.to_lowercase();
+                let secret = denied.iter().any(|denied| denied.eq_ignore_ascii_case(key))
+                    || SECRET_SUFFIXES.iter().any(|suffix| lower.ends_with(suffix));
+                if secret && !value.is_null() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_secrets(value, denied);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| redact_secrets(value, denied)),
+        _ => {}
+    }
+}
@@ -0,0 +1,56 @@
+use super::LogConfig;
+use crate::utils::LogLevel;
+
+/// Environment variable holding an env_logger-style filter, e.g.
+/// `LOGENGINE_FILTER=info,my_app::db=trace`, overriding the configured levels.
+pub const FILTER_VAR: &str = "LOGENGINE_FILTER";
+
+/// Level name disabling every record, from a filter such as `hyper=off`.
+pub const LEVEL_OFF: &str = "OFF";
+
+/// A filter string in the `RUST_LOG` syntax of env_logger: comma-separated directives,
+/// each a level (`info`), a module/target prefix (`my_app`, enabling everything it logs),
+/// or both (`my_app::db=trace`). Levels are case-insensitive and include `off`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FilterSpec {
+    /// Level for targets no directive names.
+    pub level: Option<String>,
+    /// Levels by module/target prefix, in the order given.
+    pub modules: Vec<(String, String)>,
+}
+
+impl FilterSpec {
+    /// Parses a filter string. Level names are checked when the configuration they are
+    /// applied to is validated, since they may refer to its custom levels.
+    pub fn parse(spec: &str) -> Self {
+        let mut filter = FilterSpec::default();
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    filter.modules.push((target.trim().to_string(), level.trim().to_uppercase()));
+                }
+                None if is_level(directive) => filter.level = Some(directive.to_uppercase()),
+                None => filter.modules.push((directive.to_string(), LogLevel::TRACE.as_str().to_string())),
+            }
+        }
+        filter
+    }
+
+    /// Reads and parses [`FILTER_VAR`], if it is set.
+    pub fn from_env() -> Option<Self> {
+        std::env::var(FILTER_VAR).ok().map(|spec| FilterSpec::parse(&spec))
+    }
+
+    /// Replaces the configuration's module level overrides with this filter's and, if the
+    /// filter names one, its level.
+    pub fn apply(&self, config: &mut LogConfig) {
+        if let Some(level) = &self.level {
+            config.level = level.clone();
+        }
+        config.filters = Some(self.modules.iter().cloned().collect());
+    }
+}
+
+fn is_level(name: &str) -> bool {
+    name.eq_ignore_ascii_case(LEVEL_OFF) || LogLevel::from_str(name).is_some()
+}
@@ -0,0 +1,168 @@
+use super::{ConfigError, ConfigOrigin, ConfigurationManager};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use std::sync::Arc;
+use crate::utils::time;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::net::TcpStream;
+
+/// How long fetching the remote configuration may take before the attempt fails.
+pub const REMOTE_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A centrally managed configuration document, in YAML or JSON.
+///
+/// Only plain `http://` endpoints are supported, and none on `wasm32`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteSource {
+    /// A document served at a URL, e.g. `http://config.internal/logging.yaml`.
+    Http(String),
+    /// A key of an etcd v3 cluster, read through its JSON gateway, e.g.
+    /// `http://etcd:2379` and `/logging/my-service`.
+    Etcd { endpoint: String, key: String },
+    /// A key of Consul's KV store, e.g. `http://consul:8500` and `logging/my-service`.
+    Consul { endpoint: String, key: String },
+}
+
+impl RemoteSource {
+    /// Fetches the configuration document.
+    pub async fn fetch(&self) -> Result<String, ConfigError> {
+        time::timeout(REMOTE_FETCH_TIMEOUT, self.fetch_now())
+            .await
+            .map_err(|_| ConfigError::RemoteError(format!("Timed out fetching {}", self)))?
+    }
+
+    async fn fetch_now(&self) -> Result<String, ConfigError> {
+        match self {
+            RemoteSource::Http(url) => request("GET", url, None).await,
+            RemoteSource::Consul { endpoint, key } => {
+                let endpoint = endpoint.trim_end_matches('/');
+                let url = format!("{}/v1/kv/{}?raw", endpoint, key.trim_start_matches('/'));
+                request("GET", &url, None).await
+            }
+            RemoteSource::Etcd { endpoint, key } => {
+                let url = format!("{}/v3/kv/range", endpoint.trim_end_matches('/'));
+                let body = serde_json::json!({ "key": STANDARD.encode(key) }).to_string();
+                let response = request("POST", &url, Some(&body)).await?;
+                let response: serde_json::Value = serde_json::from_str(&response)
+                    .map_err(|e| ConfigError::RemoteError(format!("Invalid etcd response: {}", e)))?;
+                let value = response["kvs"][0]["value"]
+                    .as_str()
+                    .ok_or_else(|| ConfigError::RemoteError(format!("etcd key not found: {}", key)))?;
+                let invalid =
+                    |e: &dyn std::fmt::Display| ConfigError::RemoteError(format!("Invalid etcd value: {}", e));
+                let value = STANDARD.decode(value).map_err(|e| invalid(&e))?;
+                String::from_utf8(value).map_err(|e| invalid(&e))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for RemoteSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RemoteSource::Http(url) => write!(f, "{}", url),
+            RemoteSource::Etcd { endpoint, key } => write!(f, "etcd {} key {}", endpoint, key),
+            RemoteSource::Consul { endpoint, key } => write!(f, "consul {} key {}", endpoint, key),
+        }
+    }
+}
+
+/// Sends an HTTP/1.0 request, so the response is neither chunked nor kept alive, and
+/// returns the body of a 2xx response.
+#[cfg(not(target_arch = "wasm32"))]
+async fn request(method: &str, url: &str, body: Option<&str>) -> Result<String, ConfigError> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| ConfigError::RemoteError(format!("Unsupported URL: {}", url)))?;
+    let (host, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    if host.is_empty() {
+        return Err(ConfigError::RemoteError(format!("Invalid URL: {}", url)));
+    }
+    let addr = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:80", host)
+    };
+
+    let io_error = |e: std::io::Error| ConfigError::RemoteError(format!("{}: {}", url, e));
+    let mut stream = TcpStream::connect(&addr).await.map_err(io_error)?;
+    let body = body.unwrap_or_default();
+    let request = format!(
+        "{} {} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        host,
+        body.len(),
+        body
+    );
+    stream.write_all(request.as_bytes()).await.map_err(io_error)?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.map_err(io_error)?;
+
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| ConfigError::RemoteError(format!("Malformed response from {}", url)))?;
+    let status = head.lines().next().unwrap_or_default();
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(body.to_string()),
+        _ => Err(ConfigError::RemoteError(format!("{} responded: {}", url, status))),
+    }
+}
+
+/// Browsers only allow HTTP through their `fetch` API, which is not used yet.
+#[cfg(target_arch = "wasm32")]
+async fn request(_method: &str, url: &str, _body: Option<&str>) -> Result<String, ConfigError> {
+    Err(ConfigError::RemoteError(format!("Cannot fetch {} on wasm32", url)))
+}
+
+impl ConfigurationManager {
+    /// Initializes the ConfigurationManager from a remote document, falling back to the
+    /// local `fallback` file when the source cannot be reached. The document goes through
+    /// the same includes, profiles, environment overrides and validation as a file.
+    pub async fn from_remote(source: &RemoteSource, fallback: Option<&str>) -> Result<Self, ConfigError> {
+        match source.fetch().await {
+            Ok(document) => {
+                let mut manager = Self::with_config(Self::parse(&document)?);
+                manager.origin = Some(ConfigOrigin::Remote(source.clone()));
+                Ok(manager)
+            }
+            Err(e) => match fallback {
+                Some(config_file) => {
+                    eprintln!("Using {} instead of the remote configuration: {}", config_file, e);
+                    Self::new(config_file).await
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Fetches `source` every `interval`, replacing the configuration and notifying
+    /// subscribers when the document changes. Unreachable sources and invalid documents
+    /// are reported and the current configuration kept. Polling stops once every clone of
+    /// the manager has been dropped.
+    pub fn watch_remote(&self, source: RemoteSource, interval: Duration) {
+        let config = Arc::downgrade(&self.config);
+        let updates = Arc::downgrade(&self.updates);
+        crate::utils::spawn(async move {
+            let mut ticker = time::interval(interval);
+            // The first tick completes immediately
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let (Some(config), Some(updates)) = (config.upgrade(), updates.upgrade()) else {
+                    break;
+                };
+                match source.fetch().await.and_then(|document| Self::parse(&document)) {
+                    Ok(new_config) => Self::publish(&config, &updates, new_config).await,
+                    Err(e) => eprintln!("Keeping the current configuration, {} failed: {}", source, e),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,188 @@
+use super::LogConfig;
+use crate::security::{PII_DETECTORS, SECRET_DETECTORS};
+use serde_json::{json, Value};
+
+impl LogConfig {
+    /// Returns a JSON Schema (draft 2020-12) describing the configuration file, for
+    /// editors to validate and complete YAML or JSON configurations, e.g. through a
+    /// `# yaml-language-server: $schema=log-engine.schema.json` modeline.
+    pub fn json_schema() -> Value {
+        let level = json!({
+            "anyOf": [
+                { "enum": ["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"] },
+                { "type": "string", "description": "A level declared in custom_levels." }
+            ]
+        });
+        let detectors: Vec<&str> = PII_DETECTORS.iter().chain(SECRET_DETECTORS).copied().collect();
+        let handler_types = ["console", "file", "remote", "memory"];
+        let handler_settings = handler_types.iter().map(|type_| {
+            json!({
+                "if": { "properties": { "type_": { "const": type_ } } },
+                "then": { "properties": { "config": { "$ref": format!("#/$defs/{}_handler", type_) } } }
+            })
+        });
+
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "log-engine configuration",
+            "$ref": "#/$defs/settings",
+            "required": ["level", "handlers"],
+            "$defs": {
+                "level": level,
+                "settings": {
+                    "type": "object",
+                    "properties": {
+                        "level": { "$ref": "#/$defs/level" },
+                        "filters": {
+                            "description": "Level overrides by module/target prefix.",
+                            "type": "object",
+                            "additionalProperties": { "$ref": "#/$defs/level" }
+                        },
+                        "handlers": { "type": "array", "items": { "$ref": "#/$defs/handler" } },
+                        "formatter": {
+                            "anyOf": [
+                                { "enum": ["json", "text"] },
+                                {
+                                    "type": "object",
+                                    "required": ["type"],
+                                    "additionalProperties": false,
+                                    "properties": {
+                                        "type": { "enum": ["json", "text"] },
+                                        "pattern": { "type": "string" },
+                                        "timestamp": { "type": "string", "description": "strftime format" }
+                                    }
+                                }
+                            ]
+                        },
+                        "plugins": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "required": ["name"],
+                                "properties": { "name": { "type": "string" }, "config": {} }
+                            }
+                        },
+                        "global_filters": { "type": "array", "items": { "$ref": "#/$defs/filter" } },
+                        "enrichers": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "required": ["type_"],
+                                "properties": {
+                                    "type_": { "enum": ["hostname", "pid", "thread", "env", "otel_trace"] },
+                                    "config": { "type": "object" }
+                                }
+                            }
+                        },
+                        "custom_levels": {
+                            "type": "object",
+                            "additionalProperties": { "type": "integer", "minimum": 0, "maximum": 65535 }
+                        },
+                        "security": { "$ref": "#/$defs/security" },
+                        "backtrace": {
+                            "description": "Attach a backtrace to ERROR and FATAL records.",
+                            "type": "boolean",
+                            "default": false
+                        },
+                        "include": {
+                            "description": "Fragments merged under this file, relative to it.",
+                            "anyOf": [
+                                { "type": "string" },
+                                { "type": "array", "items": { "type": "string" } }
+                            ]
+                        },
+                        "profiles": {
+                            "description": "Settings merged over the file when selected by LOGENGINE_PROFILE.",
+                            "type": "object",
+                            "additionalProperties": { "$ref": "#/$defs/settings" }
+                        }
+                    }
+                },
+                "handler": {
+                    "type": "object",
+                    "required": ["type_"],
+                    "properties": {
+                        "type_": { "enum": handler_types },
+                        "level": { "$ref": "#/$defs/level" },
+                        "clearance": { "enum": ["public", "internal", "confidential"] },
+                        "config": { "type": "object" },
+                        "filters": { "type": "array", "items": { "$ref": "#/$defs/filter" } }
+                    },
+                    "allOf": handler_settings.collect::<Vec<_>>()
+                },
+                "console_handler": { "type": "object", "additionalProperties": false },
+                "file_handler": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "file_path": { "type": "string", "default": "logs/app.log" },
+                        "max_size": { "type": "integer", "minimum": 1, "default": 10485760 }
+                    }
+                },
+                "remote_handler": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "address": { "type": "string", "minLength": 1, "default": "127.0.0.1" },
+                        "port": { "type": "integer", "minimum": 1, "maximum": 65535, "default": 9000 },
+                        "retries": { "type": "integer", "minimum": 0 }
+                    }
+                },
+                "memory_handler": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "capacity": { "type": "integer", "minimum": 1, "default": 1000 }
+                    }
+                },
+                "filter": {
+                    "type": "object",
+                    "required": ["type_"],
+                    "properties": {
+                        "type_": { "enum": ["level", "regex", "metadata", "module"] },
+                        "config": { "type": "object" }
+                    }
+                },
+                "mask": {
+                    "anyOf": [
+                        { "enum": ["redact", "pseudonymize"] },
+                        {
+                            "type": "object",
+                            "minProperties": 1,
+                            "maxProperties": 1,
+                            "properties": {
+                                "keep_last": { "type": "integer", "minimum": 0 },
+                                "asterisks": { "type": "integer", "minimum": 0 },
+                                "replace": { "type": "string" }
+                            },
+                            "additionalProperties": false
+                        }
+                    ]
+                },
+                "security": {
+                    "type": "object",
+                    "properties": {
+                        "preset": { "enum": ["pci", "hipaa", "gdpr"] },
+                        "patterns": { "type": "array", "items": { "type": "string", "format": "regex" } },
+                        "denied_keys": { "type": "array", "items": { "type": "string" } },
+                        "detectors": { "type": "array", "items": { "enum": detectors } },
+                        "rules": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "required": ["pattern"],
+                                "properties": {
+                                    "pattern": { "type": "string", "format": "regex" },
+                                    "mask": { "$ref": "#/$defs/mask" },
+                                    "replacement": { "type": "string" }
+                                }
+                            }
+                        },
+                        "encrypted_fields": { "type": "array", "items": { "type": "string" } },
+                        "encryption": { "type": "boolean", "default": true }
+                    }
+                }
+            }
+        })
+    }
+}
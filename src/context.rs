@@ -0,0 +1,93 @@
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::future::Future;
+
+tokio::task_local! {
+    static TASK_CONTEXT: RefCell<Map<String, Value>>;
+}
+
+thread_local! {
+    static THREAD_CONTEXT: RefCell<Map<String, Value>> = RefCell::new(Map::new());
+}
+
+/// Handle to the diagnostic context of the current task or thread.
+///
+/// Inside a future run through [`scope`] the context is task-local and follows the task
+/// across threads; elsewhere it is thread-local. Fields in the context are merged into the
+/// metadata of every message logged from that scope, without overriding fields passed
+/// explicitly at the call site.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Context;
+
+/// Returns a handle to the current diagnostic context.
+pub fn context() -> Context {
+    Context
+}
+
+/// Runs `f` with `fields` added on top of the current context, so that they are attached
+/// to every message logged from within the future.
+pub async fn scope<F: Future>(fields: Map<String, Value>, f: F) -> F::Output {
+    let mut merged = Context.snapshot();
+    merged.extend(fields);
+    TASK_CONTEXT.scope(RefCell::new(merged), f).await
+}
+
+impl Context {
+    /// Inserts a field, returning a guard that restores the previous value when dropped.
+    pub fn insert(&self, key: &str, value: impl Into<Value>) -> ContextGuard {
+        let previous = with_context(|fields| fields.insert(key.to_string(), value.into()));
+        ContextGuard {
+            key: key.to_string(),
+            previous,
+        }
+    }
+
+    /// Returns the current value of a field.
+    pub fn get(&self, key: &str) -> Option<Value> {
+        with_context(|fields| fields.get(key).cloned())
+    }
+
+    /// Returns a copy of all fields in the current context.
+    pub fn snapshot(&self) -> Map<String, Value> {
+        with_context(|fields| fields.clone())
+    }
+
+    /// Merges the context into a metadata object, keeping fields already present.
+    pub(crate) fn merge_into(&self, metadata: &mut Value) {
+        if let Value::Object(target) = metadata {
+            with_context(|fields| {
+                for (key, value) in fields.iter() {
+                    target.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+            });
+        }
+    }
+}
+
+/// Restores a context field to its previous value when dropped.
+///
+/// Guards should be dropped on the task or thread that created them.
+#[must_use = "the field is removed again when the guard is dropped"]
+pub struct ContextGuard {
+    key: String,
+    previous: Option<Value>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        with_context(|fields| match previous {
+            Some(value) => fields.insert(self.key.clone(), value),
+            None => fields.remove(&self.key),
+        });
+    }
+}
+
+/// Runs `f` against the task-local context if inside a [`scope`], else the thread-local one.
+fn with_context<R>(f: impl FnOnce(&mut Map<String, Value>) -> R) -> R {
+    let mut f = Some(f);
+    if let Ok(result) = TASK_CONTEXT.try_with(|fields| (f.take().unwrap())(&mut fields.borrow_mut())) {
+        return result;
+    }
+    THREAD_CONTEXT.with(|fields| (f.take().unwrap())(&mut fields.borrow_mut()))
+}
@@ -0,0 +1,38 @@
+use super::{insert_field, EnricherError, LogEnricher};
+use serde_json::{Map, Value};
+
+/// Adds selected environment variables, captured once at startup.
+pub struct EnvEnricher {
+    values: Vec<(String, String)>,
+}
+
+impl EnvEnricher {
+    /// Initializes the EnvEnricher with the variables to capture. Unset variables are skipped.
+    pub fn new(vars: &[&str]) -> Self {
+        let values = vars
+            .iter()
+            .filter_map(|var| std::env::var(var).ok().map(|value| (var.to_string(), value)))
+            .collect();
+        EnvEnricher { values }
+    }
+
+    /// Builds the enricher from `{ "vars": ["APP_ENV", "REGION"] }` options.
+    pub fn from_config(options: &Value) -> Result<Self, EnricherError> {
+        let vars: Vec<&str> = options
+            .get("vars")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| EnricherError::ConfigError("env enricher requires vars".into()))?
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        Ok(EnvEnricher::new(&vars))
+    }
+}
+
+impl LogEnricher for EnvEnricher {
+    fn enrich(&self, fields: &mut Map<String, Value>) {
+        for (key, value) in &self.values {
+            insert_field(fields, key, Value::String(value.clone()));
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use super::{insert_field, LogEnricher};
+use serde_json::{Map, Value};
+
+/// Adds the machine hostname as `hostname`.
+pub struct HostnameEnricher {
+    hostname: String,
+}
+
+impl HostnameEnricher {
+    /// Initializes the HostnameEnricher, resolving the hostname once. On `wasm32`, which
+    /// has no host name, it is `unknown`.
+    pub fn new() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let hostname = hostname::get()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|_| "unknown".to_string());
+        #[cfg(target_arch = "wasm32")]
+        let hostname = "unknown".to_string();
+        HostnameEnricher { hostname }
+    }
+}
+
+impl Default for HostnameEnricher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogEnricher for HostnameEnricher {
+    fn enrich(&self, fields: &mut Map<String, Value>) {
+        insert_field(fields, "hostname", Value::String(self.hostname.clone()));
+    }
+}
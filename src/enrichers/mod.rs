@@ -0,0 +1,55 @@
+pub mod env_enricher;
+pub mod hostname_enricher;
+#[cfg(feature = "otel")]
+pub mod otel_trace_enricher;
+pub mod pid_enricher;
+pub mod thread_enricher;
+
+use crate::config::EnricherConfig;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Custom error type for enricher construction.
+#[derive(Error, Debug)]
+pub enum EnricherError {
+    #[error("Unknown enricher type: {0}")]
+    UnknownType(String),
+    #[error("Invalid enricher configuration: {0}")]
+    ConfigError(String),
+}
+
+/// Trait defining the interface for log enrichers.
+///
+/// Enrichers run on the logging thread when a message is enqueued, so they can
+/// capture call-site state such as the current thread.
+pub trait LogEnricher: Send + Sync {
+    /// Appends fields to the metadata of a log message.
+    fn enrich(&self, fields: &mut Map<String, Value>);
+}
+
+/// Inserts a field unless the caller already provided one with the same key.
+pub(crate) fn insert_field(fields: &mut Map<String, Value>, key: &str, value: Value) {
+    fields.entry(key.to_string()).or_insert(value);
+}
+
+/// Builds an enricher from its configuration entry.
+pub fn build_enricher(cfg: &EnricherConfig) -> Result<Arc<dyn LogEnricher>, EnricherError> {
+    let options = cfg.config.clone().unwrap_or(serde_json::json!({}));
+    match cfg.type_.as_str() {
+        "hostname" => Ok(Arc::new(HostnameEnricher::new())),
+        "pid" => Ok(Arc::new(PidEnricher::new())),
+        "thread" => Ok(Arc::new(ThreadEnricher)),
+        "env" => Ok(Arc::new(EnvEnricher::from_config(&options)?)),
+        #[cfg(feature = "otel")]
+        "otel_trace" => Ok(Arc::new(OtelTraceEnricher)),
+        other => Err(EnricherError::UnknownType(other.to_string())),
+    }
+}
+
+pub use env_enricher::EnvEnricher;
+pub use hostname_enricher::HostnameEnricher;
+#[cfg(feature = "otel")]
+pub use otel_trace_enricher::OtelTraceEnricher;
+pub use pid_enricher::PidEnricher;
+pub use thread_enricher::ThreadEnricher;
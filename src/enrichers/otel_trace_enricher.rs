@@ -0,0 +1,23 @@
+use super::{insert_field, LogEnricher};
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry::Context;
+use serde_json::{Map, Value};
+
+/// Adds the trace and span ids of the current OpenTelemetry span as `trace_id` and
+/// `span_id`, so records can be joined with their traces, e.g. in Grafana Tempo. Records
+/// logged outside a valid span are left as they are.
+#[derive(Default)]
+pub struct OtelTraceEnricher;
+
+impl LogEnricher for OtelTraceEnricher {
+    fn enrich(&self, fields: &mut Map<String, Value>) {
+        let context = Context::current();
+        let span = context.span();
+        let span_context = span.span_context();
+        if !span_context.is_valid() {
+            return;
+        }
+        insert_field(fields, "trace_id", Value::String(span_context.trace_id().to_string()));
+        insert_field(fields, "span_id", Value::String(span_context.span_id().to_string()));
+    }
+}
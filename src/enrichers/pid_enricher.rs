@@ -0,0 +1,28 @@
+use super::{insert_field, LogEnricher};
+use serde_json::{Map, Value};
+
+/// Adds the current process id as `pid`.
+pub struct PidEnricher {
+    pid: u32,
+}
+
+impl PidEnricher {
+    /// Initializes the PidEnricher.
+    pub fn new() -> Self {
+        PidEnricher {
+            pid: std::process::id(),
+        }
+    }
+}
+
+impl Default for PidEnricher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogEnricher for PidEnricher {
+    fn enrich(&self, fields: &mut Map<String, Value>) {
+        insert_field(fields, "pid", Value::from(self.pid));
+    }
+}
@@ -0,0 +1,15 @@
+use super::{insert_field, LogEnricher};
+use serde_json::{Map, Value};
+
+/// Adds the id and name of the logging thread as `thread_id` and `thread_name`.
+pub struct ThreadEnricher;
+
+impl LogEnricher for ThreadEnricher {
+    fn enrich(&self, fields: &mut Map<String, Value>) {
+        let thread = std::thread::current();
+        insert_field(fields, "thread_id", Value::String(format!("{:?}", thread.id())));
+        if let Some(name) = thread.name() {
+            insert_field(fields, "thread_name", Value::String(name.to_string()));
+        }
+    }
+}
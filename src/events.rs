@@ -0,0 +1,70 @@
+use crate::security::REDACTED;
+use crate::utils::LogLevel;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+pub use log_engine_derive::LogEvent;
+
+/// A typed event with a fixed name, level and set of redacted fields, logged with
+/// [`Logger::log_event`](crate::logger::Logger::log_event).
+///
+/// Usually derived:
+///
+/// ```ignore
+/// #[derive(Serialize, LogEvent)]
+/// #[log(name = "player_login", level = "INFO")]
+/// struct PlayerLogin {
+///     player: String,
+///     #[log(redact)]
+///     session_token: String,
+/// }
+/// ```
+pub trait LogEvent: Serialize {
+    /// Name recorded in the `event` field and used as the message.
+    fn event_name(&self) -> &'static str;
+
+    /// Level the event is logged at.
+    fn level(&self) -> LogLevel {
+        LogLevel::INFO
+    }
+
+    /// Top-level fields whose values are masked before the event is logged.
+    fn redacted_fields(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// Returns the type name of `T` without its module path or generic arguments.
+pub(crate) fn short_type_name<T: ?Sized>() -> &'static str {
+    let full_name = std::any::type_name::<T>();
+    full_name
+        .split('<')
+        .next()
+        .and_then(|path| path.rsplit("::").next())
+        .unwrap_or(full_name)
+}
+
+/// Serializes an event into metadata, storing `name` as the `event` field and masking the
+/// `redacted` fields. Non-object values are stored under `data`.
+pub(crate) fn event_metadata<T: Serialize + ?Sized>(name: &str, event: &T, redacted: &[&str]) -> Value {
+    let mut fields = match serde_json::to_value(event) {
+        Ok(Value::Object(fields)) => fields,
+        Ok(other) => {
+            let mut fields = Map::new();
+            fields.insert("data".to_string(), other);
+            fields
+        }
+        Err(e) => {
+            let mut fields = Map::new();
+            fields.insert("serialization_error".to_string(), Value::String(e.to_string()));
+            fields
+        }
+    };
+    for key in redacted {
+        if let Some(value) = fields.get_mut(*key) {
+            *value = Value::String(REDACTED.to_string());
+        }
+    }
+    fields.insert("event".to_string(), Value::String(name.to_string()));
+    Value::Object(fields)
+}
@@ -0,0 +1,73 @@
+use crate::logger::Logger;
+use crate::utils::LogLevel;
+use serde_json::json;
+use std::fmt::Display;
+
+/// Logs the error of a `Result` and passes the `Result` on, so error paths can be
+/// logged inline without breaking `?` chains:
+///
+/// ```ignore
+/// let config = read_config(path).log_err(&logger, "loading configuration")?;
+/// ```
+pub trait LogResultExt {
+    /// Logs an `Err` at ERROR as `"{context}: {error}"`, with the error under the
+    /// `error` metadata key.
+    fn log_err(self, logger: &Logger, context: &str) -> Self;
+
+    /// Logs an `Err` at WARN, e.g. for errors that are retried or recovered from.
+    fn log_warn(self, logger: &Logger, context: &str) -> Self;
+}
+
+impl<T, E: Display> LogResultExt for Result<T, E> {
+    fn log_err(self, logger: &Logger, context: &str) -> Self {
+        log_result(self, logger, LogLevel::ERROR, context)
+    }
+
+    fn log_warn(self, logger: &Logger, context: &str) -> Self {
+        log_result(self, logger, LogLevel::WARN, context)
+    }
+}
+
+fn log_result<T, E: Display>(
+    result: Result<T, E>,
+    logger: &Logger,
+    level: LogLevel,
+    context: &str,
+) -> Result<T, E> {
+    if let Err(error) = &result {
+        if logger.enabled(level) {
+            let message = error.to_string();
+            logger.log(
+                level,
+                &format!("{}: {}", context, message),
+                Some(json!({ "error": { "message": message } })),
+            );
+        }
+    }
+    result
+}
+
+/// Logs a missing value of an `Option` and passes the `Option` on.
+pub trait LogOptionExt {
+    /// Logs a `None` at ERROR with `context` as the message.
+    fn log_err(self, logger: &Logger, context: &str) -> Self;
+
+    /// Logs a `None` at WARN with `context` as the message.
+    fn log_warn(self, logger: &Logger, context: &str) -> Self;
+}
+
+impl<T> LogOptionExt for Option<T> {
+    fn log_err(self, logger: &Logger, context: &str) -> Self {
+        if self.is_none() {
+            logger.log(LogLevel::ERROR, context, None);
+        }
+        self
+    }
+
+    fn log_warn(self, logger: &Logger, context: &str) -> Self {
+        if self.is_none() {
+            logger.log(LogLevel::WARN, context, None);
+        }
+        self
+    }
+}
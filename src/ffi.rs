@@ -0,0 +1,144 @@
+//! C API for feeding the Logger from native hosts such as C++ game engines, built into
+//! the crate's `cdylib` with the `ffi` feature. The declarations are in
+//! `include/logengine.h`.
+//!
+//! Every function returns [`LOGENGINE_OK`] or a negative error code, and never unwinds
+//! into the caller. The functions may be called from any thread.
+
+use crate::logger::Logger;
+use crate::utils::LogLevel;
+use serde_json::Value;
+use std::ffi::{c_char, c_int, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+pub const LOGENGINE_OK: c_int = 0;
+/// A pointer was null where a string is required, a string was not UTF-8, the metadata
+/// was not a JSON object, or the level was out of range.
+pub const LOGENGINE_ERR_INVALID_ARGUMENT: c_int = -1;
+pub const LOGENGINE_ERR_NOT_INITIALIZED: c_int = -2;
+pub const LOGENGINE_ERR_ALREADY_INITIALIZED: c_int = -3;
+/// The configuration or the key could not be loaded.
+pub const LOGENGINE_ERR_INIT_FAILED: c_int = -4;
+/// The records were not all written within the flush timeout.
+pub const LOGENGINE_ERR_TIMEOUT: c_int = -5;
+/// The call panicked; the Logger may be unusable.
+pub const LOGENGINE_ERR_PANIC: c_int = -6;
+
+static LOGGER: Mutex<Option<Arc<Logger>>> = Mutex::new(None);
+
+fn runtime() -> Result<&'static Runtime, c_int> {
+    crate::utils::runtime().map_err(|_| LOGENGINE_ERR_INIT_FAILED)
+}
+
+fn logger() -> Result<Arc<Logger>, c_int> {
+    let logger = LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+    logger.clone().ok_or(LOGENGINE_ERR_NOT_INITIALIZED)
+}
+
+/// Reads a nullable C string.
+///
+/// # Safety
+/// `ptr` must be null or point to a NUL-terminated string.
+unsafe fn optional_str<'a>(ptr: *const c_char) -> Result<Option<&'a str>, c_int> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map(Some)
+        .map_err(|_| LOGENGINE_ERR_INVALID_ARGUMENT)
+}
+
+/// Runs `f`, turning a panic into [`LOGENGINE_ERR_PANIC`].
+fn guarded(f: impl FnOnce() -> Result<(), c_int>) -> c_int {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => LOGENGINE_OK,
+        Ok(Err(code)) => code,
+        Err(_) => LOGENGINE_ERR_PANIC,
+    }
+}
+
+/// Starts the Logger, as [`Logger::new`] does. `config_path` may be null to use the
+/// default configuration; `key` is a key descriptor such as `env:LOG_KEY` or
+/// `file:/run/secrets/logkey` (see [`KeySource`](crate::keys::KeySource)), so key bytes
+/// never pass through the host.
+///
+/// # Safety
+/// `config_path` must be null or a NUL-terminated string, and `key` a NUL-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn logengine_init(config_path: *const c_char, key: *const c_char) -> c_int {
+    guarded(|| {
+        let config_path = optional_str(config_path)?;
+        let key = optional_str(key)?.ok_or(LOGENGINE_ERR_INVALID_ARGUMENT)?;
+        let mut slot = LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+        if slot.is_some() {
+            return Err(LOGENGINE_ERR_ALREADY_INITIALIZED);
+        }
+        let logger = runtime()?.block_on(Logger::new(config_path, key)).map_err(|e| {
+            eprintln!("Failed to initialize logger: {}", e);
+            LOGENGINE_ERR_INIT_FAILED
+        })?;
+        *slot = Some(logger);
+        Ok(())
+    })
+}
+
+/// Logs `message` at the level with severity `level`: 100 TRACE, 200 DEBUG, 300 INFO,
+/// 400 WARN, 500 ERROR, 600 FATAL, or that of a registered custom level; any other
+/// severity is rejected. `json_metadata` may be null or a JSON object, e.g.
+/// `{"player_id": 42}`. The record is queued, not yet written.
+///
+/// # Safety
+/// `message` must be a NUL-terminated string, and `json_metadata` null or one.
+#[no_mangle]
+pub unsafe extern "C" fn logengine_log(level: c_int, message: *const c_char, json_metadata: *const c_char) -> c_int {
+    guarded(|| {
+        let level = level_from_severity(level).ok_or(LOGENGINE_ERR_INVALID_ARGUMENT)?;
+        let message = optional_str(message)?.ok_or(LOGENGINE_ERR_INVALID_ARGUMENT)?;
+        let metadata = match optional_str(json_metadata)? {
+            Some(json) => match serde_json::from_str(json) {
+                Ok(metadata @ Value::Object(_)) => Some(metadata),
+                _ => return Err(LOGENGINE_ERR_INVALID_ARGUMENT),
+            },
+            None => None,
+        };
+        logger()?.log(level, message, metadata);
+        Ok(())
+    })
+}
+
+/// Blocks until every record logged so far has been written and the handlers flushed,
+/// waiting at most `timeout_ms` milliseconds.
+#[no_mangle]
+pub extern "C" fn logengine_flush(timeout_ms: u32) -> c_int {
+    guarded(|| {
+        if logger()?.flush_blocking(Duration::from_millis(timeout_ms.into())) {
+            Ok(())
+        } else {
+            Err(LOGENGINE_ERR_TIMEOUT)
+        }
+    })
+}
+
+/// Writes the queued records and stops the Logger, as [`Logger::shutdown`] does. The
+/// Logger can then be started again with [`logengine_init`].
+#[no_mangle]
+pub extern "C" fn logengine_shutdown() -> c_int {
+    guarded(|| {
+        let logger = LOGGER
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .ok_or(LOGENGINE_ERR_NOT_INITIALIZED)?;
+        runtime()?.block_on(logger.shutdown());
+        Ok(())
+    })
+}
+
+fn level_from_severity(severity: c_int) -> Option<LogLevel> {
+    LogLevel::from_severity(u16::try_from(severity).ok()?)
+}
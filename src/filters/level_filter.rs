@@ -0,0 +1,41 @@
+use super::{FilterError, LogFilter};
+use crate::logger::LogMessage;
+use crate::utils::LogLevel;
+use serde_json::Value;
+
+/// Keeps log messages whose level falls within an inclusive range.
+pub struct LevelFilter {
+    min: Option<LogLevel>,
+    max: Option<LogLevel>,
+}
+
+impl LevelFilter {
+    /// Initializes the LevelFilter with optional lower and upper bounds.
+    pub fn new(min: Option<LogLevel>, max: Option<LogLevel>) -> Self {
+        LevelFilter { min, max }
+    }
+
+    /// Keeps messages at `level` or above.
+    pub fn at_least(level: LogLevel) -> Self {
+        LevelFilter::new(Some(level), None)
+    }
+
+    /// Builds the filter from `{ "min": "INFO", "max": "ERROR" }` options.
+    pub fn from_config(options: &Value) -> Result<Self, FilterError> {
+        let parse = |key: &str| -> Result<Option<LogLevel>, FilterError> {
+            match options.get(key).and_then(|v| v.as_str()) {
+                Some(level) => LogLevel::from_str(level)
+                    .map(Some)
+                    .ok_or_else(|| FilterError::ConfigError(format!("Invalid level: {}", level))),
+                None => Ok(None),
+            }
+        };
+        Ok(LevelFilter::new(parse("min")?, parse("max")?))
+    }
+}
+
+impl LogFilter for LevelFilter {
+    fn allow(&self, log: &LogMessage) -> bool {
+        self.min.is_none_or(|min| log.level.enabled_at(min)) && self.max.is_none_or(|max| log.level <= max)
+    }
+}
@@ -0,0 +1,55 @@
+use super::{FilterError, LogFilter};
+use crate::logger::LogMessage;
+use serde_json::Value;
+
+/// Keeps log messages whose metadata satisfies a predicate.
+pub struct MetadataFilter {
+    predicate: Box<dyn Fn(&Value) -> bool + Send + Sync>,
+}
+
+impl MetadataFilter {
+    /// Initializes the MetadataFilter with an arbitrary predicate over the metadata.
+    pub fn new<F>(predicate: F) -> Self
+    where
+        F: Fn(&Value) -> bool + Send + Sync + 'static,
+    {
+        MetadataFilter {
+            predicate: Box::new(predicate),
+        }
+    }
+
+    /// Keeps messages whose metadata has `key` (a field name or JSON pointer),
+    /// optionally requiring it to equal `value`. When `exclude` is set the match is inverted.
+    pub fn matching(key: &str, value: Option<Value>, exclude: bool) -> Self {
+        let key = key.to_string();
+        MetadataFilter::new(move |metadata| {
+            let found = if key.starts_with('/') {
+                metadata.pointer(&key)
+            } else {
+                metadata.get(&key)
+            };
+            let matched = match (&value, found) {
+                (Some(expected), Some(actual)) => expected == actual,
+                (None, Some(_)) => true,
+                (_, None) => false,
+            };
+            matched != exclude
+        })
+    }
+
+    /// Builds the filter from `{ "key": "user", "value": "bot", "exclude": true }` options.
+    pub fn from_config(options: &Value) -> Result<Self, FilterError> {
+        let key = options
+            .get("key")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FilterError::ConfigError("metadata filter requires a key".into()))?;
+        let exclude = options.get("exclude").and_then(|v| v.as_bool()).unwrap_or(false);
+        Ok(MetadataFilter::matching(key, options.get("value").cloned(), exclude))
+    }
+}
+
+impl LogFilter for MetadataFilter {
+    fn allow(&self, log: &LogMessage) -> bool {
+        (self.predicate)(&log.metadata)
+    }
+}
@@ -0,0 +1,43 @@
+pub mod level_filter;
+pub mod metadata_filter;
+pub mod module_filter;
+pub mod module_level_filter;
+pub mod regex_filter;
+
+use crate::config::FilterConfig;
+use crate::logger::LogMessage;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Custom error type for filter construction.
+#[derive(Error, Debug)]
+pub enum FilterError {
+    #[error("Unknown filter type: {0}")]
+    UnknownType(String),
+    #[error("Invalid filter configuration: {0}")]
+    ConfigError(String),
+}
+
+/// Trait defining the interface for log filters.
+pub trait LogFilter: Send + Sync {
+    /// Returns `true` if the log message should be kept.
+    fn allow(&self, log: &LogMessage) -> bool;
+}
+
+/// Builds a filter from its configuration entry.
+pub fn build_filter(cfg: &FilterConfig) -> Result<Arc<dyn LogFilter>, FilterError> {
+    let options = cfg.config.clone().unwrap_or(serde_json::json!({}));
+    match cfg.type_.as_str() {
+        "level" => Ok(Arc::new(LevelFilter::from_config(&options)?)),
+        "regex" => Ok(Arc::new(RegexFilter::from_config(&options)?)),
+        "metadata" => Ok(Arc::new(MetadataFilter::from_config(&options)?)),
+        "module" => Ok(Arc::new(ModuleFilter::from_config(&options)?)),
+        other => Err(FilterError::UnknownType(other.to_string())),
+    }
+}
+
+pub use level_filter::LevelFilter;
+pub use metadata_filter::MetadataFilter;
+pub use module_filter::ModuleFilter;
+pub use module_level_filter::ModuleLevelFilter;
+pub use regex_filter::RegexFilter;
@@ -0,0 +1,58 @@
+use super::{FilterError, LogFilter};
+use crate::logger::LogMessage;
+use serde_json::Value;
+
+/// Keeps or drops log messages by module/target prefix.
+///
+/// The target is the module the message was logged from, falling back to the `target`
+/// field of the message metadata. Messages without a target are always kept.
+pub struct ModuleFilter {
+    prefixes: Vec<String>,
+    exclude: bool,
+}
+
+impl ModuleFilter {
+    /// Initializes the ModuleFilter. When `exclude` is set, messages from matching
+    /// targets are dropped; otherwise only matching targets are kept.
+    pub fn new(prefixes: Vec<String>, exclude: bool) -> Self {
+        ModuleFilter { prefixes, exclude }
+    }
+
+    /// Builds the filter from `{ "prefixes": ["hyper", "my_app::net"], "exclude": true }` options.
+    pub fn from_config(options: &Value) -> Result<Self, FilterError> {
+        let prefixes = options
+            .get("prefixes")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| FilterError::ConfigError("module filter requires prefixes".into()))?
+            .iter()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect();
+        let exclude = options.get("exclude").and_then(|v| v.as_bool()).unwrap_or(false);
+        Ok(ModuleFilter::new(prefixes, exclude))
+    }
+
+    fn matches(&self, target: &str) -> bool {
+        self.prefixes.iter().any(|prefix| matches_prefix(target, prefix))
+    }
+}
+
+/// Returns the module a message was logged from, falling back to its `target` metadata.
+pub(crate) fn target(log: &LogMessage) -> Option<&str> {
+    log.module
+        .as_deref()
+        .or_else(|| log.metadata.get("target").and_then(|v| v.as_str()))
+}
+
+/// Returns `true` if `target` is the module `prefix` or one of its submodules.
+pub(crate) fn matches_prefix(target: &str, prefix: &str) -> bool {
+    target == prefix || (target.starts_with(prefix) && target[prefix.len()..].starts_with("::"))
+}
+
+impl LogFilter for ModuleFilter {
+    fn allow(&self, log: &LogMessage) -> bool {
+        match target(log) {
+            Some(target) => self.matches(target) != self.exclude,
+            None => true,
+        }
+    }
+}
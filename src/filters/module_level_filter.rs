@@ -0,0 +1,50 @@
+use super::module_filter::{matches_prefix, target};
+use super::LogFilter;
+use crate::logger::LogMessage;
+use crate::utils::LogLevel;
+
+/// Keeps log messages at or above a level chosen by the module/target they were logged
+/// from, e.g. DEBUG for `my_app::network` and WARN for `hyper`.
+///
+/// The longest matching prefix wins; messages from other targets, or without a target,
+/// use the default level.
+pub struct ModuleLevelFilter {
+    default: LogLevel,
+    /// Overrides, longest prefix first.
+    overrides: Vec<(String, LogLevel)>,
+}
+
+impl ModuleLevelFilter {
+    /// Initializes the ModuleLevelFilter with a default level and per-prefix overrides.
+    pub fn new(default: LogLevel, overrides: impl IntoIterator<Item = (String, LogLevel)>) -> Self {
+        let mut overrides: Vec<_> = overrides.into_iter().collect();
+        overrides.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        ModuleLevelFilter { default, overrides }
+    }
+
+    /// Returns the lowest threshold, below which no message passes whatever its target.
+    pub fn most_verbose(&self) -> LogLevel {
+        self.overrides
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, std::cmp::min)
+    }
+
+    /// Returns the threshold applied to messages from `target`.
+    pub fn level_for(&self, target: Option<&str>) -> LogLevel {
+        target
+            .and_then(|target| {
+                self.overrides
+                    .iter()
+                    .find(|(prefix, _)| matches_prefix(target, prefix))
+                    .map(|(_, level)| *level)
+            })
+            .unwrap_or(self.default)
+    }
+}
+
+impl LogFilter for ModuleLevelFilter {
+    fn allow(&self, log: &LogMessage) -> bool {
+        log.level.enabled_at(self.level_for(target(log)))
+    }
+}
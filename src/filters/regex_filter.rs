@@ -0,0 +1,35 @@
+use super::{FilterError, LogFilter};
+use crate::logger::LogMessage;
+use regex::Regex;
+use serde_json::Value;
+
+/// Keeps or drops log messages whose text matches a regular expression.
+pub struct RegexFilter {
+    pattern: Regex,
+    exclude: bool,
+}
+
+impl RegexFilter {
+    /// Initializes the RegexFilter. When `exclude` is set, matching messages are dropped;
+    /// otherwise only matching messages are kept.
+    pub fn new(pattern: &str, exclude: bool) -> Result<Self, FilterError> {
+        let pattern = Regex::new(pattern).map_err(|e| FilterError::ConfigError(e.to_string()))?;
+        Ok(RegexFilter { pattern, exclude })
+    }
+
+    /// Builds the filter from `{ "pattern": "...", "exclude": true }` options.
+    pub fn from_config(options: &Value) -> Result<Self, FilterError> {
+        let pattern = options
+            .get("pattern")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FilterError::ConfigError("regex filter requires a pattern".into()))?;
+        let exclude = options.get("exclude").and_then(|v| v.as_bool()).unwrap_or(false);
+        RegexFilter::new(pattern, exclude)
+    }
+}
+
+impl LogFilter for RegexFilter {
+    fn allow(&self, log: &LogMessage) -> bool {
+        self.pattern.is_match(&log.message) != self.exclude
+    }
+}
@@ -1,20 +1,64 @@
 use super::Formatter;
+use crate::logger::LogMessage;
 use async_trait::async_trait;
-use chrono::Utc;
 use serde_json::json;
 
 /// Formats log messages as JSON.
-pub struct JsonFormatter;
+///
+/// Records also carry their `sequence` number, a `thread` object and `task_id` when logged from a tokio task,
+/// plus `module`, `file`, and `line` fields when they have a source location.
+#[derive(Default)]
+pub struct JsonFormatter {
+    timestamp_format: Option<String>,
+}
+
+impl JsonFormatter {
+    /// Initializes the JsonFormatter with RFC 3339 timestamps.
+    pub fn new() -> Self {
+        JsonFormatter::default()
+    }
+
+    /// Renders `timestamp` with a strftime-style format, e.g. `%s%.3f`, instead of
+    /// RFC 3339.
+    pub fn with_timestamp_format(mut self, format: Option<String>) -> Self {
+        self.timestamp_format = format;
+        self
+    }
+}
 
 #[async_trait]
 impl Formatter for JsonFormatter {
     async fn format(&self, level: &str, message: &str, metadata: &serde_json::Value) -> String {
         let log = json!({
-            "timestamp": Utc::now().to_rfc3339(),
+            "timestamp": super::timestamp(self.timestamp_format.as_deref()),
             "level": level,
             "message": message,
             "metadata": metadata,
         });
         log.to_string()
     }
+
+    async fn format_record(&self, log: &LogMessage, message: &str, metadata: &serde_json::Value) -> String {
+        let mut record = json!({
+            "timestamp": super::timestamp(self.timestamp_format.as_deref()),
+            "level": log.level.as_str(),
+            "message": message,
+            "metadata": metadata,
+            "sequence": log.sequence,
+            "thread": { "id": log.thread_id, "name": log.thread_name.as_deref() },
+        });
+        if let Some(task_id) = log.task_id {
+            record["task_id"] = json!(task_id.to_string());
+        }
+        if let Some(module) = &log.module {
+            record["module"] = json!(module);
+        }
+        if let Some(file) = &log.file {
+            record["file"] = json!(file);
+        }
+        if let Some(line) = log.line {
+            record["line"] = json!(line);
+        }
+        record.to_string()
+    }
 }
@@ -1,6 +1,8 @@
 pub mod json_formatter;
 pub mod text_formatter;
 
+use crate::logger::LogMessage;
+use crate::utils::now;
 use async_trait::async_trait;
 use serde_json::Value;
 
@@ -9,6 +11,21 @@ use serde_json::Value;
 pub trait Formatter: Send + Sync {
     /// Formats a log message based on level, message, and metadata.
     async fn format(&self, level: &str, message: &str, metadata: &Value) -> String;
+
+    /// Formats a log message with access to the full record, e.g. its source location.
+    /// `message` and `metadata` are the processed values to render in place of the raw ones.
+    /// Defaults to [`Formatter::format`].
+    async fn format_record(&self, log: &LogMessage, message: &str, metadata: &Value) -> String {
+        self.format(log.level.as_str(), message, metadata).await
+    }
+}
+
+/// Renders the current time with a strftime-style `format`, or as RFC 3339 without one.
+pub(crate) fn timestamp(format: Option<&str>) -> String {
+    match format {
+        Some(format) => now().format(format).to_string(),
+        None => now().to_rfc3339(),
+    }
 }
 
 pub use json_formatter::JsonFormatter;
@@ -1,10 +1,15 @@
 use super::Formatter;
+use crate::logger::LogMessage;
 use async_trait::async_trait;
-use chrono::Utc;
 
 /// Formats log messages as plain text.
+///
+/// Supported placeholders: `{timestamp}`, `{level}`, `{message}`, `{metadata}`,
+/// `{thread}` (name, or id for unnamed threads), `{thread_id}`, `{task_id}`, `{sequence}`,
+/// and, when the record carries a source location, `{module}`, `{file}`, and `{line}`.
 pub struct TextFormatter {
     pattern: String,
+    timestamp_format: Option<String>,
 }
 
 impl TextFormatter {
@@ -14,14 +19,19 @@ impl TextFormatter {
         let default_pattern = "{timestamp} [{level}] - {message} - {metadata}".to_string();
         TextFormatter {
             pattern: pattern.unwrap_or(default_pattern),
+            timestamp_format: None,
         }
     }
-}
 
-#[async_trait]
-impl Formatter for TextFormatter {
-    async fn format(&self, level: &str, message: &str, metadata: &serde_json::Value) -> String {
-        let timestamp = Utc::now().to_rfc3339();
+    /// Renders `{timestamp}` with a strftime-style format, e.g. `%H:%M:%S`, instead of
+    /// RFC 3339.
+    pub fn with_timestamp_format(mut self, format: Option<String>) -> Self {
+        self.timestamp_format = format;
+        self
+    }
+
+    fn render(&self, level: &str, message: &str, metadata: &serde_json::Value) -> String {
+        let timestamp = super::timestamp(self.timestamp_format.as_deref());
         let metadata_str = metadata.to_string();
         self.pattern
             .replace("{timestamp}", &timestamp)
@@ -30,3 +40,31 @@ impl Formatter for TextFormatter {
             .replace("{metadata}", &metadata_str)
     }
 }
+
+#[async_trait]
+impl Formatter for TextFormatter {
+    async fn format(&self, level: &str, message: &str, metadata: &serde_json::Value) -> String {
+        self.render(level, message, metadata)
+            .replace("{module}", "")
+            .replace("{file}", "")
+            .replace("{line}", "")
+            .replace("{thread}", "")
+            .replace("{thread_id}", "")
+            .replace("{task_id}", "")
+            .replace("{sequence}", "")
+    }
+
+    async fn format_record(&self, log: &LogMessage, message: &str, metadata: &serde_json::Value) -> String {
+        let line = log.line.map(|line| line.to_string()).unwrap_or_default();
+        let thread_id = log.thread_id.to_string();
+        let task_id = log.task_id.map(|id| id.to_string()).unwrap_or_default();
+        self.render(log.level.as_str(), message, metadata)
+            .replace("{module}", log.module.as_deref().unwrap_or_default())
+            .replace("{file}", log.file.as_deref().unwrap_or_default())
+            .replace("{line}", &line)
+            .replace("{thread}", log.thread_name.as_deref().unwrap_or(&thread_id))
+            .replace("{thread_id}", &thread_id)
+            .replace("{task_id}", &task_id)
+            .replace("{sequence}", &log.sequence.to_string())
+    }
+}
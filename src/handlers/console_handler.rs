@@ -2,6 +2,10 @@ use super::LogHandler;
 use async_trait::async_trait;
 
 /// Handles console output for log messages.
+///
+/// In the browser (the `wasm` feature on `wasm32`) messages go to the developer console
+/// through `web_sys::console`, using the console method matching their level.
+#[derive(Default)]
 pub struct ConsoleHandler {
     // Configuration fields if needed, e.g., color schemes
 }
@@ -15,6 +19,21 @@ impl ConsoleHandler {
 
 #[async_trait]
 impl LogHandler for ConsoleHandler {
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    async fn emit(&self, formatted: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        use web_sys::console;
+        let message = formatted.into();
+        match level_of(formatted) {
+            Some("TRACE" | "DEBUG") => console::debug_1(&message),
+            Some("INFO") => console::info_1(&message),
+            Some("WARN") => console::warn_1(&message),
+            Some("ERROR" | "FATAL") => console::error_1(&message),
+            _ => console::log_1(&message),
+        }
+        Ok(())
+    }
+
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
     async fn emit(&self, formatted: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         // Simple color-coding based on log level
         if let Some(start) = formatted.find('[') {
@@ -37,4 +56,23 @@ impl LogHandler for ConsoleHandler {
         }
         Ok(())
     }
+
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    async fn flush(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        use std::io::Write;
+        std::io::stdout().flush()?;
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "console"
+    }
+}
+
+/// Returns the level name in the first `[...]` of a formatted message.
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+fn level_of(formatted: &str) -> Option<&str> {
+    let start = formatted.find('[')? + 1;
+    let end = formatted[start..].find(']')?;
+    Some(&formatted[start..start + end])
 }
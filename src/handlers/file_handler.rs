@@ -83,9 +83,25 @@ impl LogHandler for FileHandler {
         let bytes = formatted.as_bytes();
         file.write_all(bytes).await?;
         file.write_all(b"\n").await?;
+        // tokio completes writes in the background; wait so the record is on disk
+        file.flush().await?;
 
         let mut size = self.current_size.lock().await;
         *size += bytes.len() as u64 + 1; // +1 for newline
         Ok(())
     }
+
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    /// Checks that the log file can be opened for appending.
+    async fn health_check(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file_path)
+            .await?;
+        Ok(())
+    }
 }
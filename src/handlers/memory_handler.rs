@@ -32,6 +32,13 @@ impl MemoryHandler {
         let buf = self.buffer.lock().await;
         buf.iter().cloned().collect()
     }
+
+    /// Retrieves a copy of the current logs without waiting, or `None` while a message is
+    /// being added, for callers that cannot await such as game loop systems.
+    pub fn try_get_logs(&self) -> Option<Vec<String>> {
+        let buf = self.buffer.try_lock().ok()?;
+        Some(buf.iter().cloned().collect())
+    }
 }
 
 #[async_trait]
@@ -44,4 +51,8 @@ impl LogHandler for MemoryHandler {
         buf.push_back(formatted.to_string());
         Ok(())
     }
+
+    fn name(&self) -> &str {
+        "memory"
+    }
 }
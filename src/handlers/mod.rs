@@ -1,6 +1,8 @@
 pub mod console_handler;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod file_handler;
 pub mod memory_handler;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod remote_handler;
 
 use async_trait::async_trait;
@@ -10,9 +12,33 @@ use async_trait::async_trait;
 pub trait LogHandler: Send + Sync {
     /// Emits a formatted log message to the handler's destination.
     async fn emit(&self, formatted: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Returns a short name identifying the handler type.
+    fn name(&self) -> &str {
+        "custom"
+    }
+
+    /// Returns how many times the handler has retried a write since it was created.
+    fn retry_count(&self) -> u64 {
+        0
+    }
+
+    /// Writes out anything the handler has buffered. Called before the process may exit,
+    /// e.g. after a FATAL record; the default does nothing.
+    async fn flush(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    /// Checks that the destination is reachable without writing a record. Called
+    /// periodically by the Logger; the default reports the handler as healthy.
+    async fn health_check(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
 }
 
 pub use console_handler::ConsoleHandler;
+#[cfg(not(target_arch = "wasm32"))]
 pub use file_handler::FileHandler;
 pub use memory_handler::MemoryHandler;
+#[cfg(not(target_arch = "wasm32"))]
 pub use remote_handler::RemoteHandler;
@@ -1,5 +1,6 @@
 use super::LogHandler;
 use async_trait::async_trait;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 use thiserror::Error;
 use tokio::io::AsyncWriteExt;
@@ -19,6 +20,7 @@ pub struct RemoteHandler {
     address: String,
     port: u16,
     retries: usize,
+    retried: AtomicU64,
 }
 
 impl RemoteHandler {
@@ -28,6 +30,7 @@ impl RemoteHandler {
             address,
             port,
             retries: retries.unwrap_or(3),
+            retried: AtomicU64::new(0),
         }
     }
 
@@ -35,6 +38,9 @@ impl RemoteHandler {
     async fn send_with_retries(&self, message: &str) -> Result<(), RemoteHandlerError> {
         let mut attempt = 0;
         while attempt < self.retries {
+            if attempt > 0 {
+                self.retried.fetch_add(1, Ordering::Relaxed);
+            }
             match TcpStream::connect((&*self.address, self.port)).await {
                 Ok(mut stream) => {
                     if let Err(_e) = stream.write_all(message.as_bytes()).await {
@@ -61,4 +67,20 @@ impl LogHandler for RemoteHandler {
             .await
             .map_err(|e| Box::new(e) as _)
     }
+
+    fn name(&self) -> &str {
+        "remote"
+    }
+
+    fn retry_count(&self) -> u64 {
+        self.retried.load(Ordering::Relaxed)
+    }
+
+    /// Checks that the server accepts connections.
+    async fn health_check(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        TcpStream::connect((&*self.address, self.port))
+            .await
+            .map(|_| ())
+            .map_err(|e| Box::new(RemoteHandlerError::ConnectionError(e.to_string())) as _)
+    }
 }
@@ -0,0 +1,102 @@
+use super::requests::{log_failed, log_finished, log_started, request_fields, REQUEST_ID_HEADER};
+use super::traceparent::TRACEPARENT_HEADER;
+use crate::context::{context, scope};
+use crate::logger::Logger;
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::Error;
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+/// actix-web middleware logging every HTTP request, the counterpart of the `axum`
+/// feature's `RequestLogLayer` with the same records and request id handling:
+///
+/// ```ignore
+/// App::new().wrap(RequestLogger::new(logger.clone())).service(index)
+/// ```
+#[derive(Clone)]
+pub struct RequestLogger {
+    logger: Arc<Logger>,
+}
+
+impl RequestLogger {
+    pub fn new(logger: Arc<Logger>) -> Self {
+        RequestLogger { logger }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestLogger
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestLoggerMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestLoggerMiddleware {
+            service,
+            logger: self.logger.clone(),
+        }))
+    }
+}
+
+/// The service built by [`RequestLogger`].
+pub struct RequestLoggerMiddleware<S> {
+    service: S,
+    logger: Arc<Logger>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestLoggerMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let header = |name| request.headers().get(name).and_then(|v: &HeaderValue| v.to_str().ok());
+        let fields = request_fields(header(REQUEST_ID_HEADER), header(TRACEPARENT_HEADER));
+        let request_id = fields["request_id"].as_str().unwrap_or_default().to_string();
+        let method = request.method().to_string();
+        let path = request.path().to_string();
+        let logger = self.logger.clone();
+        let started = Instant::now();
+
+        // Covers the start record and anything the service logs while creating its future
+        let response = {
+            let _guards: Vec<_> = fields.iter().map(|(key, value)| context().insert(key, value.clone())).collect();
+            log_started(&logger, &method, &path);
+            self.service.call(request)
+        };
+
+        Box::pin(scope(fields, async move {
+            let mut result = response.await;
+            match &mut result {
+                Ok(response) => {
+                    if let Ok(value) = HeaderValue::from_str(&request_id) {
+                        response
+                            .headers_mut()
+                            .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+                    }
+                    log_finished(&logger, &method, &path, response.status().as_u16(), started);
+                }
+                Err(e) => log_failed(&logger, &method, &path, e, started),
+            }
+            result
+        }))
+    }
+}
@@ -0,0 +1,130 @@
+use super::TracingLayer;
+use crate::handlers::MemoryHandler;
+use crate::logger::Logger;
+use bevy_app::{App, Plugin};
+use bevy_ecs::error::Result;
+use bevy_ecs::resource::Resource;
+use bevy_ecs::system::{Res, ResMut};
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+use bevy_input::keyboard::KeyCode;
+use bevy_input::ButtonInput;
+use bevy_log::BoxedLayer;
+use std::sync::Arc;
+
+/// Number of records kept for the in-game console by default.
+pub const DEFAULT_CONSOLE_CAPACITY: usize = 1000;
+
+/// The Logger, as a Bevy resource for systems to log through.
+#[derive(Resource, Clone)]
+pub struct LogEngine(pub Arc<Logger>);
+
+/// Bevy plugin installing the Logger as the [`LogEngine`] resource:
+///
+/// ```ignore
+/// App::new()
+///     .add_plugins(LogEnginePlugin::new(logger.clone()).console(DEFAULT_CONSOLE_CAPACITY))
+///     .add_plugins(DefaultPlugins.set(LogPlugin { custom_layer: log_engine_layer, ..default() }))
+///     .add_plugins(EguiPlugin::default())
+///     .run();
+/// ```
+///
+/// Bevy's own `tracing` output is routed to the Logger by [`log_engine_layer`], which
+/// needs the plugin to be added before Bevy's `LogPlugin`. With
+/// [`console`](LogEnginePlugin::console), records are also kept by a
+/// [`MemoryHandler`] and shown in an egui window toggled with the backquote key. The
+/// console shows records as formatted, so it is meant for Loggers writing plaintext.
+pub struct LogEnginePlugin {
+    logger: Arc<Logger>,
+    console: Option<usize>,
+}
+
+impl LogEnginePlugin {
+    pub fn new(logger: Arc<Logger>) -> Self {
+        LogEnginePlugin { logger, console: None }
+    }
+
+    /// Adds the in-game log console, keeping the last `capacity` records.
+    pub fn console(mut self, capacity: usize) -> Self {
+        self.console = Some(capacity);
+        self
+    }
+}
+
+impl Plugin for LogEnginePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LogEngine(self.logger.clone()));
+        if let Some(capacity) = self.console {
+            let records = Arc::new(MemoryHandler::new(capacity));
+            self.logger.add_handler(records.clone());
+            app.insert_resource(LogConsole {
+                records,
+                lines: Vec::new(),
+                open: false,
+                toggle_key: Some(KeyCode::Backquote),
+                filter: String::new(),
+            })
+            .add_systems(EguiPrimaryContextPass, log_console);
+        }
+    }
+}
+
+/// For `LogPlugin::custom_layer`: a [`TracingLayer`] passing Bevy's `tracing` events to
+/// the Logger of the [`LogEngine`] resource.
+pub fn log_engine_layer(app: &mut App) -> Option<BoxedLayer> {
+    let logger = app.world().get_resource::<LogEngine>()?.0.clone();
+    Some(Box::new(TracingLayer::new(logger)))
+}
+
+/// State of the in-game log console, added by [`LogEnginePlugin::console`].
+#[derive(Resource)]
+pub struct LogConsole {
+    records: Arc<MemoryHandler>,
+    /// Records shown, refreshed from the handler every frame the console is open.
+    lines: Vec<String>,
+    /// Whether the console window is shown.
+    pub open: bool,
+    /// Key toggling the console, or `None` to only toggle it through [`open`](Self::open).
+    pub toggle_key: Option<KeyCode>,
+    /// Only records containing this text are shown.
+    pub filter: String,
+}
+
+impl LogConsole {
+    /// The handler keeping the console's records, the oldest dropped once it is full.
+    pub fn records(&self) -> &Arc<MemoryHandler> {
+        &self.records
+    }
+}
+
+fn log_console(mut contexts: EguiContexts, mut console: ResMut<LogConsole>, keys: Option<Res<ButtonInput<KeyCode>>>) -> Result {
+    if let (Some(key), Some(keys)) = (console.toggle_key, keys) {
+        if keys.just_pressed(key) {
+            console.open = !console.open;
+        }
+    }
+    if !console.open {
+        return Ok(());
+    }
+    // Keep the previous frame's lines while a record is being added
+    if let Some(lines) = console.records.try_get_logs() {
+        console.lines = lines;
+    }
+
+    let console = &mut *console;
+    egui::Window::new("Log console")
+        .open(&mut console.open)
+        .default_size([720.0, 360.0])
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Filter");
+                ui.text_edit_singleline(&mut console.filter);
+            });
+            ui.separator();
+            egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+                for line in console.lines.iter().filter(|line| line.contains(console.filter.as_str())) {
+                    ui.monospace(line);
+                }
+            });
+        });
+    Ok(())
+}
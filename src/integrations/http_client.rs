@@ -0,0 +1,116 @@
+use super::traceparent::{current_traceparent, TRACEPARENT_HEADER};
+use crate::logger::Logger;
+use crate::utils::LogLevel;
+use async_trait::async_trait;
+use http::{Extensions, HeaderValue};
+use reqwest::{Request, Response, Url};
+use reqwest_middleware::{Middleware, Next, Result};
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// `reqwest-middleware` middleware logging outbound HTTP calls:
+///
+/// ```ignore
+/// let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+///     .with(RetryTransientMiddleware::new_with_policy(policy))
+///     .with(HttpClientLogger::new(logger.clone()))
+///     .build();
+/// ```
+///
+/// Each call is logged with its method, URL, status and latency, at WARN for 4xx and
+/// ERROR for 5xx responses and failed requests. The URL is logged without credentials or
+/// query string; the query parameters are logged separately under `query`, after the
+/// Logger's [`SecurityManager`](crate::security::SecurityManager) has redacted denied keys
+/// such as `token` and applied its rules to the values. Placed after a retry middleware,
+/// as above, every attempt is logged with the number of `retries` before it.
+///
+/// When a trace is bound in the context, e.g. by
+/// [`continue_trace`](super::continue_trace), requests without a `traceparent` header are
+/// sent with one from [`current_traceparent`], so the next service continues the trace.
+pub struct HttpClientLogger {
+    logger: Arc<Logger>,
+}
+
+impl HttpClientLogger {
+    pub fn new(logger: Arc<Logger>) -> Self {
+        HttpClientLogger { logger }
+    }
+
+    /// Splits `url` into a loggable URL and its sanitized query parameters.
+    fn sanitize(&self, url: &Url) -> (String, Map<String, Value>) {
+        let mut query = Value::Object(
+            url.query_pairs()
+                .map(|(key, value)| (key.into_owned(), Value::String(value.into_owned())))
+                .collect(),
+        );
+        self.logger.security().scrub("", &mut query);
+
+        let mut url = url.clone();
+        url.set_query(None);
+        url.set_fragment(None);
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+        match query {
+            Value::Object(query) => (url.to_string(), query),
+            _ => (url.to_string(), Map::new()),
+        }
+    }
+}
+
+/// Times a request has passed through the middleware, across retries.
+#[derive(Clone)]
+struct Attempts(u32);
+
+#[async_trait]
+impl Middleware for HttpClientLogger {
+    async fn handle(&self, mut request: Request, extensions: &mut Extensions, next: Next<'_>) -> Result<Response> {
+        let retries = match extensions.get_mut::<Attempts>() {
+            Some(Attempts(attempts)) => {
+                *attempts += 1;
+                *attempts - 1
+            }
+            None => {
+                extensions.insert(Attempts(1));
+                0
+            }
+        };
+        if !request.headers().contains_key(TRACEPARENT_HEADER) {
+            if let Some(value) = current_traceparent().and_then(|header| HeaderValue::from_str(&header).ok()) {
+                request.headers_mut().insert(TRACEPARENT_HEADER, value);
+            }
+        }
+        let method = request.method().to_string();
+        let (url, query) = self.sanitize(request.url());
+        let started = Instant::now();
+
+        let result = next.run(request, extensions).await;
+
+        let mut metadata = json!({
+            "method": method,
+            "url": url,
+            "latency_ms": started.elapsed().as_secs_f64() * 1000.0,
+            "retries": retries,
+        });
+        if !query.is_empty() {
+            metadata["query"] = Value::Object(query);
+        }
+        match &result {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                let level = match status {
+                    500.. => LogLevel::ERROR,
+                    400..=499 => LogLevel::WARN,
+                    _ => LogLevel::INFO,
+                };
+                metadata["status"] = json!(status);
+                self.logger.log(level, "http request", Some(metadata));
+            }
+            Err(e) => {
+                metadata["error"] = json!(e.to_string());
+                self.logger.error("http request failed", Some(metadata));
+            }
+        }
+        result
+    }
+}
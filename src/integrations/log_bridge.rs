@@ -0,0 +1,64 @@
+use crate::logger::{Location, Logger, FATAL_FLUSH_TIMEOUT};
+use crate::utils::LogLevel;
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use std::sync::Arc;
+
+/// The `log` facade's logger, sending records of `log::info!` and friends, including those
+/// of dependencies, through a Logger. Levels are checked against the Logger's
+/// configuration, so changes to it apply to facade records too.
+pub struct LogBridge {
+    logger: Arc<Logger>,
+}
+
+impl LogBridge {
+    pub fn new(logger: Arc<Logger>) -> Self {
+        LogBridge { logger }
+    }
+
+    /// Installs a bridge to `logger` as the facade's logger. Fails if the facade already
+    /// has one, as it can only be set once per process.
+    pub fn init(logger: Arc<Logger>) -> Result<(), SetLoggerError> {
+        log::set_boxed_logger(Box::new(LogBridge::new(logger)))?;
+        log::set_max_level(LevelFilter::Trace);
+        Ok(())
+    }
+}
+
+fn log_level(level: Level) -> LogLevel {
+    match level {
+        Level::Error => LogLevel::ERROR,
+        Level::Warn => LogLevel::WARN,
+        Level::Info => LogLevel::INFO,
+        Level::Debug => LogLevel::DEBUG,
+        Level::Trace => LogLevel::TRACE,
+    }
+}
+
+impl Log for LogBridge {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.logger.enabled(log_level(metadata.level()))
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        let level = log_level(record.level());
+        if !self.logger.enabled(level) {
+            return;
+        }
+        // An explicit target, as in `log::info!(target: "db", ..)`, is filtered on instead
+        // of the module, as by env_logger
+        let location = record
+            .module_path_static()
+            .filter(|module| *module == record.target())
+            .map(|module| Location {
+                module,
+                file: record.file_static().unwrap_or_default(),
+                line: record.line().unwrap_or_default(),
+            });
+        let metadata = serde_json::json!({ "target": record.target() });
+        self.logger.log_at(level, &record.args().to_string(), Some(metadata), location);
+    }
+
+    fn flush(&self) {
+        self.logger.flush_blocking(FATAL_FLUSH_TIMEOUT);
+    }
+}
@@ -0,0 +1,36 @@
+//! Adapters between the Logger and other logging, web and database crates. Those
+//! depending on another crate are behind a feature of the same name.
+
+#[cfg(feature = "actix")]
+mod actix;
+#[cfg(feature = "bevy_log_engine")]
+mod bevy;
+#[cfg(feature = "reqwest-middleware")]
+mod http_client;
+#[cfg(feature = "log")]
+mod log_bridge;
+mod query;
+#[cfg(any(feature = "axum", feature = "actix"))]
+mod requests;
+#[cfg(feature = "axum")]
+mod tower_layer;
+mod traceparent;
+#[cfg(feature = "tracing")]
+mod tracing_layer;
+
+#[cfg(feature = "actix")]
+pub use actix::{RequestLogger, RequestLoggerMiddleware};
+#[cfg(feature = "bevy_log_engine")]
+pub use bevy::{log_engine_layer, LogConsole, LogEngine, LogEnginePlugin, DEFAULT_CONSOLE_CAPACITY};
+#[cfg(feature = "reqwest-middleware")]
+pub use http_client::HttpClientLogger;
+#[cfg(feature = "log")]
+pub use log_bridge::LogBridge;
+pub use query::{normalize_sql, QueryEvent, QueryLogger};
+#[cfg(any(feature = "axum", feature = "actix"))]
+pub use requests::REQUEST_ID_HEADER;
+#[cfg(feature = "axum")]
+pub use tower_layer::{RequestLogLayer, RequestLogService};
+pub use traceparent::{continue_trace, current_traceparent, TraceParent, TRACEPARENT_HEADER};
+#[cfg(feature = "tracing")]
+pub use tracing_layer::TracingLayer;
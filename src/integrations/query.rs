@@ -0,0 +1,273 @@
+use crate::logger::Logger;
+use crate::utils::LogLevel;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// An executed SQL statement, as reported to [`QueryLogger::log`].
+#[derive(Debug, Clone)]
+pub struct QueryEvent<'a> {
+    pub sql: &'a str,
+    /// Bind parameters: an array of positional values or an object of named ones.
+    pub params: Option<Value>,
+    pub duration: Duration,
+    pub rows_affected: Option<u64>,
+    pub rows_returned: Option<u64>,
+}
+
+impl<'a> QueryEvent<'a> {
+    pub fn new(sql: &'a str, duration: Duration) -> Self {
+        QueryEvent {
+            sql,
+            params: None,
+            duration,
+            rows_affected: None,
+            rows_returned: None,
+        }
+    }
+
+    pub fn params(mut self, params: Value) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    pub fn rows_affected(mut self, rows: u64) -> Self {
+        self.rows_affected = Some(rows);
+        self
+    }
+
+    pub fn rows_returned(mut self, rows: u64) -> Self {
+        self.rows_returned = Some(rows);
+        self
+    }
+}
+
+/// Logs SQL statements as structured records: the statement normalized by
+/// [`normalize_sql`] under `statement`, `duration_ms`, the row counts, and the bind
+/// parameters under `params` once the Logger's
+/// [`SecurityManager`](crate::security::SecurityManager) has redacted denied keys and
+/// applied its rules to them.
+///
+/// Database layers can report statements with [`log`](QueryLogger::log), e.g. from a
+/// diesel `Instrumentation`. With the `sqlx` feature, the QueryLogger is also a
+/// `tracing-subscriber` layer picking up sqlx's own statement logging:
+///
+/// ```ignore
+/// tracing_subscriber::registry().with(QueryLogger::new(logger.clone())).init();
+/// ```
+pub struct QueryLogger {
+    logger: Arc<Logger>,
+    level: LogLevel,
+    slow_threshold: Option<Duration>,
+}
+
+impl QueryLogger {
+    /// Creates a QueryLogger logging statements at DEBUG.
+    pub fn new(logger: Arc<Logger>) -> Self {
+        QueryLogger {
+            logger,
+            level: LogLevel::DEBUG,
+            slow_threshold: None,
+        }
+    }
+
+    pub fn level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Logs statements taking longer than `threshold` at WARN, marked `slow`.
+    pub fn slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = Some(threshold);
+        self
+    }
+
+    pub fn log(&self, query: QueryEvent<'_>) {
+        let slow = self.slow_threshold.is_some_and(|threshold| query.duration > threshold);
+        let level = if slow { self.level.max(LogLevel::WARN) } else { self.level };
+        if !self.logger.enabled(level) {
+            return;
+        }
+
+        let mut metadata = json!({
+            "statement": normalize_sql(query.sql),
+            "duration_ms": query.duration.as_secs_f64() * 1000.0,
+        });
+        if let Some(rows) = query.rows_affected {
+            metadata["rows_affected"] = json!(rows);
+        }
+        if let Some(rows) = query.rows_returned {
+            metadata["rows_returned"] = json!(rows);
+        }
+        if let Some(mut params) = query.params {
+            self.logger.security().scrub("", &mut params);
+            metadata["params"] = params;
+        }
+        if slow {
+            metadata["slow"] = json!(true);
+            self.logger.log(level, "slow sql query", Some(metadata));
+        } else {
+            self.logger.log(level, "sql query", Some(metadata));
+        }
+    }
+}
+
+/// Normalizes a SQL statement so executions of the same query look alike: comments are
+/// removed, whitespace collapsed, and string and numeric literals replaced by `?`.
+/// Placeholders such as `$1` and quoted identifiers are kept.
+pub fn normalize_sql(sql: &str) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut normalized = String::with_capacity(sql.len());
+    let mut space = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c == '-' && next == Some('-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            space = true;
+            continue;
+        }
+        if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+            space = true;
+            continue;
+        }
+        if c.is_whitespace() {
+            space = true;
+            i += 1;
+            continue;
+        }
+        if space && !normalized.is_empty() {
+            normalized.push(' ');
+        }
+        space = false;
+
+        let previous = i.checked_sub(1).map(|j| chars[j]);
+        match c {
+            '\'' => {
+                // A string literal, with '' as an escaped quote
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == '\'' {
+                        if chars.get(i + 1) != Some(&'\'') {
+                            break;
+                        }
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 1;
+                normalized.push('?');
+            }
+            '"' | '`' => {
+                normalized.push(c);
+                i += 1;
+                while i < chars.len() && chars[i] != c {
+                    normalized.push(chars[i]);
+                    i += 1;
+                }
+                if i < chars.len() {
+                    normalized.push(c);
+                }
+                i += 1;
+            }
+            c if c.is_ascii_digit() && !previous.is_some_and(|p| is_word(p) || p == '$') => {
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                    i += 1;
+                }
+                normalized.push('?');
+            }
+            c if is_word(c) || c == '$' => {
+                while i < chars.len() && (is_word(chars[i]) || chars[i] == '$') {
+                    normalized.push(chars[i]);
+                    i += 1;
+                }
+            }
+            c => {
+                normalized.push(c);
+                i += 1;
+            }
+        }
+    }
+    normalized
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(feature = "sqlx")]
+mod sqlx {
+    use super::{QueryEvent, QueryLogger};
+    use std::fmt;
+    use std::time::Duration;
+    use tracing::field::{Field, Visit};
+    use tracing::{Event, Subscriber};
+    use tracing_subscriber::layer::Context;
+    use tracing_subscriber::Layer;
+
+    /// Target of sqlx's statement logging.
+    const SQLX_TARGET: &str = "sqlx::query";
+
+    /// Fields of a sqlx statement event.
+    #[derive(Default)]
+    struct SqlxFields {
+        summary: String,
+        statement: String,
+        elapsed_secs: f64,
+        rows_affected: Option<u64>,
+        rows_returned: Option<u64>,
+    }
+
+    impl Visit for SqlxFields {
+        fn record_f64(&mut self, field: &Field, value: f64) {
+            if field.name() == "elapsed_secs" {
+                self.elapsed_secs = value;
+            }
+        }
+
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            match field.name() {
+                "rows_affected" => self.rows_affected = Some(value),
+                "rows_returned" => self.rows_returned = Some(value),
+                _ => {}
+            }
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            match field.name() {
+                "summary" => self.summary = value.to_string(),
+                "db.statement" => self.statement = value.to_string(),
+                _ => {}
+            }
+        }
+
+        fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+    }
+
+    impl<S: Subscriber> Layer<S> for QueryLogger {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            if event.metadata().target() != SQLX_TARGET {
+                return;
+            }
+            let mut fields = SqlxFields::default();
+            event.record(&mut fields);
+            // sqlx leaves the statement empty when the summary is the whole of it
+            let sql = match fields.statement.trim() {
+                "" => fields.summary.as_str(),
+                statement => statement,
+            };
+            let mut query = QueryEvent::new(sql, Duration::from_secs_f64(fields.elapsed_secs.max(0.0)));
+            query.rows_affected = fields.rows_affected;
+            query.rows_returned = fields.rows_returned;
+            self.log(query);
+        }
+    }
+}
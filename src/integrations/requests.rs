@@ -0,0 +1,65 @@
+use crate::logger::Logger;
+use crate::utils::LogLevel;
+use super::traceparent::continue_trace;
+use serde_json::{json, Map, Value};
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Header carrying the request id: read from the request when present, generated
+/// otherwise, and set on the response.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest incoming request id accepted; longer ones are replaced by a generated id.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Returns the caller's request id if it is usable, or a fresh one.
+fn request_id(incoming: Option<&str>) -> String {
+    incoming
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN)
+        .filter(|id| id.bytes().all(|b| b.is_ascii_graphic()))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+/// Returns the context fields for a request: its `request_id`, plus the caller's trace
+/// when it sent a valid `traceparent` header.
+pub(crate) fn request_fields(request_id_header: Option<&str>, traceparent: Option<&str>) -> Map<String, Value> {
+    let mut fields = match traceparent {
+        Some(header) if super::TraceParent::parse(header).is_some() => continue_trace(Some(header)),
+        _ => Map::new(),
+    };
+    fields.insert("request_id".to_string(), Value::String(request_id(request_id_header)));
+    fields
+}
+
+pub(crate) fn log_started(logger: &Logger, method: &str, path: &str) {
+    logger.info("request started", Some(json!({ "method": method, "path": path })));
+}
+
+/// Logs the response at INFO, or WARN for client and ERROR for server errors.
+pub(crate) fn log_finished(logger: &Logger, method: &str, path: &str, status: u16, started: Instant) {
+    let level = match status {
+        500.. => LogLevel::ERROR,
+        400..=499 => LogLevel::WARN,
+        _ => LogLevel::INFO,
+    };
+    let metadata = json!({
+        "method": method,
+        "path": path,
+        "status": status,
+        "latency_ms": started.elapsed().as_secs_f64() * 1000.0,
+    });
+    logger.log(level, "request finished", Some(metadata));
+}
+
+/// Logs a request the service failed to produce a response for.
+pub(crate) fn log_failed(logger: &Logger, method: &str, path: &str, error: &dyn std::fmt::Display, started: Instant) {
+    let metadata = json!({
+        "method": method,
+        "path": path,
+        "error": error.to_string(),
+        "latency_ms": started.elapsed().as_secs_f64() * 1000.0,
+    });
+    logger.error("request failed", Some(metadata));
+}
@@ -0,0 +1,101 @@
+use super::requests::{log_failed, log_finished, log_started, request_fields, REQUEST_ID_HEADER};
+use super::traceparent::TRACEPARENT_HEADER;
+use crate::context::{context, scope};
+use crate::logger::Logger;
+use http::{HeaderValue, Request, Response};
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// A `tower` layer logging every HTTP request, for axum and other tower-based servers:
+///
+/// ```ignore
+/// let app = Router::new().route("/", get(handler)).layer(RequestLogLayer::new(logger.clone()));
+/// ```
+///
+/// Each request is logged when it starts and when its response is ready, with the method,
+/// path, status and latency; responses with a 4xx status are logged at WARN and 5xx at
+/// ERROR. The request id from the [`REQUEST_ID_HEADER`] header, or a generated one, is
+/// put in the diagnostic context under `request_id`, so every record logged while
+/// handling the request carries it, and is returned in the response's header. A valid
+/// W3C `traceparent` header continues the caller's trace, as by
+/// [`continue_trace`](super::continue_trace).
+#[derive(Clone)]
+pub struct RequestLogLayer {
+    logger: Arc<Logger>,
+}
+
+impl RequestLogLayer {
+    pub fn new(logger: Arc<Logger>) -> Self {
+        RequestLogLayer { logger }
+    }
+}
+
+impl<S> Layer<S> for RequestLogLayer {
+    type Service = RequestLogService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestLogService {
+            inner,
+            logger: self.logger.clone(),
+        }
+    }
+}
+
+/// The service built by [`RequestLogLayer`].
+#[derive(Clone)]
+pub struct RequestLogService<S> {
+    inner: S,
+    logger: Arc<Logger>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for RequestLogService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+    S::Error: Display,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let header = |name| request.headers().get(name).and_then(|v: &HeaderValue| v.to_str().ok());
+        let fields = request_fields(header(REQUEST_ID_HEADER), header(TRACEPARENT_HEADER));
+        let request_id = fields["request_id"].as_str().unwrap_or_default().to_string();
+        let method = request.method().to_string();
+        let path = request.uri().path().to_string();
+        let logger = self.logger.clone();
+        let started = Instant::now();
+
+        // Covers the start record and anything the service logs while creating its future
+        let response = {
+            let _guards: Vec<_> = fields.iter().map(|(key, value)| context().insert(key, value.clone())).collect();
+            log_started(&logger, &method, &path);
+            self.inner.call(request)
+        };
+
+        Box::pin(scope(fields, async move {
+            let mut result = response.await;
+            match &mut result {
+                Ok(response) => {
+                    if let Ok(value) = HeaderValue::from_str(&request_id) {
+                        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+                    }
+                    log_finished(&logger, &method, &path, response.status().as_u16(), started);
+                }
+                Err(e) => log_failed(&logger, &method, &path, e, started),
+            }
+            result
+        }))
+    }
+}
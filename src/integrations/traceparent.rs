@@ -0,0 +1,109 @@
+use crate::context::context;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Header carrying the W3C trace context between services.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// A parsed W3C `traceparent` header, `00-<trace-id>-<parent-id>-<trace-flags>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceParent {
+    /// 32 lowercase hex digits.
+    pub trace_id: String,
+    /// Id of the caller's span, 16 lowercase hex digits.
+    pub parent_id: String,
+    pub flags: u8,
+}
+
+impl TraceParent {
+    /// Parses a header value, returning `None` if it is malformed or uses the invalid
+    /// all-zero ids. Versions after `00` are accepted as long as they start with the
+    /// `00` fields.
+    pub fn parse(header: &str) -> Option<TraceParent> {
+        let mut parts = header.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let parent_id = parts.next()?;
+        let flags = parts.next()?;
+        if !is_hex(version, 2) || version == "ff" || (version == "00" && parts.next().is_some()) {
+            return None;
+        }
+        if !is_hex(trace_id, 32) || !is_hex(parent_id, 16) || !is_hex(flags, 2) {
+            return None;
+        }
+        if is_zero(trace_id) || is_zero(parent_id) {
+            return None;
+        }
+        Some(TraceParent {
+            trace_id: trace_id.to_string(),
+            parent_id: parent_id.to_string(),
+            flags: u8::from_str_radix(flags, 16).ok()?,
+        })
+    }
+
+    /// Whether the caller recorded its part of the trace.
+    pub fn sampled(&self) -> bool {
+        self.flags & 0x01 != 0
+    }
+}
+
+impl fmt::Display for TraceParent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "00-{}-{}-{:02x}", self.trace_id, self.parent_id, self.flags)
+    }
+}
+
+/// Returns the context fields for work done on behalf of a caller that sent `header`:
+/// its `trace_id` and `trace_flags`, the caller's span as `parent_span_id`, and a new
+/// `span_id`. Without a valid header a new sampled trace is started. Bind them with
+/// [`scope`](crate::context::scope) so every record carries them and
+/// [`current_traceparent`] can pass the trace on.
+pub fn continue_trace(header: Option<&str>) -> Map<String, Value> {
+    let mut fields = Map::new();
+    match header.and_then(TraceParent::parse) {
+        Some(parent) => {
+            fields.insert("trace_id".to_string(), Value::String(parent.trace_id));
+            fields.insert("parent_span_id".to_string(), Value::String(parent.parent_id));
+            fields.insert("trace_flags".to_string(), Value::String(format!("{:02x}", parent.flags)));
+        }
+        None => {
+            fields.insert("trace_id".to_string(), Value::String(random_id(16)));
+            fields.insert("trace_flags".to_string(), Value::String("01".to_string()));
+        }
+    }
+    fields.insert("span_id".to_string(), Value::String(random_id(8)));
+    fields
+}
+
+/// Renders the trace in the current context as a `traceparent` header value, with the
+/// current `span_id` as the parent, or `None` if no valid trace is in context.
+pub fn current_traceparent() -> Option<String> {
+    let field = |key: &str| match context().get(key) {
+        Some(Value::String(value)) => Some(value),
+        _ => None,
+    };
+    let flags = field("trace_flags").unwrap_or_else(|| "01".to_string());
+    let header = format!("00-{}-{}-{}", field("trace_id")?, field("span_id")?, flags);
+    TraceParent::parse(&header).map(|parent| parent.to_string())
+}
+
+fn is_hex(value: &str, len: usize) -> bool {
+    value.len() == len && value.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+fn is_zero(value: &str) -> bool {
+    value.bytes().all(|b| b == b'0')
+}
+
+/// A random non-zero id of `len` bytes, hex encoded.
+fn random_id(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    loop {
+        OsRng.fill_bytes(&mut bytes);
+        if bytes.iter().any(|&b| b != 0) {
+            return hex::encode(&bytes);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::logger::{Location, Logger};
+use crate::utils::LogLevel;
+use serde_json::{Map, Number, Value};
+use std::fmt;
+use std::sync::Arc;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// A `tracing-subscriber` layer sending `tracing` events through a Logger, so code
+/// instrumented with `tracing` gets the Logger's filters, handlers and security:
+///
+/// ```ignore
+/// use tracing_subscriber::prelude::*;
+///
+/// tracing_subscriber::registry().with(TracingLayer::new(logger.clone())).init();
+/// tracing::info!(user_id = 42, "user login");
+/// ```
+///
+/// An event's fields become metadata, along with the fields of the spans it happened in;
+/// event fields win over span fields and inner spans over outer ones. The event's module
+/// path, or its target when there is none, is used as its module for level overrides.
+pub struct TracingLayer {
+    logger: Arc<Logger>,
+}
+
+impl TracingLayer {
+    pub fn new(logger: Arc<Logger>) -> Self {
+        TracingLayer { logger }
+    }
+}
+
+/// Fields recorded on a span, kept in its extensions.
+struct SpanFields(Map<String, Value>);
+
+/// Collects field values as JSON, keeping the `message` field apart.
+#[derive(Default)]
+struct FieldVisitor {
+    fields: Map<String, Value>,
+    message: Option<String>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        self.fields.insert(field.name().to_string(), value);
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        let value = Number::from_f64(value).map_or(Value::Null, Value::Number);
+        self.insert(field, value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, Value::Bool(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+        } else {
+            self.insert(field, Value::String(value.to_string()));
+        }
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.insert(field, Value::String(value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
+        } else {
+            self.insert(field, Value::String(format!("{:?}", value)));
+        }
+    }
+}
+
+fn log_level(level: &Level) -> LogLevel {
+    match *level {
+        Level::TRACE => LogLevel::TRACE,
+        Level::DEBUG => LogLevel::DEBUG,
+        Level::INFO => LogLevel::INFO,
+        Level::WARN => LogLevel::WARN,
+        Level::ERROR => LogLevel::ERROR,
+    }
+}
+
+impl<S> Layer<S> for TracingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(SpanFields(visitor.fields));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = FieldVisitor::default();
+        values.record(&mut visitor);
+        let mut extensions = span.extensions_mut();
+        match extensions.get_mut::<SpanFields>() {
+            Some(SpanFields(fields)) => fields.extend(visitor.fields),
+            None => extensions.insert(SpanFields(visitor.fields)),
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let level = log_level(metadata.level());
+        if !self.logger.enabled(level) {
+            return;
+        }
+
+        let mut fields = Map::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(SpanFields(span_fields)) = span.extensions().get::<SpanFields>() {
+                    fields.extend(span_fields.clone());
+                }
+            }
+        }
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        fields.extend(visitor.fields);
+
+        let location = Location {
+            module: metadata.module_path().unwrap_or(metadata.target()),
+            file: metadata.file().unwrap_or_default(),
+            line: metadata.line().unwrap_or_default(),
+        };
+        let message = visitor.message.unwrap_or_default();
+        self.logger.log_at(level, &message, Some(Value::Object(fields)), Some(location));
+    }
+}
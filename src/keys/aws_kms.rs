@@ -0,0 +1,78 @@
+use super::{DataKey, KeyError, KeyProvider, Zeroizing};
+use async_trait::async_trait;
+use aws_sdk_kms::primitives::Blob;
+use aws_sdk_kms::types::DataKeySpec;
+use aws_sdk_kms::Client;
+
+/// Wraps data keys with an AWS KMS key.
+pub struct AwsKmsKeyProvider {
+    client: Client,
+    key_id: String,
+}
+
+impl AwsKmsKeyProvider {
+    /// Initializes the provider with a KMS client and the id, ARN, or alias of the KMS key.
+    pub fn new(client: Client, key_id: &str) -> Self {
+        AwsKmsKeyProvider {
+            client,
+            key_id: key_id.to_string(),
+        }
+    }
+
+    /// Initializes the provider with a client configured from the environment.
+    pub async fn from_env(key_id: &str) -> Self {
+        let config = aws_config::load_from_env().await;
+        AwsKmsKeyProvider::new(Client::new(&config), key_id)
+    }
+}
+
+#[async_trait]
+impl KeyProvider for AwsKmsKeyProvider {
+    async fn generate_data_key(&self) -> Result<DataKey, KeyError> {
+        let output = self
+            .client
+            .generate_data_key()
+            .key_id(&self.key_id)
+            .key_spec(DataKeySpec::Aes256)
+            .send()
+            .await
+            .map_err(|e| KeyError::ProviderError(e.to_string()))?;
+        match (output.plaintext(), output.ciphertext_blob()) {
+            (Some(plaintext), Some(wrapped)) => Ok(DataKey {
+                plaintext: Zeroizing::new(plaintext.as_ref().to_vec()),
+                wrapped: wrapped.as_ref().to_vec(),
+            }),
+            _ => Err(KeyError::ProviderError("KMS returned an incomplete data key".into())),
+        }
+    }
+
+    async fn wrap(&self, key: &[u8]) -> Result<Vec<u8>, KeyError> {
+        let output = self
+            .client
+            .encrypt()
+            .key_id(&self.key_id)
+            .plaintext(Blob::new(key))
+            .send()
+            .await
+            .map_err(|e| KeyError::ProviderError(e.to_string()))?;
+        output
+            .ciphertext_blob()
+            .map(|blob| blob.as_ref().to_vec())
+            .ok_or_else(|| KeyError::ProviderError("KMS returned no ciphertext".into()))
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<Zeroizing<Vec<u8>>, KeyError> {
+        let output = self
+            .client
+            .decrypt()
+            .key_id(&self.key_id)
+            .ciphertext_blob(Blob::new(wrapped))
+            .send()
+            .await
+            .map_err(|e| KeyError::ProviderError(e.to_string()))?;
+        output
+            .plaintext()
+            .map(|blob| Zeroizing::new(blob.as_ref().to_vec()))
+            .ok_or_else(|| KeyError::ProviderError("KMS returned no plaintext".into()))
+    }
+}
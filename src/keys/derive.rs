@@ -0,0 +1,68 @@
+use super::KeyError;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use argon2::{Algorithm, Argon2, Params, Version};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+/// Length of salts produced by [`generate_salt`].
+pub const SALT_LEN: usize = 16;
+
+/// Key derivation function turning a passphrase and salt into a 32-byte key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kdf {
+    /// Argon2id with the given memory cost (KiB), iterations, and lanes.
+    Argon2id {
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+    },
+    /// PBKDF2-HMAC-SHA256 with the given number of rounds.
+    Pbkdf2Sha256 { rounds: u32 },
+}
+
+impl Default for Kdf {
+    /// Argon2id with 19 MiB of memory and 2 iterations, the OWASP baseline.
+    fn default() -> Self {
+        Kdf::Argon2id {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+/// Generates a random salt to store alongside the logs.
+pub fn generate_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+/// Derives a 32-byte key from `passphrase` and `salt` (at least 8 bytes).
+pub fn derive_key(passphrase: &str, salt: &[u8], kdf: Kdf) -> Result<Zeroizing<[u8; 32]>, KeyError> {
+    if salt.len() < 8 {
+        return Err(KeyError::InvalidKey("Salt must be at least 8 bytes".into()));
+    }
+    let mut key = Zeroizing::new([0u8; 32]);
+    match kdf {
+        Kdf::Argon2id {
+            memory_kib,
+            iterations,
+            parallelism,
+        } => {
+            let params = Params::new(memory_kib, iterations, parallelism, Some(key.len()))
+                .map_err(|e| KeyError::InvalidKey(e.to_string()))?;
+            Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+                .map_err(|e| KeyError::InvalidKey(e.to_string()))?;
+        }
+        Kdf::Pbkdf2Sha256 { rounds } => {
+            if rounds == 0 {
+                return Err(KeyError::InvalidKey("PBKDF2 rounds must be positive".into()));
+            }
+            pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, rounds, key.as_mut());
+        }
+    }
+    Ok(key)
+}
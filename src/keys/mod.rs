@@ -0,0 +1,95 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::Aes256Gcm;
+use async_trait::async_trait;
+use thiserror::Error;
+
+mod derive;
+mod source;
+
+#[cfg(feature = "aws-kms")]
+mod aws_kms;
+#[cfg(feature = "vault")]
+mod vault;
+
+pub use derive::{derive_key, generate_salt, Kdf, SALT_LEN};
+pub use source::{KeySource, DEFAULT_KEYRING_USER};
+pub use zeroize::Zeroizing;
+
+#[cfg(feature = "aws-kms")]
+pub use aws_kms::AwsKmsKeyProvider;
+#[cfg(feature = "vault")]
+pub use vault::VaultKeyProvider;
+
+#[derive(Error, Debug)]
+pub enum KeyError {
+    #[error("Key provider error: {0}")]
+    ProviderError(String),
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+}
+
+/// A data key in plaintext, together with its form wrapped by the key provider. The
+/// plaintext is wiped from memory when dropped.
+pub struct DataKey {
+    pub plaintext: Zeroizing<Vec<u8>>,
+    pub wrapped: Vec<u8>,
+}
+
+/// Source of data keys for envelope encryption.
+///
+/// Log records are encrypted with a 32-byte data key; only its wrapped form needs to be
+/// stored, and it is unwrapped through the provider (e.g. a KMS) at startup.
+#[async_trait]
+pub trait KeyProvider: Send + Sync {
+    /// Fetches a fresh data key. By default a random key is generated locally and wrapped.
+    async fn generate_data_key(&self) -> Result<DataKey, KeyError> {
+        let plaintext = Zeroizing::new(Aes256Gcm::generate_key(&mut OsRng).to_vec());
+        let wrapped = self.wrap(&plaintext).await?;
+        Ok(DataKey { plaintext, wrapped })
+    }
+
+    /// Wraps a data key with the provider's master key.
+    async fn wrap(&self, key: &[u8]) -> Result<Vec<u8>, KeyError>;
+
+    /// Recovers a data key from its wrapped form.
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<Zeroizing<Vec<u8>>, KeyError>;
+}
+
+/// Wraps data keys locally with AES-256-GCM under a master key, e.g. one mounted as a secret.
+pub struct LocalKeyProvider {
+    cipher: Aes256Gcm,
+}
+
+impl LocalKeyProvider {
+    /// Initializes the provider with a 32-byte master key.
+    pub fn new(master_key: &[u8]) -> Result<Self, KeyError> {
+        let cipher = Aes256Gcm::new_from_slice(master_key)
+            .map_err(|_| KeyError::InvalidKey("Master key must be 32 bytes.".into()))?;
+        Ok(LocalKeyProvider { cipher })
+    }
+}
+
+#[async_trait]
+impl KeyProvider for LocalKeyProvider {
+    async fn wrap(&self, key: &[u8]) -> Result<Vec<u8>, KeyError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, key)
+            .map_err(|e| KeyError::ProviderError(e.to_string()))?;
+        let mut wrapped = nonce.to_vec();
+        wrapped.extend(ciphertext);
+        Ok(wrapped)
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<Zeroizing<Vec<u8>>, KeyError> {
+        if wrapped.len() < 12 {
+            return Err(KeyError::InvalidKey("Wrapped key is too short".into()));
+        }
+        let (nonce, ciphertext) = wrapped.split_at(12);
+        self.cipher
+            .decrypt(nonce.into(), ciphertext)
+            .map(Zeroizing::new)
+            .map_err(|_| KeyError::InvalidKey("Wrapped key failed authentication".into()))
+    }
+}
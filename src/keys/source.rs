@@ -0,0 +1,144 @@
+use super::{derive_key, Kdf, KeyError, KeyProvider};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use std::path::PathBuf;
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+/// User name keyring entries are stored under when the descriptor does not name one.
+pub const DEFAULT_KEYRING_USER: &str = "log-engine";
+
+/// Where the encryption key comes from.
+///
+/// Besides raw bytes and [`KeyProvider`]s, keys can be named by a descriptor string:
+///
+/// - `env:LOG_KEY` reads the environment variable `LOG_KEY`,
+/// - `file:/run/secrets/logkey` reads a file,
+/// - `keyring:service` or `keyring:service/user` reads the OS keyring (`os-keyring` feature).
+///
+/// Keys read from a descriptor are trimmed and used as base64 if they decode to exactly
+/// 32 bytes, and as raw bytes otherwise. Key bytes and passphrases are wiped from memory
+/// when the source is dropped.
+#[derive(Clone)]
+pub enum KeySource {
+    Bytes(Zeroizing<Vec<u8>>),
+    Env(String),
+    File(PathBuf),
+    Keyring { service: String, user: String },
+    /// A key derived from a passphrase and a stored salt.
+    Passphrase {
+        passphrase: Zeroizing<String>,
+        salt: Vec<u8>,
+        kdf: Kdf,
+    },
+    /// A data key obtained through a provider, unwrapping the given wrapped key if any.
+    Provider(Arc<dyn KeyProvider>, Option<Vec<u8>>),
+    /// A descriptor that could not be parsed; resolving it fails.
+    Invalid(String),
+}
+
+impl KeySource {
+    /// Derives the key from `passphrase` and `salt` with the default [`Kdf`] (Argon2id).
+    pub fn passphrase(passphrase: &str, salt: &[u8]) -> Self {
+        KeySource::Passphrase {
+            passphrase: Zeroizing::new(passphrase.to_string()),
+            salt: salt.to_vec(),
+            kdf: Kdf::default(),
+        }
+    }
+
+    /// Parses a key source descriptor such as `env:LOG_KEY`.
+    pub fn parse(descriptor: &str) -> Result<Self, KeyError> {
+        let (scheme, value) = descriptor
+            .split_once(':')
+            .ok_or_else(|| KeyError::InvalidKey(format!("Invalid key source: {}", descriptor)))?;
+        if value.is_empty() {
+            return Err(KeyError::InvalidKey(format!("Invalid key source: {}", descriptor)));
+        }
+        match scheme {
+            "env" => Ok(KeySource::Env(value.to_string())),
+            "file" => Ok(KeySource::File(PathBuf::from(value))),
+            "keyring" => {
+                let (service, user) = value.split_once('/').unwrap_or((value, DEFAULT_KEYRING_USER));
+                Ok(KeySource::Keyring {
+                    service: service.to_string(),
+                    user: user.to_string(),
+                })
+            }
+            _ => Err(KeyError::InvalidKey(format!("Unknown key source: {}", scheme))),
+        }
+    }
+
+    /// Reads the key bytes. Provider-backed sources are resolved asynchronously through
+    /// [`SecurityManager::from_key_source`](crate::security::SecurityManager::from_key_source).
+    pub fn load(&self) -> Result<Zeroizing<Vec<u8>>, KeyError> {
+        match self {
+            KeySource::Bytes(key) => Ok(key.clone()),
+            KeySource::Env(name) => std::env::var(name)
+                .map(|value| decode_key(&Zeroizing::new(value)))
+                .map_err(|e| KeyError::ProviderError(format!("{}: {}", name, e))),
+            KeySource::File(path) => std::fs::read_to_string(path)
+                .map(|value| decode_key(&Zeroizing::new(value)))
+                .map_err(|e| KeyError::ProviderError(format!("{}: {}", path.display(), e))),
+            KeySource::Keyring { service, user } => load_from_keyring(service, user),
+            KeySource::Passphrase { passphrase, salt, kdf } => {
+                derive_key(passphrase, salt, *kdf).map(|key| Zeroizing::new(key.to_vec()))
+            }
+            KeySource::Provider(..) => Err(KeyError::ProviderError(
+                "Provider keys must be resolved asynchronously".into(),
+            )),
+            KeySource::Invalid(message) => Err(KeyError::InvalidKey(message.clone())),
+        }
+    }
+}
+
+/// Decodes a key read from text: base64 if it yields a 32-byte key, raw bytes otherwise.
+fn decode_key(value: &str) -> Zeroizing<Vec<u8>> {
+    let value = value.trim();
+    let decoded = Zeroizing::new(STANDARD.decode(value).unwrap_or_default());
+    if decoded.len() == 32 {
+        decoded
+    } else {
+        Zeroizing::new(value.as_bytes().to_vec())
+    }
+}
+
+#[cfg(feature = "os-keyring")]
+fn load_from_keyring(service: &str, user: &str) -> Result<Zeroizing<Vec<u8>>, KeyError> {
+    keyring::Entry::new(service, user)
+        .and_then(|entry| entry.get_password())
+        .map(|value| decode_key(&Zeroizing::new(value)))
+        .map_err(|e| KeyError::ProviderError(format!("{}/{}: {}", service, user, e)))
+}
+
+#[cfg(not(feature = "os-keyring"))]
+fn load_from_keyring(service: &str, _user: &str) -> Result<Zeroizing<Vec<u8>>, KeyError> {
+    Err(KeyError::ProviderError(format!(
+        "Cannot read keyring entry {}: the os-keyring feature is disabled",
+        service
+    )))
+}
+
+impl From<&str> for KeySource {
+    /// Parses a descriptor; an invalid one is kept and reported when the key is loaded.
+    fn from(descriptor: &str) -> Self {
+        KeySource::parse(descriptor).unwrap_or_else(|e| KeySource::Invalid(e.to_string()))
+    }
+}
+
+impl From<&[u8]> for KeySource {
+    fn from(key: &[u8]) -> Self {
+        KeySource::Bytes(Zeroizing::new(key.to_vec()))
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for KeySource {
+    fn from(key: &[u8; N]) -> Self {
+        KeySource::Bytes(Zeroizing::new(key.to_vec()))
+    }
+}
+
+impl From<Vec<u8>> for KeySource {
+    fn from(key: Vec<u8>) -> Self {
+        KeySource::Bytes(Zeroizing::new(key))
+    }
+}
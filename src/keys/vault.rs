@@ -0,0 +1,89 @@
+use super::{DataKey, KeyError, KeyProvider, Zeroizing};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::{json, Value};
+
+/// Wraps data keys with a HashiCorp Vault transit key.
+pub struct VaultKeyProvider {
+    client: reqwest::Client,
+    address: String,
+    token: String,
+    mount: String,
+    key_name: String,
+}
+
+impl VaultKeyProvider {
+    /// Initializes the provider for the transit key `key_name` on the Vault server at `address`.
+    pub fn new(address: &str, token: &str, key_name: &str) -> Self {
+        VaultKeyProvider {
+            client: reqwest::Client::new(),
+            address: address.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            mount: "transit".to_string(),
+            key_name: key_name.to_string(),
+        }
+    }
+
+    /// Sets the mount path of the transit secrets engine (`transit` by default).
+    pub fn mount(mut self, mount: &str) -> Self {
+        self.mount = mount.trim_matches('/').to_string();
+        self
+    }
+
+    /// Posts `body` to a transit endpoint and returns the `data` object of the response.
+    async fn call(&self, operation: &str, body: Value) -> Result<Value, KeyError> {
+        let url = format!("{}/v1/{}/{}/{}", self.address, self.mount, operation, self.key_name);
+        let response = self
+            .client
+            .post(url)
+            .header("X-Vault-Token", &self.token)
+            .json(&body)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| KeyError::ProviderError(e.to_string()))?;
+        let mut body: Value = response
+            .json()
+            .await
+            .map_err(|e| KeyError::ProviderError(e.to_string()))?;
+        Ok(body["data"].take())
+    }
+}
+
+/// Reads a string field from a transit response.
+fn field<'a>(data: &'a Value, name: &str) -> Result<&'a str, KeyError> {
+    data[name]
+        .as_str()
+        .ok_or_else(|| KeyError::ProviderError(format!("Vault response lacks {}", name)))
+}
+
+/// Decodes a base64 field from a transit response.
+fn decode(data: &Value, name: &str) -> Result<Zeroizing<Vec<u8>>, KeyError> {
+    STANDARD
+        .decode(field(data, name)?)
+        .map(Zeroizing::new)
+        .map_err(|e| KeyError::ProviderError(e.to_string()))
+}
+
+#[async_trait]
+impl KeyProvider for VaultKeyProvider {
+    async fn generate_data_key(&self) -> Result<DataKey, KeyError> {
+        let data = self.call("datakey/plaintext", json!({ "bits": 256 })).await?;
+        Ok(DataKey {
+            plaintext: decode(&data, "plaintext")?,
+            wrapped: field(&data, "ciphertext")?.as_bytes().to_vec(),
+        })
+    }
+
+    async fn wrap(&self, key: &[u8]) -> Result<Vec<u8>, KeyError> {
+        let data = self.call("encrypt", json!({ "plaintext": STANDARD.encode(key) })).await?;
+        Ok(field(&data, "ciphertext")?.as_bytes().to_vec())
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<Zeroizing<Vec<u8>>, KeyError> {
+        let ciphertext = std::str::from_utf8(wrapped)
+            .map_err(|_| KeyError::InvalidKey("Vault ciphertext must be UTF-8".into()))?;
+        let data = self.call("decrypt", json!({ "ciphertext": ciphertext })).await?;
+        decode(&data, "plaintext")
+    }
+}
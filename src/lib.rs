@@ -1,11 +1,45 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub mod admin;
+pub mod audit;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod compat;
 pub mod config;
+pub mod context;
+pub mod enrichers;
+pub mod events;
+pub mod ext;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod filters;
 pub mod formatters;
 pub mod handlers;
+pub mod integrations;
+pub mod keys;
 pub mod logger;
 pub mod macros;
 pub mod metrics;
+mod pipeline;
+pub mod plugins;
+#[cfg(feature = "python")]
+mod python;
+pub mod reader;
+pub mod registry;
 pub mod security;
+pub mod timing;
 pub mod utils;
 
+// Lets the derive macros refer to this crate as `::log_engine_v1` from within it too
+extern crate self as log_engine_v1;
+
+pub use events::LogEvent;
+pub use ext::{LogOptionExt, LogResultExt};
+pub use registry::{get, global, init, register};
+pub use timing::log_instrument;
+
 #[cfg(test)]
 mod tests;
+
+// The worker and timers run on the browser's event loop through the `wasm` dependencies
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+compile_error!("wasm32 builds need the `wasm` feature");
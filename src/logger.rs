@@ -1,19 +1,41 @@
 use std::fmt::Display;
-use crate::config::ConfigurationManager;
-use crate::formatters::Formatter;
+use crate::config::{ConfigurationManager, LogConfig, RemoteSource};
+pub use crate::context::context;
+use crate::enrichers::LogEnricher;
+use crate::events::{event_metadata, short_type_name, LogEvent};
+use crate::filters::LogFilter;
 use crate::handlers::LogHandler;
-use crate::metrics::MetricsManager;
-use crate::security::SecurityManager;
-use crate::utils::LogLevel;
-use chrono::Utc;
+use crate::keys::{KeyProvider, KeySource};
+use crate::metrics::{AlertAction, AlertEvaluator, AlertRule, DropReason, HealthReport, MetricsManager, Stage};
+use crate::pipeline::{Extensions, FilteredHandler, HandlerEntry, Pipeline};
+use crate::plugins::PluginRegistry;
+use crate::security::{Classification, EncryptedRecord, Scrubber, SecurityError, SecurityManager};
+use crate::utils::{time, Instant, LogLevel};
+use arc_swap::ArcSwap;
 use crossbeam::queue::SegQueue;
-use serde_json::Value;
-use std::sync::Arc;
-use std::{fmt, thread_local};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
 use thiserror::Error;
-use tokio::sync::Notify;
-use tokio::task;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+use tokio::sync::{watch, Notify};
 use uuid::Uuid;
+use zeroize::Zeroizing;
+
+/// How often handlers are health-checked unless the builder says otherwise.
+pub const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long [`Logger::fatal_sync`] and the FATAL macros wait for their record to be
+/// written and the handlers flushed.
+pub const FATAL_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often alert rules are evaluated unless the builder says otherwise.
+pub const DEFAULT_ALERT_INTERVAL: Duration = Duration::from_secs(10);
 
 #[derive(Error, Debug)]
 pub enum LoggerError {
@@ -23,6 +45,10 @@ pub enum LoggerError {
     FormatterError(String),
     #[error("Security error: {0}")]
     SecurityError(String),
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+    #[error("Worker error: {0}")]
+    WorkerError(String),
 }
 
 /// Represents a log message with associated metadata.
@@ -32,6 +58,73 @@ pub struct LogMessage {
     pub message: String,
     pub metadata: Value,
     pub timestamp: String,
+    pub module: Option<Cow<'static, str>>,
+    pub file: Option<Cow<'static, str>>,
+    pub line: Option<u32>,
+    pub thread_id: u64,
+    pub thread_name: Option<Arc<str>>,
+    pub task_id: Option<tokio::task::Id>,
+    /// Position of the message in its logger's stream, assigned when it is enqueued.
+    ///
+    /// Numbers start at 1 and increase by one for every message that passes the global
+    /// filters, so a gap seen by a handler means a message was dropped on the way. The
+    /// single worker emits messages in queue order: messages logged from one thread always
+    /// reach each handler in increasing sequence order, while messages enqueued
+    /// concurrently from different threads may be emitted slightly out of sequence order.
+    /// Messages that were never enqueued carry 0.
+    pub sequence: u64,
+    /// When the message was enqueued, used to measure its end-to-end latency.
+    pub enqueued_at: Option<Instant>,
+}
+
+/// Source of the numeric thread ids stamped on log messages, starting at 1.
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// Numeric id and name of the current thread, assigned on its first log message.
+    static THREAD_IDENTITY: (u64, Option<Arc<str>>) = {
+        let id = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+        (id, std::thread::current().name().map(Arc::from))
+    };
+}
+
+impl LogMessage {
+    /// Creates a log message stamped with a fresh id, the current time, and the identity
+    /// of the calling thread and tokio task.
+    pub fn new(level: LogLevel, message: &str, metadata: Value) -> Self {
+        let (thread_id, thread_name) = THREAD_IDENTITY.with(|identity| identity.clone());
+        LogMessage {
+            id: Uuid::new_v4(),
+            level,
+            message: message.to_string(),
+            metadata,
+            timestamp: crate::utils::now().to_rfc3339(),
+            module: None,
+            file: None,
+            line: None,
+            thread_id,
+            thread_name,
+            task_id: tokio::task::try_id(),
+            sequence: 0,
+            enqueued_at: None,
+        }
+    }
+
+    /// Attaches the source location the message was logged from.
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.module = Some(Cow::Borrowed(location.module));
+        self.file = Some(Cow::Borrowed(location.file));
+        self.line = Some(location.line);
+        self
+    }
+}
+
+/// Source location of a logging call, usually captured with [`source_location!`](crate::source_location).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub module: &'static str,
+    pub file: &'static str,
+    pub line: u32,
 }
 
 impl Display for LogMessage {
@@ -44,219 +137,807 @@ impl Display for LogMessage {
     }
 }
 
+/// Identifies a handler attached to a Logger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HandlerId(pub(crate) u64);
+
+impl Display for HandlerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// Core Logger struct managing the logging process.
 pub struct Logger {
     config_manager: Arc<ConfigurationManager>,
-    handlers: Vec<Arc<dyn LogHandler>>,
-    formatter: Arc<dyn Formatter>,
+    handlers: Arc<ArcSwap<Vec<HandlerEntry>>>,
+    next_handler_id: AtomicU64,
+    pipeline: Arc<ArcSwap<Pipeline>>,
+    /// Severity of the pipeline's `min_level`, read by [`Logger::enabled`].
+    min_severity: AtomicU16,
+    extensions: Extensions,
     queue: Arc<SegQueue<LogMessage>>,
     notify: Arc<Notify>,
+    shutdown: Arc<AtomicBool>,
+    /// Becomes `true` once the worker has drained the queue and exited.
+    stopped: watch::Receiver<bool>,
+    sequence: AtomicU64,
+    /// Sequence number through which records have been written and the handlers flushed.
+    flushed: Arc<Watermark>,
+    /// Highest sequence number a flush has been requested through.
+    flush_through: Arc<AtomicU64>,
     pub metrics: Arc<MetricsManager>,
     security: Arc<SecurityManager>,
 }
 
-impl Logger {
-    /// Initializes the Logger with configuration and security key.
-    pub async fn new(config_file: &str, security_key: &[u8]) -> Result<Arc<Self>, LoggerError> {
-        let config_manager = Arc::new(
-            ConfigurationManager::new(config_file)
+/// A sequence number advanced by the worker that other threads can block on.
+#[derive(Default)]
+struct Watermark {
+    sequence: Mutex<u64>,
+    advanced: Condvar,
+}
+
+impl Watermark {
+    fn advance(&self, sequence: u64) {
+        let mut current = self.sequence.lock().unwrap_or_else(|e| e.into_inner());
+        if sequence > *current {
+            *current = sequence;
+            self.advanced.notify_all();
+        }
+    }
+
+    /// Blocks until the watermark reaches `sequence`, returning `false` on timeout.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn wait_for(&self, sequence: u64, timeout: Duration) -> bool {
+        let current = self.sequence.lock().unwrap_or_else(|e| e.into_inner());
+        let (current, _) = self
+            .advanced
+            .wait_timeout_while(current, timeout, |current| *current < sequence)
+            .unwrap_or_else(|e| e.into_inner());
+        *current >= sequence
+    }
+
+    /// Returns whether the watermark has reached `sequence`. The browser's main thread
+    /// cannot block, and the worker only runs once it yields.
+    #[cfg(target_arch = "wasm32")]
+    fn wait_for(&self, sequence: u64, _timeout: Duration) -> bool {
+        *self.sequence.lock().unwrap_or_else(|e| e.into_inner()) >= sequence
+    }
+}
+
+/// Builder for attaching filters and extra handlers on top of the configuration file.
+pub struct LoggerBuilder {
+    config_file: Option<String>,
+    key: KeySource,
+    filters: Vec<Arc<dyn LogFilter>>,
+    enrichers: Vec<Arc<dyn LogEnricher>>,
+    handlers: Vec<(FilteredHandler, Classification)>,
+    plugins: PluginRegistry,
+    signing_key: Option<Zeroizing<[u8; 32]>>,
+    hash_chain: bool,
+    plaintext: bool,
+    scrubbers: Vec<Arc<dyn Scrubber>>,
+    health_check_interval: Option<Duration>,
+    summary_interval: Option<Duration>,
+    alerts: Vec<AlertRule>,
+    alert_interval: Duration,
+    watch_config: bool,
+    remote_config: Option<(RemoteSource, Duration)>,
+    lenient_config: bool,
+}
+
+impl LoggerBuilder {
+    /// Adds a global filter applied to every message before it is enqueued.
+    pub fn filter(mut self, filter: Arc<dyn LogFilter>) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Adds an enricher run on every message after the global filters.
+    pub fn enricher(mut self, enricher: Arc<dyn LogEnricher>) -> Self {
+        self.enrichers.push(enricher);
+        self
+    }
+
+    /// Adds a handler in addition to those declared in the configuration.
+    pub fn handler(self, handler: Arc<dyn LogHandler>) -> Self {
+        self.handler_with_filters(handler, Vec::new())
+    }
+
+    /// Adds a handler that only receives messages accepted by all of `filters`.
+    pub fn handler_with_filters(
+        mut self,
+        handler: Arc<dyn LogHandler>,
+        filters: Vec<Arc<dyn LogFilter>>,
+    ) -> Self {
+        self.handlers.push(((handler, filters), Classification::default()));
+        self
+    }
+
+    /// Adds a handler approved for records up to `clearance`, e.g. an encrypted file
+    /// handler cleared for `Classification::Confidential`.
+    pub fn handler_with_clearance(mut self, handler: Arc<dyn LogHandler>, clearance: Classification) -> Self {
+        self.handlers.push(((handler, Vec::new()), clearance));
+        self
+    }
+
+    /// Sets the registry used to instantiate the `plugins:` configuration section.
+    pub fn plugins(mut self, registry: PluginRegistry) -> Self {
+        self.plugins = registry;
+        self
+    }
+
+    /// Adds a custom scrubber, run after the sanitization rules from the configuration.
+    pub fn scrubber(mut self, scrubber: Arc<dyn Scrubber>) -> Self {
+        self.scrubbers.push(scrubber);
+        self
+    }
+
+    /// Signs every record with the Ed25519 key derived from `secret`; the public key is
+    /// available from `logger.security().verifying_key()`.
+    pub fn signing_key(mut self, secret: [u8; 32]) -> Self {
+        self.signing_key = Some(Zeroizing::new(secret));
+        self
+    }
+
+    /// Chains record MACs so that inserted, deleted, or reordered records can be detected
+    /// with `SecurityManager::verify_chain`. Each handler keeps its own chain over the
+    /// records it receives, so its output verifies whatever its level, filters and
+    /// clearance let through. A rotated file continues the chain of its archives and is
+    /// verified together with them, oldest first, as by `logctl verify`.
+    pub fn hash_chain(mut self, enabled: bool) -> Self {
+        self.hash_chain = enabled;
+        self
+    }
+
+    /// Writes records sanitized but unencrypted and without MACs, like `encryption: false`
+    /// in the `security:` section.
+    pub fn plaintext(mut self, enabled: bool) -> Self {
+        self.plaintext = enabled;
+        self
+    }
+
+    /// Sets how often handlers' [`health_check`](LogHandler::health_check) is run, or
+    /// disables the checks with `None`. Defaults to every 30 seconds.
+    pub fn health_check_interval(mut self, interval: Option<Duration>) -> Self {
+        self.health_check_interval = interval;
+        self
+    }
+
+    /// Logs an INFO summary such as `processed 12,402 logs, 3 errors in last 60s` every
+    /// `interval`, with the full [`WindowSummary`](crate::metrics::WindowSummary) under
+    /// the `summary` metadata key.
+    pub fn summary_interval(mut self, interval: Duration) -> Self {
+        self.summary_interval = Some(interval);
+        self
+    }
+
+    /// Reloads the configuration file whenever it changes, applying it to the running
+    /// Logger. Invalid edits are reported and ignored.
+    pub fn watch_config(mut self, enabled: bool) -> Self {
+        self.watch_config = enabled;
+        self
+    }
+
+    /// Takes the configuration from `source`, e.g. an etcd or Consul key, polling it every
+    /// `interval` so levels can be adjusted centrally. The configuration file, if any, is
+    /// used when the source cannot be reached at startup.
+    pub fn remote_config(mut self, source: RemoteSource, interval: Duration) -> Self {
+        self.remote_config = Some((source, interval));
+        self
+    }
+
+    /// Starts with [`LogConfig::default`], text records at INFO and above on the console,
+    /// when the configuration file does not exist, logging a warning instead of failing.
+    /// Useful for CLI tools run on machines without a deployed configuration. Invalid
+    /// configuration files are still rejected.
+    pub fn lenient_config(mut self, enabled: bool) -> Self {
+        self.lenient_config = enabled;
+        self
+    }
+
+    /// Adds a threshold alert on the logger's own metrics, e.g.
+    /// `AlertRule::new("errors", AlertMetric::ErrorsPerMinute, 100.0)`.
+    pub fn alert(mut self, rule: AlertRule) -> Self {
+        self.alerts.push(rule);
+        self
+    }
+
+    /// Sets how often alert rules are evaluated; per-minute rates are measured over this
+    /// interval. Defaults to every 10 seconds.
+    pub fn alert_interval(mut self, interval: Duration) -> Self {
+        self.alert_interval = interval;
+        self
+    }
+
+    /// Obtains the encryption key from `provider` instead of the raw key bytes: `wrapped_key`
+    /// is unwrapped if given, otherwise a new data key is generated, whose wrapped form can
+    /// be read back with `logger.security().wrapped_key(..)` and persisted.
+    pub fn key_provider(mut self, provider: Arc<dyn KeyProvider>, wrapped_key: Option<Vec<u8>>) -> Self {
+        self.key = KeySource::Provider(provider, wrapped_key);
+        self
+    }
+
+    /// Builds the Logger and starts its worker.
+    pub async fn build(self) -> Result<Arc<Logger>, LoggerError> {
+        let missing_config = self
+            .config_file
+            .as_ref()
+            .filter(|config_file| self.lenient_config && !std::path::Path::new(config_file).exists());
+        let config_manager = Arc::new(match (&self.remote_config, &self.config_file) {
+            (None, Some(_)) if missing_config.is_some() => ConfigurationManager::with_defaults(),
+            (Some((source, _)), fallback) => ConfigurationManager::from_remote(source, fallback.as_deref())
+                .await
+                .map_err(|e| LoggerError::ConfigError(e.to_string()))?,
+            (None, Some(config_file)) => ConfigurationManager::new(config_file)
                 .await
                 .map_err(|e| LoggerError::FormatterError(e.to_string()))?,
-        );
-        let config = config_manager.get_config().await;
-
-        // Initialize handlers based on config
-        let mut handlers: Vec<Arc<dyn LogHandler>> = Vec::new();
-        for handler_cfg in config.handlers {
-            match handler_cfg.type_.as_str() {
-                "console" => handlers.push(Arc::new(crate::handlers::ConsoleHandler::new())),
-                "file" => {
-                    let file_path = handler_cfg
-                        .config
-                        .as_ref()
-                        .and_then(|cfg| cfg.get("file_path"))
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("logs/app.log")
-                        .to_string();
-                    let max_size = handler_cfg
-                        .config
-                        .as_ref()
-                        .and_then(|cfg| cfg.get("max_size"))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(10 * 1024 * 1024);
-                    handlers.push(Arc::new(crate::handlers::FileHandler::new(
-                        file_path.into(),
-                        max_size,
-                    )));
-                }
-                "remote" => {
-                    let address = handler_cfg
-                        .config
-                        .as_ref()
-                        .and_then(|cfg| cfg.get("address"))
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("127.0.0.1")
-                        .to_string();
-                    let port = handler_cfg
-                        .config
-                        .as_ref()
-                        .and_then(|cfg| cfg.get("port"))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(9000) as u16;
-                    let retries = handler_cfg
-                        .config
-                        .as_ref()
-                        .and_then(|cfg| cfg.get("retries"))
-                        .and_then(|v| v.as_u64())
-                        .map(|v| v as usize);
-                    handlers.push(Arc::new(crate::handlers::RemoteHandler::new(
-                        address, port, retries,
-                    )));
-                }
-                "memory" => {
-                    let capacity = handler_cfg
-                        .config
-                        .as_ref()
-                        .and_then(|cfg| cfg.get("capacity"))
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(1000) as usize;
-                    handlers.push(Arc::new(crate::handlers::MemoryHandler::new(capacity)));
-                }
-                _ => continue,
+            (None, None) => ConfigurationManager::with_defaults(),
+        });
+        if let Some((source, interval)) = self.remote_config {
+            config_manager.watch_remote(source, interval);
+        }
+        match (&self.config_file, self.watch_config) {
+            (Some(config_file), true) => config_manager
+                .watch_config(config_file)
+                .await
+                .map_err(|e| LoggerError::ConfigError(e.to_string()))?,
+            (None, true) => {
+                return Err(LoggerError::ConfigError("No configuration file to watch".into()))
             }
+            (_, false) => {}
         }
+        let config = config_manager.get_config().await;
 
-        // Initialize formatter
-        let formatter: Arc<dyn Formatter> = match config.formatter.as_deref() {
-            Some("json") => Arc::new(crate::formatters::JsonFormatter),
-            Some("text") => Arc::new(crate::formatters::TextFormatter::new(None)),
-            _ => Arc::new(crate::formatters::TextFormatter::new(None)),
+        // Build the configuration-derived pipeline and handlers
+        let extensions = Extensions {
+            filters: self.filters,
+            enrichers: self.enrichers,
+            plugins: self.plugins,
         };
-
-        // Initialize security manager
-        let security = Arc::new(
-            SecurityManager::new(security_key, None)
-                .map_err(|e| LoggerError::SecurityError(e.to_string()))?,
-        );
+        let pipeline = Pipeline::from_config(&config, &extensions)?;
+        let next_handler_id = AtomicU64::new(0);
+        let mut handlers = pipeline.reconcile_handlers(&config, &[], &next_handler_id)?;
+        for (handler, clearance) in self.handlers {
+            handlers.push(pipeline.entry(handler, clearance, &next_handler_id));
+        }
 
         // Initialize metrics
         let metrics = Arc::new(MetricsManager::new());
 
+        // Initialize security manager
+        let patterns = config.security.as_ref().and_then(|security| security.patterns.clone());
+        let mut security = SecurityManager::from_key_source(&self.key, patterns)
+            .await
+            .map_err(|e| LoggerError::SecurityError(e.to_string()))?;
+        if let Some(security_config) = &config.security {
+            security = security
+                .with_config(security_config)
+                .map_err(|e| LoggerError::SecurityError(e.to_string()))?;
+        }
+        for scrubber in self.scrubbers {
+            security = security.with_scrubber(scrubber);
+        }
+        if let Some(secret) = &self.signing_key {
+            security = security.with_signing_key(secret);
+        }
+        if self.hash_chain {
+            security = security.with_hash_chain();
+        }
+        if self.plaintext {
+            security = security
+                .with_plaintext()
+                .map_err(|e| LoggerError::SecurityError(e.to_string()))?;
+        }
+        let security = Arc::new(security.with_redaction_counter(metrics.redactions.clone()));
+
         // Initialize lock-free queue
         let queue = Arc::new(SegQueue::new());
 
         // Initialize notify for worker
         let notify = Arc::new(Notify::new());
 
+        let (stopped_tx, stopped) = watch::channel(false);
+
         let logger = Arc::new(Logger {
             config_manager: config_manager.clone(),
-            handlers,
-            formatter,
+            handlers: Arc::new(ArcSwap::from_pointee(handlers)),
+            next_handler_id,
+            min_severity: AtomicU16::new(pipeline.min_level.severity()),
+            pipeline: Arc::new(ArcSwap::from_pointee(pipeline)),
+            extensions,
             queue: queue.clone(),
             notify: notify.clone(),
+            shutdown: Arc::new(AtomicBool::new(false)),
+            stopped,
+            sequence: AtomicU64::new(0),
+            flushed: Arc::new(Watermark::default()),
+            flush_through: Arc::new(AtomicU64::new(0)),
             metrics,
             security,
         });
 
-        // Initialize thread-local buffer
-        thread_local! {
-            static BUFFER: task::LocalSet = task::LocalSet::new();
+        // Start the worker task and follow configuration updates
+        Logger::start_worker(logger.clone(), stopped_tx)?;
+        Logger::start_config_listener(&logger);
+        if let Some(interval) = self.health_check_interval {
+            Logger::start_health_prober(&logger, interval);
+        }
+        if let Some(interval) = self.summary_interval {
+            Logger::start_summary(&logger, interval);
+        }
+        if !self.alerts.is_empty() {
+            Logger::start_alerts(&logger, self.alerts, self.alert_interval);
+        }
+        if let Some(config_file) = missing_config {
+            logger.warn(
+                "Configuration file not found, using the default configuration",
+                Some(serde_json::json!({ "config_file": config_file })),
+            );
         }
-
-        // Start the worker task
-        Logger::start_worker(logger.clone());
 
         Ok(logger)
     }
+}
+
+impl Logger {
+    /// Initializes the Logger with configuration and security key. Without a configuration
+    /// file, [`LogConfig::default`] is used: text records at INFO and above on the console.
+    ///
+    /// The key is given as bytes or as a [`KeySource`] descriptor such as `"env:LOG_KEY"`.
+    pub async fn new(
+        config_file: Option<&str>,
+        security_key: impl Into<KeySource>,
+    ) -> Result<Arc<Self>, LoggerError> {
+        Logger::builder(config_file, security_key).build().await
+    }
+
+    /// Creates a builder for a Logger with the given configuration file, if any, and
+    /// security key.
+    pub fn builder(config_file: Option<&str>, security_key: impl Into<KeySource>) -> LoggerBuilder {
+        LoggerBuilder {
+            config_file: config_file.map(str::to_string),
+            key: security_key.into(),
+            filters: Vec::new(),
+            enrichers: Vec::new(),
+            handlers: Vec::new(),
+            plugins: PluginRegistry::new(),
+            signing_key: None,
+            hash_chain: false,
+            plaintext: false,
+            scrubbers: Vec::new(),
+            health_check_interval: Some(DEFAULT_HEALTH_CHECK_INTERVAL),
+            summary_interval: None,
+            alerts: Vec::new(),
+            alert_interval: DEFAULT_ALERT_INTERVAL,
+            watch_config: false,
+            remote_config: None,
+            lenient_config: false,
+        }
+    }
+
+    /// Attaches a handler at runtime and returns its id.
+    pub fn add_handler(&self, handler: Arc<dyn LogHandler>) -> HandlerId {
+        self.add_handler_with_filters(handler, Vec::new())
+    }
+
+    /// Attaches a handler at runtime that only receives messages accepted by all of `filters`.
+    pub fn add_handler_with_filters(
+        &self,
+        handler: Arc<dyn LogHandler>,
+        filters: Vec<Arc<dyn LogFilter>>,
+    ) -> HandlerId {
+        self.attach(((handler, filters), Classification::default()))
+    }
+
+    /// Attaches a handler at runtime approved for records up to `clearance`.
+    pub fn add_handler_with_clearance(
+        &self,
+        handler: Arc<dyn LogHandler>,
+        clearance: Classification,
+    ) -> HandlerId {
+        self.attach(((handler, Vec::new()), clearance))
+    }
+
+    fn attach(&self, (handler, clearance): (FilteredHandler, Classification)) -> HandlerId {
+        let entry = self
+            .pipeline
+            .load()
+            .entry(handler, clearance, &self.next_handler_id);
+        let id = entry.id;
+        self.handlers.rcu(|current| {
+            let mut updated = Vec::clone(current);
+            updated.push(entry.clone());
+            updated
+        });
+        id
+    }
+
+    /// Detaches a handler. Returns `false` if no handler has this id.
+    pub fn remove_handler(&self, id: HandlerId) -> bool {
+        let previous = self.handlers.rcu(|current| {
+            current
+                .iter()
+                .filter(|entry| entry.id != id)
+                .cloned()
+                .collect::<Vec<_>>()
+        });
+        previous.iter().any(|entry| entry.id == id)
+    }
+
+    /// Lists the ids and names of the attached handlers, in emit order.
+    pub fn handlers(&self) -> Vec<(HandlerId, String)> {
+        self.handlers
+            .load()
+            .iter()
+            .map(|entry| (entry.id, entry.handler.name().to_string()))
+            .collect()
+    }
+
+    /// Stops the worker once every message enqueued so far has been written, then pushes
+    /// the final metrics to any Pushgateway registered with
+    /// [`MetricsManager::push_to_gateway`]. Messages logged after this are not written.
+    pub async fn shutdown(&self) {
+        self.metrics.set_ready(false);
+        self.shutdown.store(true, Ordering::SeqCst);
+        self.notify.notify_one();
+        let mut stopped = self.stopped.clone();
+        // An error means the worker is gone without reporting, which also ends it
+        let _ = stopped.wait_for(|stopped| *stopped).await;
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Err(e) = self.metrics.push_to_gateways().await {
+            eprintln!("Failed to push final metrics: {}", e);
+        }
+    }
+
+    /// Returns the configuration manager; updating its configuration reconfigures this Logger.
+    pub fn config_manager(&self) -> Arc<ConfigurationManager> {
+        self.config_manager.clone()
+    }
+
+    /// Returns the security manager, e.g. to rotate the encryption key at runtime.
+    pub fn security(&self) -> Arc<SecurityManager> {
+        self.security.clone()
+    }
+
+    /// Rebuilds filters, enrichers, plugins, and the formatter from `config`, and reconciles
+    /// the configured handlers. Handlers whose configuration is unchanged are kept, and
+    /// handlers added through the builder or at runtime are left untouched.
+    pub fn apply_config(&self, config: &LogConfig) -> Result<(), LoggerError> {
+        let pipeline = Pipeline::from_config(config, &self.extensions)?;
+        let current = self.handlers.load_full();
+        let handlers = pipeline.reconcile_handlers(config, &current, &self.next_handler_id)?;
+        self.min_severity.store(pipeline.min_level.severity(), Ordering::Relaxed);
+        self.pipeline.store(Arc::new(pipeline));
+        // Keep handlers attached concurrently with the reconciliation
+        self.handlers.rcu(|latest| {
+            let mut updated = handlers.clone();
+            updated.extend(
+                latest
+                    .iter()
+                    .filter(|e| e.source.is_none() && !current.iter().any(|c| c.id == e.id))
+                    .cloned(),
+            );
+            updated
+        });
+        Ok(())
+    }
+
+    /// Applies configuration updates published by the configuration manager until the Logger is dropped.
+    fn start_config_listener(logger: &Arc<Logger>) {
+        let mut updates = logger.config_manager.subscribe();
+        let weak = Arc::downgrade(logger);
+        crate::utils::spawn(async move {
+            while updates.changed().await.is_ok() {
+                let Some(logger) = weak.upgrade() else {
+                    break;
+                };
+                let config = updates.borrow_and_update().clone();
+                if let Err(e) = logger.apply_config(&config) {
+                    logger.metrics.increment_error();
+                    eprintln!("Failed to apply configuration: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Runs the handlers' health checks every `interval` until the Logger is dropped.
+    fn start_health_prober(logger: &Arc<Logger>, interval: Duration) {
+        let weak = Arc::downgrade(logger);
+        crate::utils::spawn(async move {
+            let mut ticker = time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let Some(logger) = weak.upgrade() else {
+                    break;
+                };
+                logger.check_handlers().await;
+            }
+        });
+    }
+
+    /// Logs a summary of the metrics every `interval` until the Logger is dropped.
+    fn start_summary(logger: &Arc<Logger>, interval: Duration) {
+        let weak = Arc::downgrade(logger);
+        let mut window = logger.metrics.window();
+        crate::utils::spawn(async move {
+            let mut ticker = time::interval(interval);
+            // The first tick completes immediately
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(logger) = weak.upgrade() else {
+                    break;
+                };
+                let summary = window.take(&logger.metrics);
+                logger.info(&summary.to_string(), Some(serde_json::json!({ "summary": summary })));
+            }
+        });
+    }
+
+    /// Evaluates `rules` every `interval` until the Logger is dropped.
+    fn start_alerts(logger: &Arc<Logger>, rules: Vec<AlertRule>, interval: Duration) {
+        let weak = Arc::downgrade(logger);
+        let mut evaluator = AlertEvaluator::new(rules, &logger.metrics);
+        crate::utils::spawn(async move {
+            let mut ticker = time::interval(interval);
+            // The first tick completes immediately
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(logger) = weak.upgrade() else {
+                    break;
+                };
+                for (action, alert) in evaluator.evaluate(&logger.metrics) {
+                    match action {
+                        AlertAction::Callback(callback) => callback(&alert),
+                        AlertAction::Log(level) => {
+                            logger.log(level, &alert.to_string(), Some(serde_json::json!({ "alert": alert })))
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Runs every handler's health check now and records the results.
+    pub async fn check_handlers(&self) {
+        let handlers = self.handlers.load_full();
+        for entry in handlers.iter() {
+            let result = entry.handler.health_check().await.map_err(|e| e.to_string());
+            self.metrics.handler(&entry.metrics_key()).record_health_check(result);
+        }
+    }
+
+    /// Returns the health of the worker and of each handler, as last checked.
+    pub fn health(&self) -> HealthReport {
+        self.metrics.health()
+    }
 
     /// Starts the asynchronous logging worker that processes log messages from the queue.
-    fn start_worker(logger: Arc<Logger>) {
+    fn start_worker(logger: Arc<Logger>, stopped: watch::Sender<bool>) -> Result<(), LoggerError> {
         let queue = logger.queue.clone();
         let notify = logger.notify.clone();
         let handlers = logger.handlers.clone();
-        let formatter = logger.formatter.clone();
+        let pipeline = logger.pipeline.clone();
         let metrics = logger.metrics.clone();
         let security = logger.security.clone();
+        let shutdown = logger.shutdown.clone();
+        let flushed = logger.flushed.clone();
+        let flush_through = logger.flush_through.clone();
 
-        task::spawn_blocking(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            rt.block_on(async move {
-                loop {
-                    // Wait for notification or check queue periodically
-                    tokio::select! {
-                        _ = notify.notified() => {},
-                        _ = tokio::time::sleep(tokio::time::Duration::from_millis(100)) => {},
-                    }
+        let worker = async move {
+            metrics.set_ready(true);
+            let mut flushed_through = 0;
+            loop {
+                metrics.record_worker_heartbeat();
+                // Wait for notification or check queue periodically
+                tokio::select! {
+                    _ = notify.notified() => {},
+                    _ = time::sleep(Duration::from_millis(100)) => {},
+                }
 
-                    let mut batch = Vec::new();
-                    while let Some(log) = queue.pop() {
-                        batch.push(log);
-                    }
+                // Read the flag before draining so nothing enqueued earlier is lost
+                let stopping = shutdown.load(Ordering::SeqCst);
+                // Likewise, records logged before a flush request are in this batch
+                let flush_requested = flush_through.load(Ordering::SeqCst);
 
-                    if !batch.is_empty() {
-                        for log in batch {
-                            // Security: sanitize, encrypt, and hash
-                            let sanitized = security.sanitize(&log.message);
-                            let encrypted = match security.encrypt(&sanitized) {
-                                Ok(enc) => enc,
-                                Err(e) => {
-                                    metrics.increment_error();
-                                    eprintln!("Encryption failed: {}", e);
-                                    continue;
-                                }
-                            };
-                            let hash = match security.hash(&encrypted) {
-                                Ok(h) => h,
+                metrics.record_drain();
+                let mut batch = Vec::new();
+                while let Some(log) = queue.pop() {
+                    batch.push(log);
+                }
+
+                if !batch.is_empty() {
+                    let pipeline = pipeline.load();
+                    let current = handlers.load();
+                    for mut log in batch {
+                        metrics.record_worker_heartbeat();
+                        for processor in &pipeline.processors {
+                            processor.process(&mut log);
+                        }
+
+                        // Security: sanitize, encrypt, and authenticate
+                        let started = Instant::now();
+                        let sanitized = security.scrub(&log.message, &mut log.metadata);
+                        metrics.observe_stage(Stage::Sanitize, started.elapsed());
+                        let started = Instant::now();
+                        let encrypted = security.encrypt_record(&mut log, sanitized);
+                        metrics.observe_stage(Stage::Encrypt, started.elapsed());
+                        let record = match encrypted {
+                            Ok(record) => record,
+                            Err(e) => {
+                                metrics.increment_error();
+                                metrics.increment_dropped(DropReason::SecurityError);
+                                eprintln!("Securing record failed: {}", e);
+                                continue;
+                            }
+                        };
+
+                        // Authenticate and format the log once, or with hash chaining once per
+                        // handler, so that each handler's records form an unbroken chain
+                        // whatever its filters let through
+                        let shared = if security.is_hash_chained() {
+                            None
+                        } else {
+                            let started = Instant::now();
+                            let formatted = finish_record(&pipeline, &security, &log, &record, None).await;
+                            metrics.observe_stage(Stage::Format, started.elapsed());
+                            match formatted {
+                                Ok(formatted) => Some(formatted),
                                 Err(e) => {
                                     metrics.increment_error();
-                                    eprintln!("Hashing failed: {}", e);
+                                    metrics.increment_dropped(DropReason::SecurityError);
+                                    eprintln!("Securing record failed: {}", e);
                                     continue;
                                 }
-                            };
+                            }
+                        };
 
-                            let metadata = serde_json::json!({
-                                "hash": hash,
-                                "timestamp": log.timestamp,
-                                "metadata": log.metadata,
-                            });
-
-                            // Format the log
-                            let formatted = formatter
-                                .format(&log.level.to_string(), &encrypted, &metadata)
-                                .await;
-
-                            // Emit to all handlers
-                            for handler in &handlers {
-                                let emit_result = handler.emit(&formatted).await;
-                                if emit_result.is_err() {
-                                    metrics.increment_error();
-                                    eprintln!("Handler emit failed: {:?}", emit_result.err());
+                        // Emit to all handlers accepting this message
+                        let emitting = Instant::now();
+                        for entry in current.iter().filter(|h| h.accepts(&log)) {
+                            let chained;
+                            let formatted = match &shared {
+                                Some(formatted) => formatted,
+                                None => {
+                                    let started = Instant::now();
+                                    let formatted =
+                                        finish_record(&pipeline, &security, &log, &record, Some(&entry.chain)).await;
+                                    metrics.observe_stage(Stage::Format, started.elapsed());
+                                    match formatted {
+                                        Ok(formatted) => {
+                                            chained = formatted;
+                                            &chained
+                                        }
+                                        Err(e) => {
+                                            metrics.increment_error();
+                                            eprintln!("Securing record failed: {}", e);
+                                            continue;
+                                        }
+                                    }
                                 }
+                            };
+                            let retries = entry.handler.retry_count();
+                            let started = Instant::now();
+                            let emit_result = entry.handler.emit(formatted).await;
+                            let error = emit_result.as_ref().err().map(|e| e.to_string());
+                            metrics.handler(&entry.metrics_key()).record_emit(
+                                error.as_deref(),
+                                formatted.len(),
+                                started.elapsed(),
+                                entry.handler.retry_count().saturating_sub(retries),
+                            );
+                            if let Err(e) = emit_result {
+                                metrics.increment_error();
+                                eprintln!("Handler emit failed: {:?}", e);
                             }
+                        }
+                        metrics.observe_stage(Stage::Emit, emitting.elapsed());
 
-                            // Update metrics
-                            metrics.increment_log_count();
-                            // Optionally, record latency or other metrics
+                        // Update metrics
+                        metrics.increment_log_count();
+                        metrics.increment_level(log.level);
+                        if let Some(enqueued_at) = log.enqueued_at {
+                            metrics.observe_delivery(enqueued_at.elapsed());
                         }
+                    }
 
-                        // Update queue size metric
-                        metrics.set_queue_size(queue.len());
+                    // Update queue size metric
+                    metrics.set_queue_size(queue.len());
+                }
+                if flush_requested > flushed_through {
+                    for entry in handlers.load().iter() {
+                        if let Err(e) = entry.handler.flush().await {
+                            eprintln!("Handler flush failed: {:?}", e);
+                        }
                     }
+                    flushed_through = flush_requested;
+                    flushed.advance(flushed_through);
                 }
-            });
+
+                if stopping {
+                    break;
+                }
+            }
+            metrics.set_ready(false);
+        };
+        Logger::spawn_worker(worker, stopped)
+    }
+
+    /// Runs the worker on a dedicated thread, which keeps a long-lived (e.g. global) logger
+    /// from blocking the shutdown of the caller's runtime.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn spawn_worker(
+        worker: impl Future<Output = ()> + Send + 'static,
+        stopped: watch::Sender<bool>,
+    ) -> Result<(), LoggerError> {
+        std::thread::Builder::new()
+            .name("log-engine-worker".into())
+            .spawn(move || {
+                let rt = tokio::runtime::Runtime::new().unwrap();
+                rt.block_on(worker);
+                stopped.send_replace(true);
+            })
+            .map(|_| ())
+            .map_err(|e| LoggerError::WorkerError(e.to_string()))
+    }
+
+    /// Runs the worker on the browser's event loop, which has no threads to spare.
+    #[cfg(target_arch = "wasm32")]
+    fn spawn_worker(worker: impl Future<Output = ()> + 'static, stopped: watch::Sender<bool>) -> Result<(), LoggerError> {
+        crate::utils::spawn(async move {
+            worker.await;
+            stopped.send_replace(true);
         });
+        Ok(())
+    }
+
+    /// Returns `false` if no message at `level` can pass the configured level or any of its
+    /// module overrides, or is compiled out (see [`crate::utils::STATIC_MIN_SEVERITY`]), so
+    /// callers can skip building the message. Costs a single atomic load; the logging macros
+    /// check it before evaluating their arguments.
+    pub fn enabled(&self, level: LogLevel) -> bool {
+        crate::utils::static_enabled(level) && level.severity() >= self.min_severity.load(Ordering::Relaxed)
     }
 
     /// Enqueues a log message for processing.
     pub fn log(&self, level: LogLevel, message: &str, metadata: Option<Value>) {
-        let log = LogMessage {
-            id: Uuid::new_v4(),
-            level,
-            message: message.to_string(),
-            metadata: metadata.unwrap_or(serde_json::json!({})),
-            timestamp: Utc::now().to_rfc3339(),
+        self.log_at(level, message, metadata, None);
+    }
+
+    /// Enqueues a log message recording the source location it was logged from.
+    pub fn log_at(&self, level: LogLevel, message: &str, metadata: Option<Value>, location: Option<Location>) {
+        let mut log = LogMessage::new(level, message, metadata.unwrap_or(serde_json::json!({})));
+        if let Some(location) = location {
+            log = log.with_location(location);
+        }
+        context().merge_into(&mut log.metadata);
+        let capture = match log.metadata.get("backtrace") {
+            Some(Value::Bool(capture)) => Some(*capture),
+            _ => None,
         };
+        if capture.is_some() {
+            if let Some(fields) = log.metadata.as_object_mut() {
+                fields.remove("backtrace");
+            }
+        }
+        let pipeline = self.pipeline.load();
+        if !pipeline.accepts(&log) {
+            self.metrics.increment_dropped(DropReason::Filtered);
+            return;
+        }
+        let capture = capture.unwrap_or(pipeline.backtrace && level.severity() >= LogLevel::ERROR.severity());
+        if capture {
+            if let Some(fields) = log.metadata.as_object_mut() {
+                fields.insert("backtrace".to_string(), backtrace_metadata());
+            }
+        }
+        pipeline.enrich(&mut log);
+        log.sequence = self.sequence.fetch_add(1, Ordering::SeqCst) + 1;
+        log.enqueued_at = Some(Instant::now());
         self.queue.push(log);
+        self.metrics.record_enqueue(self.queue.len());
         self.notify.notify_one();
     }
 
@@ -280,4 +961,316 @@ impl Logger {
     pub fn fatal(&self, message: &str, metadata: Option<Value>) {
         self.log(LogLevel::FATAL, message, metadata);
     }
+
+    /// Logs at FATAL and blocks until the record has been written and the handlers
+    /// flushed, or [`FATAL_FLUSH_TIMEOUT`] has passed, so the record survives the process
+    /// exiting right after. Returns `false` if it timed out.
+    pub fn fatal_sync(&self, message: &str, metadata: Option<Value>) -> bool {
+        self.fatal(message, metadata);
+        self.flush_blocking(FATAL_FLUSH_TIMEOUT)
+    }
+
+    /// Blocks the calling thread until every record logged so far has been written and
+    /// the handlers flushed, or `timeout` has passed. Returns `false` if it timed out or
+    /// the Logger has been shut down. On `wasm32` it cannot wait, and only requests the
+    /// flush and reports whether it has already happened.
+    pub fn flush_blocking(&self, timeout: Duration) -> bool {
+        let sequence = self.sequence.load(Ordering::SeqCst);
+        if sequence == 0 {
+            return true;
+        }
+        if *self.stopped.borrow() {
+            return false;
+        }
+        self.flush_through.fetch_max(sequence, Ordering::SeqCst);
+        self.notify.notify_one();
+        self.flushed.wait_for(sequence, timeout)
+    }
+
+    /// Logs `err` at ERROR level with its full `source()` chain as structured metadata.
+    pub fn error_with(&self, err: &dyn std::error::Error) {
+        self.log(LogLevel::ERROR, &err.to_string(), Some(error_metadata(err)));
+    }
+
+    /// Logs a typed event; its serialized fields become the metadata and its type name the message.
+    pub fn event<T: Serialize + ?Sized>(&self, level: LogLevel, event: &T) {
+        let name = short_type_name::<T>();
+        self.log(level, name, Some(event_metadata(name, event, &[])));
+    }
+
+    /// Logs a [`LogEvent`] at its own level, with its redacted fields masked.
+    pub fn log_event<E: LogEvent>(&self, event: &E) {
+        let name = event.event_name();
+        let metadata = event_metadata(name, event, event.redacted_fields());
+        self.log(event.level(), name, Some(metadata));
+    }
+
+    /// Logs every line read from `reader` until it ends, e.g. the stdout of a child process,
+    /// and returns the number of records logged. The lines go through the same filters,
+    /// sanitization, encryption and handlers as other records; see [`parse_ingested`] for
+    /// how their level, message and metadata are read. Use a [`ChildLogger`] to tag them
+    /// with their source.
+    pub async fn ingest_reader<R: AsyncBufRead + Unpin>(&self, reader: R) -> std::io::Result<u64> {
+        ingest(reader, |level, message, metadata| self.log(level, message, metadata)).await
+    }
+
+    /// Creates a child logger sharing this pipeline that attaches `fields` to every message.
+    pub fn with_fields(self: &Arc<Self>, fields: Value) -> ChildLogger {
+        ChildLogger {
+            logger: self.clone(),
+            fields: into_object(fields),
+        }
+    }
+}
+
+/// A logger bound to a set of fields, created with [`Logger::with_fields`].
+///
+/// Fields passed at the call site take precedence over bound fields with the same key.
+#[derive(Clone)]
+pub struct ChildLogger {
+    logger: Arc<Logger>,
+    fields: Map<String, Value>,
+}
+
+impl ChildLogger {
+    /// Creates a nested child logger with additional bound fields.
+    pub fn with_fields(&self, fields: Value) -> ChildLogger {
+        let mut merged = self.fields.clone();
+        merged.extend(into_object(fields));
+        ChildLogger {
+            logger: self.logger.clone(),
+            fields: merged,
+        }
+    }
+
+    /// Returns the fields bound to this logger.
+    pub fn fields(&self) -> &Map<String, Value> {
+        &self.fields
+    }
+
+    /// See [`Logger::enabled`].
+    pub fn enabled(&self, level: LogLevel) -> bool {
+        self.logger.enabled(level)
+    }
+
+    /// Enqueues a log message with the bound fields attached.
+    pub fn log(&self, level: LogLevel, message: &str, metadata: Option<Value>) {
+        self.log_at(level, message, metadata, None);
+    }
+
+    /// Enqueues a log message with the bound fields and source location attached.
+    pub fn log_at(&self, level: LogLevel, message: &str, metadata: Option<Value>, location: Option<Location>) {
+        let mut fields = self.fields.clone();
+        match metadata {
+            Some(Value::Object(call_fields)) => fields.extend(call_fields),
+            Some(other) => {
+                fields.insert("value".to_string(), other);
+            }
+            None => {}
+        }
+        self.logger.log_at(level, message, Some(Value::Object(fields)), location);
+    }
+
+    // Convenience methods for different log levels
+    pub fn debug(&self, message: &str, metadata: Option<Value>) {
+        self.log(LogLevel::DEBUG, message, metadata);
+    }
+
+    pub fn info(&self, message: &str, metadata: Option<Value>) {
+        self.log(LogLevel::INFO, message, metadata);
+    }
+
+    pub fn warn(&self, message: &str, metadata: Option<Value>) {
+        self.log(LogLevel::WARN, message, metadata);
+    }
+
+    pub fn error(&self, message: &str, metadata: Option<Value>) {
+        self.log(LogLevel::ERROR, message, metadata);
+    }
+
+    pub fn fatal(&self, message: &str, metadata: Option<Value>) {
+        self.log(LogLevel::FATAL, message, metadata);
+    }
+
+    /// See [`Logger::fatal_sync`].
+    pub fn fatal_sync(&self, message: &str, metadata: Option<Value>) -> bool {
+        self.fatal(message, metadata);
+        self.flush_blocking(FATAL_FLUSH_TIMEOUT)
+    }
+
+    /// See [`Logger::flush_blocking`].
+    pub fn flush_blocking(&self, timeout: Duration) -> bool {
+        self.logger.flush_blocking(timeout)
+    }
+
+    /// See [`Logger::ingest_reader`]; the lines carry this logger's fields.
+    pub async fn ingest_reader<R: AsyncBufRead + Unpin>(&self, reader: R) -> std::io::Result<u64> {
+        ingest(reader, |level, message, metadata| self.log(level, message, metadata)).await
+    }
+
+    /// Logs `err` at ERROR level with its full `source()` chain as structured metadata.
+    pub fn error_with(&self, err: &dyn std::error::Error) {
+        self.log(LogLevel::ERROR, &err.to_string(), Some(error_metadata(err)));
+    }
+
+    /// Logs a typed event; its serialized fields become the metadata and its type name the message.
+    pub fn event<T: Serialize + ?Sized>(&self, level: LogLevel, event: &T) {
+        let name = short_type_name::<T>();
+        self.log(level, name, Some(event_metadata(name, event, &[])));
+    }
+
+    /// Logs a [`LogEvent`] at its own level, with its redacted fields masked.
+    pub fn log_event<E: LogEvent>(&self, event: &E) {
+        let name = event.event_name();
+        let metadata = event_metadata(name, event, event.redacted_fields());
+        self.log(event.level(), name, Some(metadata));
+    }
+}
+
+/// Adds an encrypted record's MAC, chained to `chain` when given, and formats it.
+async fn finish_record(
+    pipeline: &Pipeline,
+    security: &SecurityManager,
+    log: &LogMessage,
+    record: &EncryptedRecord,
+    chain: Option<&Mutex<String>>,
+) -> Result<String, SecurityError> {
+    let mut metadata = record.metadata.clone();
+    if let Some(mac) = security.mac_record(record, chain)? {
+        metadata["mac"] = Value::String(mac);
+    }
+    Ok(pipeline.formatter.format_record(log, &record.message, &metadata).await)
+}
+
+/// Describes an error and its causes, plus a backtrace when enabled via `RUST_BACKTRACE`.
+fn error_metadata(err: &dyn std::error::Error) -> Value {
+    let mut chain = Vec::new();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        chain.push(Value::String(cause.to_string()));
+        source = cause.source();
+    }
+    let mut error = Map::new();
+    error.insert("message".to_string(), Value::String(err.to_string()));
+    error.insert("chain".to_string(), Value::Array(chain));
+    let backtrace = std::backtrace::Backtrace::capture();
+    if backtrace.status() == std::backtrace::BacktraceStatus::Captured {
+        error.insert("backtrace".to_string(), Value::String(backtrace.to_string()));
+    }
+    serde_json::json!({ "error": error })
+}
+
+/// Most frames kept in a captured backtrace, counted from the logging call.
+const MAX_BACKTRACE_FRAMES: usize = 64;
+
+/// Captures the calling thread's backtrace as a list of `{function, location}` frames,
+/// starting at the caller of the logger.
+fn backtrace_metadata() -> Value {
+    let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+    let frames = parse_backtrace(&backtrace)
+        .into_iter()
+        .skip_while(|frame| {
+            let function = frame["function"].as_str().unwrap_or_default();
+            function.starts_with("std::backtrace") || function.starts_with(module_path!())
+        })
+        .take(MAX_BACKTRACE_FRAMES)
+        .collect();
+    Value::Array(frames)
+}
+
+/// Parses the `Display` form of a [`std::backtrace::Backtrace`] into frames.
+pub(crate) fn parse_backtrace(backtrace: &str) -> Vec<Value> {
+    let mut frames: Vec<Value> = Vec::new();
+    for line in backtrace.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(location) = line.strip_prefix("at ") {
+            if let Some(Value::Object(frame)) = frames.last_mut() {
+                frame.insert("location".to_string(), Value::String(location.to_string()));
+            }
+            continue;
+        }
+        let function = match line.split_once(": ") {
+            Some((index, function)) if index.bytes().all(|b| b.is_ascii_digit()) => function,
+            _ => line,
+        };
+        frames.push(serde_json::json!({ "function": function }));
+    }
+    frames
+}
+
+/// Reads `reader` line by line, passing each non-blank line to `log`.
+async fn ingest<R: AsyncBufRead + Unpin>(
+    mut reader: R,
+    log: impl Fn(LogLevel, &str, Option<Value>),
+) -> std::io::Result<u64> {
+    let mut line = Vec::new();
+    let mut count = 0;
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).await? == 0 {
+            return Ok(count);
+        }
+        // Output of other programs is not always valid UTF-8
+        let text = String::from_utf8_lossy(&line);
+        if let Some((level, message, metadata)) = parse_ingested(&text) {
+            log(level, &message, metadata);
+            count += 1;
+        }
+    }
+}
+
+/// Reads a line for [`Logger::ingest_reader`]. A JSON object is logged at its `level`
+/// field, with its `message` or `msg` field as the message and its other fields as
+/// metadata. Other lines are logged as they are, at the level named by their first word
+/// if any (e.g. `WARN: disk almost full` or `[error] ...`), at INFO otherwise. Returns
+/// `None` for blank lines.
+pub fn parse_ingested(line: &str) -> Option<(LogLevel, String, Option<Value>)> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    if let Ok(Value::Object(mut fields)) = serde_json::from_str::<Value>(line) {
+        let level = match fields.remove("level") {
+            Some(Value::String(level)) => level_named(&level),
+            _ => None,
+        };
+        let message = match fields.remove("message").or_else(|| fields.remove("msg")) {
+            Some(Value::String(message)) => message,
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+        let metadata = (!fields.is_empty()).then_some(Value::Object(fields));
+        return Some((level.unwrap_or(LogLevel::INFO), message, metadata));
+    }
+    let word = line.split(char::is_whitespace).next().unwrap_or_default();
+    let level = level_named(word.trim_matches(|c: char| !c.is_alphanumeric()));
+    Some((level.unwrap_or(LogLevel::INFO), line.to_string(), None))
+}
+
+fn level_named(name: &str) -> Option<LogLevel> {
+    match name.to_ascii_uppercase().as_str() {
+        "WARNING" => Some(LogLevel::WARN),
+        "CRITICAL" => Some(LogLevel::FATAL),
+        name => LogLevel::from_str(name),
+    }
+}
+
+/// Converts bound fields into a map; non-object values are stored under `value`.
+fn into_object(fields: Value) -> Map<String, Value> {
+    match fields {
+        Value::Object(map) => map,
+        Value::Null => Map::new(),
+        other => {
+            let mut map = Map::new();
+            map.insert("value".to_string(), other);
+            map
+        }
+    }
+}
+
+impl Drop for Logger {
+    fn drop(&mut self) {
+        // Let the worker drain the queue and exit
+        self.shutdown.store(true, Ordering::SeqCst);
+        self.notify.notify_one();
+    }
 }
@@ -1,49 +1,289 @@
+/// Captures the current source location as a [`Location`](crate::logger::Location).
 #[macro_export]
-macro_rules! log_debug {
-    ($logger:expr, $msg:expr) => {
-        $logger.debug($msg, None);
+macro_rules! source_location {
+    () => {
+        $crate::logger::Location {
+            module: module_path!(),
+            file: file!(),
+            line: line!(),
+        }
+    };
+}
+
+/// Logs at `$level`; the implementation of [`log_info!`] and friends. The message and
+/// fields are only evaluated when the logger has the level enabled, and the call compiles
+/// to nothing for levels below [`STATIC_MIN_SEVERITY`](crate::utils::STATIC_MIN_SEVERITY).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log {
+    (@enabled $level:expr; $logger:expr; $msg:expr, $metadata:expr) => {{
+        let logger = &$logger;
+        if $crate::utils::static_enabled($level) && logger.enabled($level) {
+            logger.log_at($level, $msg, $metadata, Some($crate::source_location!()));
+        }
+    }};
+    ($level:expr; $logger:expr, $msg:expr) => {
+        $crate::__log!(@enabled $level; $logger; $msg, None)
+    };
+    ($level:expr; $logger:expr, $msg:literal; $($fields:tt)+) => {
+        $crate::__log!(@enabled $level; $logger; &format!($msg), Some($crate::__fields!($($fields)+)))
+    };
+    ($level:expr; $logger:expr, $msg:expr, $($arg:tt)*) => {
+        $crate::__log!(@enabled $level; $logger; &format!($msg, $($arg)*), Some(serde_json::json!({})))
+    };
+    ($level:expr; $logger:expr, $class:ident; $msg:expr) => {
+        $crate::__log!(
+            @enabled $level; $logger; $msg,
+            Some(serde_json::json!({ "classification": $crate::security::Classification::$class }))
+        )
+    };
+    ($level:expr; $logger:expr, $class:ident; $msg:literal; $($fields:tt)+) => {
+        $crate::__log!(
+            @enabled $level; $logger; &format!($msg),
+            Some($crate::__fields!(
+                classification = $crate::security::Classification::$class,
+                $($fields)+
+            ))
+        )
     };
-    ($logger:expr, $msg:expr, $($arg:tt)*) => {
-        $logger.debug(&format!($msg, $($arg)*), Some(serde_json::json!({})));
+    ($level:expr; $logger:expr, $class:ident; $msg:expr, $($arg:tt)*) => {
+        $crate::__log!(
+            @enabled $level; $logger; &format!($msg, $($arg)*),
+            Some(serde_json::json!({ "classification": $crate::security::Classification::$class }))
+        )
     };
 }
 
+/// Builds a metadata object from `key = value` pairs. Values are serialized, or recorded
+/// as strings with `key = ?value` for their `Debug` form and `key = %value` for `Display`.
+#[doc(hidden)]
 #[macro_export]
-macro_rules! log_info {
-    ($logger:expr, $msg:expr) => {
-        $logger.info($msg, None);
+macro_rules! __fields {
+    (@insert $fields:ident;) => {};
+    (@insert $fields:ident; $key:ident = ? $value:expr $(, $($rest:tt)*)?) => {
+        $fields.insert(stringify!($key).to_string(), serde_json::Value::String(format!("{:?}", $value)));
+        $crate::__fields!(@insert $fields; $($($rest)*)?);
+    };
+    (@insert $fields:ident; $key:ident = % $value:expr $(, $($rest:tt)*)?) => {
+        $fields.insert(stringify!($key).to_string(), serde_json::Value::String(format!("{}", $value)));
+        $crate::__fields!(@insert $fields; $($($rest)*)?);
     };
-    ($logger:expr, $msg:expr, $($arg:tt)*) => {
-        $logger.info(&format!($msg, $($arg)*), Some(serde_json::json!({})));
+    (@insert $fields:ident; $key:ident = $value:expr $(, $($rest:tt)*)?) => {
+        $fields.insert(stringify!($key).to_string(), serde_json::json!($value));
+        $crate::__fields!(@insert $fields; $($($rest)*)?);
     };
+    ($($fields:tt)+) => {{
+        let mut fields = serde_json::Map::new();
+        $crate::__fields!(@insert fields; $($fields)+);
+        serde_json::Value::Object(fields)
+    }};
 }
 
+/// Logs at TRACE. See [`log_info!`] for the accepted forms.
 #[macro_export]
-macro_rules! log_warn {
-    ($logger:expr, $msg:expr) => {
-        $logger.warn($msg, None);
+macro_rules! log_trace {
+    ($($args:tt)+) => {
+        $crate::__log!($crate::utils::LogLevel::TRACE; $($args)+)
     };
-    ($logger:expr, $msg:expr, $($arg:tt)*) => {
-        $logger.warn(&format!($msg, $($arg)*), Some(serde_json::json!({})));
+}
+
+/// Logs at DEBUG. See [`log_info!`] for the accepted forms.
+#[macro_export]
+macro_rules! log_debug {
+    ($($args:tt)+) => {
+        $crate::__log!($crate::utils::LogLevel::DEBUG; $($args)+)
     };
 }
 
+/// Logs at INFO, with the caller's source location:
+///
+/// - `log_info!(logger, "started")`,
+/// - `log_info!(logger, "took {}ms", elapsed)` or `log_info!(logger, "took {ms}ms", ms = elapsed)`,
+///   formatting the message,
+/// - `log_info!(logger, "user login"; user_id = 42, region = "eu")`, recording each
+///   `key = value` pair after the `;` as a metadata field; the message may still capture
+///   variables inline, as in `"login from {ip}"`. Values are serialized, or recorded with
+///   their `Debug` form as `key = ?value` and their `Display` form as `key = %value`,
+/// - `log_info!(logger, Confidential; "card updated for {}", account)`, with any of the
+///   forms above after the [`Classification`](crate::security::Classification).
 #[macro_export]
-macro_rules! log_error {
-    ($logger:expr, $msg:expr) => {
-        $logger.error($msg, None);
+macro_rules! log_info {
+    ($($args:tt)+) => {
+        $crate::__log!($crate::utils::LogLevel::INFO; $($args)+)
     };
-    ($logger:expr, $msg:expr, $($arg:tt)*) => {
-        $logger.error(&format!($msg, $($arg)*), Some(serde_json::json!({})));
+}
+
+/// Logs at WARN. See [`log_info!`] for the accepted forms.
+#[macro_export]
+macro_rules! log_warn {
+    ($($args:tt)+) => {
+        $crate::__log!($crate::utils::LogLevel::WARN; $($args)+)
+    };
+}
+
+/// Logs at ERROR. See [`log_info!`] for the accepted forms.
+#[macro_export]
+macro_rules! log_error {
+    ($($args:tt)+) => {
+        $crate::__log!($crate::utils::LogLevel::ERROR; $($args)+)
     };
 }
 
+/// Logs at FATAL and waits for the record to be written and the handlers flushed (see
+/// [`Logger::fatal_sync`](crate::logger::Logger::fatal_sync)). See [`log_info!`] for the
+/// accepted forms; `log_fatal!(logger, exit_code = 1; "cannot continue")` also exits the
+/// process with the code once flushed.
 #[macro_export]
 macro_rules! log_fatal {
-    ($logger:expr, $msg:expr) => {
-        $logger.fatal($msg, None);
+    ($logger:expr, exit_code = $code:expr; $($args:tt)+) => {{
+        $crate::log_fatal!($logger, $($args)+);
+        ::std::process::exit($code)
+    }};
+    ($logger:expr, $($args:tt)+) => {{
+        let logger = &$logger;
+        $crate::__log!($crate::utils::LogLevel::FATAL; logger, $($args)+);
+        logger.flush_blocking($crate::logger::FATAL_FLUSH_TIMEOUT);
+    }};
+}
+
+/// Logs at `$level` through the global logger, doing nothing when none is installed.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_global {
+    ($level:expr; $($args:tt)+) => {
+        if let Some(logger) = $crate::registry::global_ref() {
+            $crate::__log!($level; logger, $($args)+)
+        }
+    };
+}
+
+/// Logs at TRACE through the global logger. See [`info!`] for the accepted forms.
+#[macro_export]
+macro_rules! trace {
+    ($($args:tt)+) => {
+        $crate::__log_global!($crate::utils::LogLevel::TRACE; $($args)+)
+    };
+}
+
+/// Logs at DEBUG through the global logger installed with [`init`](crate::init), e.g.
+/// `debug!("cache miss for {}", key)`. See [`info!`] for the accepted forms.
+#[macro_export]
+macro_rules! debug {
+    ($($args:tt)+) => {
+        $crate::__log_global!($crate::utils::LogLevel::DEBUG; $($args)+)
+    };
+}
+
+/// Logs at INFO through the global logger installed with [`init`](crate::init), so a
+/// logger need not be passed around; nothing is logged before one is installed. Accepts
+/// the forms of [`log_info!`] without the logger, e.g. `info!("user login"; user_id = 42)`.
+#[macro_export]
+macro_rules! info {
+    ($($args:tt)+) => {
+        $crate::__log_global!($crate::utils::LogLevel::INFO; $($args)+)
+    };
+}
+
+/// Logs at WARN through the global logger. See [`info!`] for the accepted forms.
+#[macro_export]
+macro_rules! warn {
+    ($($args:tt)+) => {
+        $crate::__log_global!($crate::utils::LogLevel::WARN; $($args)+)
+    };
+}
+
+/// Logs at ERROR through the global logger. See [`info!`] for the accepted forms.
+#[macro_export]
+macro_rules! error {
+    ($($args:tt)+) => {
+        $crate::__log_global!($crate::utils::LogLevel::ERROR; $($args)+)
+    };
+}
+
+/// Logs at FATAL through the global logger and waits for the record to be flushed, like
+/// [`log_fatal!`]. `fatal!(exit_code = 1; "cannot continue")` also exits the process with
+/// the code, even when no global logger is installed.
+#[macro_export]
+macro_rules! fatal {
+    (exit_code = $code:expr; $($args:tt)+) => {{
+        $crate::fatal!($($args)+);
+        ::std::process::exit($code)
+    }};
+    ($($args:tt)+) => {
+        if let Some(logger) = $crate::registry::global_ref() {
+            $crate::log_fatal!(logger, $($args)+)
+        }
+    };
+}
+
+/// Logs at the named level only when `condition` holds, e.g.
+/// `log_if!(logger, retries > 3, WARN, "retried {} times", retries)`. The condition is
+/// evaluated first, and the message only when it is true and the level enabled; the forms
+/// after the level are those of [`log_info!`].
+#[macro_export]
+macro_rules! log_if {
+    ($logger:expr, $condition:expr, $level:ident, $($args:tt)+) => {
+        if $condition {
+            $crate::__log!($crate::utils::LogLevel::$level; $logger, $($args)+)
+        }
+    };
+}
+
+/// Logs an expression with its `Debug` representation and source location and returns
+/// its value, like `dbg!` but through the logger: `let area = inspect!(logger, DEBUG, w * h);`
+/// logs `w * h = 12`. The level defaults to DEBUG; the value is only formatted when the
+/// level is enabled.
+#[macro_export]
+macro_rules! inspect {
+    ($logger:expr, $level:ident, $value:expr $(,)?) => {
+        match $value {
+            value => {
+                $crate::__log!(
+                    @enabled $crate::utils::LogLevel::$level; $logger;
+                    &format!("{} = {:?}", stringify!($value), &value),
+                    Some(serde_json::json!({ "expression": stringify!($value) }))
+                );
+                value
+            }
+        }
+    };
+    ($logger:expr, $value:expr $(,)?) => {
+        $crate::inspect!($logger, DEBUG, $value)
+    };
+}
+
+/// Runs a block and logs how long it took under a label, at DEBUG through
+/// [`Logger::time`](crate::logger::Logger::time), and evaluates to the block's value:
+///
+/// ```ignore
+/// let contacts = time_block!(logger, "physics_step", { world.step() });
+/// let user = time_block!(logger, "fetch_user", async { db.user(id).await }).await;
+/// ```
+///
+/// With `async`, the macro builds a future timed from its first poll to its completion.
+#[macro_export]
+macro_rules! time_block {
+    ($logger:expr, $label:expr, async $body:block) => {
+        async {
+            let _timer = $logger.time($label).with_location($crate::source_location!());
+            async $body.await
+        }
+    };
+    ($logger:expr, $label:expr, $body:block) => {{
+        let _timer = $logger.time($label).with_location($crate::source_location!());
+        $body
+    }};
+}
+
+/// Records an audit event with an [`AuditLogger`](crate::audit::AuditLogger), e.g.
+/// `audit!(audit_log, "alice", "delete", "invoice/42").await?`, optionally followed by a
+/// JSON value of details.
+#[macro_export]
+macro_rules! audit {
+    ($logger:expr, $actor:expr, $action:expr, $resource:expr) => {
+        $logger.record($actor, $action, $resource, None)
     };
-    ($logger:expr, $msg:expr, $($arg:tt)*) => {
-        $logger.fatal(&format!($msg, $($arg)*), Some(serde_json::json!({})));
+    ($logger:expr, $actor:expr, $action:expr, $resource:expr, $details:expr) => {
+        $logger.record($actor, $action, $resource, Some($details))
     };
 }
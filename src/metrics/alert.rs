@@ -0,0 +1,141 @@
+use super::{MetricsManager, MetricsSnapshot, MetricsWindow, WindowSummary};
+use crate::utils::LogLevel;
+use serde::Serialize;
+use std::fmt;
+use std::sync::Arc;
+
+/// Computes a custom alert value from the counts since the last evaluation and the
+/// current metrics.
+pub type AlertValueFn = dyn Fn(&WindowSummary, &MetricsSnapshot) -> f64 + Send + Sync;
+
+/// The quantity an [`AlertRule`] compares with its threshold.
+#[derive(Clone)]
+pub enum AlertMetric {
+    /// Errors per minute since the previous evaluation.
+    ErrorsPerMinute,
+    /// Records dropped per minute since the previous evaluation, for any reason.
+    DroppedPerMinute,
+    /// Records waiting in the queue.
+    QueueSize,
+    /// How long the oldest queued record has been waiting, in seconds.
+    OldestQueuedAge,
+    Custom(Arc<AlertValueFn>),
+}
+
+/// What happens when a rule is breached.
+#[derive(Clone)]
+pub enum AlertAction {
+    /// Calls the function with the alert.
+    Callback(Arc<dyn Fn(&Alert) + Send + Sync>),
+    /// Logs a record at the level through the logger itself, with the alert under the
+    /// `alert` metadata key.
+    Log(LogLevel),
+}
+
+/// A threshold on one of the logger's own metrics, such as more than 100 errors per
+/// minute or more than 50,000 queued records.
+///
+/// Rules are edge-triggered: a rule fires when its value first exceeds the threshold and
+/// fires again only after the value has dropped back to or below it.
+#[derive(Clone)]
+pub struct AlertRule {
+    name: String,
+    metric: AlertMetric,
+    threshold: f64,
+    action: AlertAction,
+}
+
+impl AlertRule {
+    /// Creates a rule breached when `metric` exceeds `threshold`, logging a WARN record by
+    /// default.
+    pub fn new(name: &str, metric: AlertMetric, threshold: f64) -> Self {
+        AlertRule {
+            name: name.to_string(),
+            metric,
+            threshold,
+            action: AlertAction::Log(LogLevel::WARN),
+        }
+    }
+
+    /// Calls `callback` instead of logging when the rule is breached.
+    pub fn on_breach(mut self, callback: impl Fn(&Alert) + Send + Sync + 'static) -> Self {
+        self.action = AlertAction::Callback(Arc::new(callback));
+        self
+    }
+
+    /// Logs the alert at `level`, e.g. FATAL, when the rule is breached.
+    pub fn log_at(mut self, level: LogLevel) -> Self {
+        self.action = AlertAction::Log(level);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self, window: &WindowSummary, snapshot: &MetricsSnapshot) -> f64 {
+        let per_minute = |count: u64| match window.duration.as_secs_f64() {
+            seconds if seconds > 0.0 => count as f64 * 60.0 / seconds,
+            _ => 0.0,
+        };
+        match &self.metric {
+            AlertMetric::ErrorsPerMinute => per_minute(window.errors),
+            AlertMetric::DroppedPerMinute => per_minute(window.dropped.values().sum()),
+            AlertMetric::QueueSize => snapshot.queue_size as f64,
+            AlertMetric::OldestQueuedAge => snapshot.oldest_queued_age_seconds.unwrap_or(0.0),
+            AlertMetric::Custom(value) => value(window, snapshot),
+        }
+    }
+}
+
+/// A breached [`AlertRule`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Alert {
+    pub rule: String,
+    pub value: f64,
+    pub threshold: f64,
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Alert {}: {} exceeds {}", self.rule, self.value, self.threshold)
+    }
+}
+
+/// Evaluates a set of rules against the metrics, remembering which are breached.
+pub(crate) struct AlertEvaluator {
+    rules: Vec<AlertRule>,
+    breached: Vec<bool>,
+    window: MetricsWindow,
+}
+
+impl AlertEvaluator {
+    pub(crate) fn new(rules: Vec<AlertRule>, metrics: &MetricsManager) -> Self {
+        AlertEvaluator {
+            breached: vec![false; rules.len()],
+            rules,
+            window: metrics.window(),
+        }
+    }
+
+    /// Returns the rules newly breached since the previous evaluation, with their actions.
+    pub(crate) fn evaluate(&mut self, metrics: &MetricsManager) -> Vec<(AlertAction, Alert)> {
+        let window = self.window.take(metrics);
+        let snapshot = metrics.snapshot();
+        let mut fired = Vec::new();
+        for (rule, breached) in self.rules.iter().zip(&mut self.breached) {
+            let value = rule.value(&window, &snapshot);
+            let exceeded = value > rule.threshold;
+            if exceeded && !*breached {
+                let alert = Alert {
+                    rule: rule.name.clone(),
+                    value,
+                    threshold: rule.threshold,
+                };
+                fired.push((rule.action.clone(), alert));
+            }
+            *breached = exceeded;
+        }
+        fired
+    }
+}
@@ -0,0 +1,36 @@
+//! Forwarding of metric updates to the [`metrics`](https://docs.rs/metrics) crate facade,
+//! enabled by the `metrics` feature. Without it these functions do nothing.
+//!
+//! Names match the Prometheus exposition of [`MetricsManager`](super::MetricsManager), so
+//! dashboards work the same whether the logger's page or the application's recorder
+//! (e.g. `metrics-exporter-prometheus`) is scraped.
+
+/// A label attached to a forwarded metric.
+pub(crate) type Label<'a> = Option<(&'static str, &'a str)>;
+
+pub(crate) fn increment(name: &'static str, label: Label<'_>, value: u64) {
+    #[cfg(feature = "metrics")]
+    match label {
+        Some((key, label)) => ::metrics::counter!(name, &[(key, label.to_string())]).increment(value),
+        None => ::metrics::counter!(name).increment(value),
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (name, label, value);
+}
+
+pub(crate) fn gauge(name: &'static str, value: f64) {
+    #[cfg(feature = "metrics")]
+    ::metrics::gauge!(name).set(value);
+    #[cfg(not(feature = "metrics"))]
+    let _ = (name, value);
+}
+
+pub(crate) fn histogram(name: &'static str, label: Label<'_>, value: f64) {
+    #[cfg(feature = "metrics")]
+    match label {
+        Some((key, label)) => ::metrics::histogram!(name, &[(key, label.to_string())]).record(value),
+        None => ::metrics::histogram!(name).record(value),
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (name, label, value);
+}
@@ -0,0 +1,100 @@
+use super::HistogramSnapshot;
+
+/// Type of a metric family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    /// Monotonically increasing total.
+    Counter,
+    /// Value that can go up and down.
+    Gauge,
+    /// Distribution of observations in fixed buckets.
+    Histogram,
+}
+
+/// Value of one sample in a family.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetricValue {
+    Scalar(f64),
+    Histogram(HistogramSnapshot),
+}
+
+/// One labeled sample of a [`MetricFamily`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSample {
+    pub labels: Vec<(String, String)>,
+    pub value: MetricValue,
+}
+
+/// A named metric with its samples, independent of any export format.
+///
+/// Names carry neither the [`NAMESPACE`](super::NAMESPACE) prefix nor format-specific
+/// suffixes such as Prometheus' `_total`; exporters add those.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricFamily {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: MetricKind,
+    pub samples: Vec<MetricSample>,
+}
+
+impl MetricFamily {
+    /// Creates a family holding a single unlabeled value.
+    pub(crate) fn scalar(name: &'static str, help: &'static str, kind: MetricKind, value: f64) -> Self {
+        MetricFamily::labeled(name, help, kind, "", [(String::new(), value)])
+    }
+
+    /// Creates a family with one sample per value of `label`. An empty `label` leaves the
+    /// samples unlabeled.
+    pub(crate) fn labeled(
+        name: &'static str,
+        help: &'static str,
+        kind: MetricKind,
+        label: &str,
+        samples: impl IntoIterator<Item = (String, f64)>,
+    ) -> Self {
+        let samples = samples
+            .into_iter()
+            .map(|(value, sample)| MetricSample {
+                labels: label_pair(label, value),
+                value: MetricValue::Scalar(sample),
+            })
+            .collect();
+        MetricFamily {
+            name,
+            help,
+            kind,
+            samples,
+        }
+    }
+
+    /// Creates a histogram family with one histogram per value of `label`. An empty
+    /// `label` leaves the samples unlabeled.
+    pub(crate) fn histograms(
+        name: &'static str,
+        help: &'static str,
+        label: &str,
+        samples: impl IntoIterator<Item = (String, HistogramSnapshot)>,
+    ) -> Self {
+        let samples = samples
+            .into_iter()
+            .map(|(value, snapshot)| MetricSample {
+                labels: label_pair(label, value),
+                value: MetricValue::Histogram(snapshot),
+            })
+            .collect();
+        MetricFamily {
+            name,
+            help,
+            kind: MetricKind::Histogram,
+            samples,
+        }
+    }
+}
+
+fn label_pair(label: &str, value: String) -> Vec<(String, String)> {
+    if label.is_empty() {
+        Vec::new()
+    } else {
+        vec![(label.to_string(), value)]
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long the worker may go without a heartbeat before it counts as stalled.
+pub const WORKER_STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Condition of a handler, from its health checks and recent emits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HandlerStatus {
+    /// The last health check and emit succeeded.
+    Ok,
+    /// The destination is reachable but the last emit failed.
+    Degraded,
+    /// The last health check failed.
+    Down,
+}
+
+impl HandlerStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HandlerStatus::Ok => "ok",
+            HandlerStatus::Degraded => "degraded",
+            HandlerStatus::Down => "down",
+        }
+    }
+}
+
+/// Health of the handlers sharing one name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HandlerHealth {
+    pub status: HandlerStatus,
+    /// Error of the last failed health check or emit.
+    pub last_error: Option<String>,
+    /// When a health check or emit last succeeded.
+    pub last_success: Option<DateTime<Utc>>,
+}
+
+/// What the health checks and emits of a handler have reported so far.
+#[derive(Default)]
+pub(crate) struct HealthState {
+    check_failed: bool,
+    last_error: Option<String>,
+    last_success: Option<DateTime<Utc>>,
+}
+
+impl HealthState {
+    pub(crate) fn record(state: &Mutex<HealthState>, check: bool, result: Result<(), String>) {
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Ok(()) => {
+                state.last_success = Some(crate::utils::now());
+                if check {
+                    state.check_failed = false;
+                }
+            }
+            Err(error) => {
+                state.last_error = Some(error);
+                if check {
+                    state.check_failed = true;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn health(&self, consecutive_failures: u64) -> HandlerHealth {
+        let status = if self.check_failed {
+            HandlerStatus::Down
+        } else if consecutive_failures > 0 {
+            HandlerStatus::Degraded
+        } else {
+            HandlerStatus::Ok
+        };
+        HandlerHealth {
+            status,
+            last_error: self.last_error.clone(),
+            last_success: self.last_success,
+        }
+    }
+}
+
+/// Health of the logging subsystem, as reported by `/healthz`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HealthReport {
+    /// The worker made progress within [`WORKER_STALL_TIMEOUT`].
+    pub worker_alive: bool,
+    /// Each handler that has emitted or been checked, by name and id, e.g. `file#2`.
+    pub handlers: BTreeMap<String, HandlerHealth>,
+}
+
+impl HealthReport {
+    /// Returns `true` if the worker is alive and no handler is down.
+    pub fn is_healthy(&self) -> bool {
+        self.worker_alive && self.handlers.values().all(|handler| handler.status != HandlerStatus::Down)
+    }
+}
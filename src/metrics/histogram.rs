@@ -0,0 +1,105 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Default latency buckets in seconds, from 10µs to 1s.
+pub const LATENCY_BUCKETS: &[f64] = &[
+    0.00001, 0.00005, 0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0,
+];
+
+/// A lock-free histogram with fixed bucket upper bounds.
+pub struct Histogram {
+    bounds: Vec<f64>,
+    /// Per-bucket counts (not cumulative), plus one for values above the last bound.
+    counts: Vec<AtomicU64>,
+    /// Sum of the observed values, as `f64` bits.
+    sum: AtomicU64,
+}
+
+/// A point-in-time copy of a [`Histogram`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistogramSnapshot {
+    /// Upper bounds with the cumulative number of observations at or below each.
+    pub buckets: Vec<(f64, u64)>,
+    pub sum: f64,
+    pub count: u64,
+}
+
+impl Histogram {
+    /// Creates a histogram with the given ascending bucket upper bounds.
+    pub fn new(bounds: &[f64]) -> Self {
+        Histogram {
+            bounds: bounds.to_vec(),
+            counts: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            sum: AtomicU64::new(0f64.to_bits()),
+        }
+    }
+
+    /// Creates a histogram with [`LATENCY_BUCKETS`].
+    pub fn latency() -> Self {
+        Histogram::new(LATENCY_BUCKETS)
+    }
+
+    /// Records one observation.
+    pub fn observe(&self, value: f64) {
+        let bucket = self.bounds.iter().position(|bound| value <= *bound).unwrap_or(self.bounds.len());
+        self.counts[bucket].fetch_add(1, Ordering::Relaxed);
+        let _ = self.sum.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+            Some((f64::from_bits(bits) + value).to_bits())
+        });
+    }
+
+    /// Clears every observation.
+    pub fn reset(&self) {
+        for count in &self.counts {
+            count.store(0, Ordering::Relaxed);
+        }
+        self.sum.store(0f64.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Returns the current bucket counts, sum and count.
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let mut cumulative = 0;
+        let mut buckets = Vec::with_capacity(self.bounds.len());
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            cumulative += count.load(Ordering::Relaxed);
+            buckets.push((*bound, cumulative));
+        }
+        let count = cumulative + self.counts[self.bounds.len()].load(Ordering::Relaxed);
+        HistogramSnapshot {
+            buckets,
+            sum: f64::from_bits(self.sum.load(Ordering::Relaxed)),
+            count,
+        }
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Histogram::latency()
+    }
+}
+
+impl HistogramSnapshot {
+    /// Estimates the `q` quantile (0.0 to 1.0) by interpolating within the bucket that
+    /// contains it, as Prometheus' `histogram_quantile` does. Returns `None` when nothing was
+    /// observed; values above the last bound are reported as the last bound.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = q.clamp(0.0, 1.0) * self.count as f64;
+        let mut lower = (0.0, 0);
+        for (bound, cumulative) in &self.buckets {
+            if *cumulative as f64 >= rank {
+                let (lower_bound, lower_count) = lower;
+                let in_bucket = (*cumulative - lower_count) as f64;
+                if in_bucket == 0.0 {
+                    return Some(*bound);
+                }
+                return Some(lower_bound + (bound - lower_bound) * (rank - lower_count as f64) / in_bucket);
+            }
+            lower = (*bound, *cumulative);
+        }
+        self.buckets.last().map(|(bound, _)| *bound)
+    }
+}
@@ -0,0 +1,661 @@
+mod alert;
+pub(crate) mod facade;
+mod family;
+mod health;
+mod histogram;
+#[cfg(feature = "otel")]
+mod otel;
+mod prometheus;
+#[cfg(not(target_arch = "wasm32"))]
+mod pushgateway;
+mod rate;
+#[cfg(not(target_arch = "wasm32"))]
+mod server;
+mod snapshot;
+#[cfg(not(target_arch = "wasm32"))]
+mod statsd;
+mod window;
+
+pub(crate) use alert::AlertEvaluator;
+pub use alert::{Alert, AlertAction, AlertMetric, AlertRule, AlertValueFn};
+pub use family::{MetricFamily, MetricKind, MetricSample, MetricValue};
+pub use health::{HandlerHealth, HandlerStatus, HealthReport, WORKER_STALL_TIMEOUT};
+pub use histogram::{Histogram, HistogramSnapshot, LATENCY_BUCKETS};
+pub use prometheus::{CONTENT_TYPE, NAMESPACE};
+pub use rate::RateMeter;
+#[cfg(all(feature = "tls", not(target_arch = "wasm32")))]
+pub use server::rustls;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use server::{respond_busy, write_response};
+#[cfg(not(target_arch = "wasm32"))]
+pub use server::{MetricsServer, MetricsServerHandle};
+pub use snapshot::{HandlerSnapshot, MetricsSnapshot};
+#[cfg(not(target_arch = "wasm32"))]
+pub use statsd::StatsdExporter;
+pub use window::{MetricsWindow, WindowSummary};
+
+use crate::utils::LogLevel;
+use health::HealthState;
+use prometheus::Exposition;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use crate::utils::Instant;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum MetricsError {
+    #[error("Failed to bind to address: {0}")]
+    BindError(String),
+    #[error("IO error: {0}")]
+    IoError(String),
+    #[error("Failed to push metrics: {0}")]
+    PushError(String),
+}
+
+/// Emit statistics of one handler.
+pub struct HandlerMetrics {
+    /// The handler's name and id, e.g. `file#2`.
+    name: String,
+    pub emits: AtomicU64,
+    pub failures: AtomicU64,
+    /// Failed emits since the last successful one.
+    pub consecutive_failures: AtomicU64,
+    pub retries: AtomicU64,
+    pub bytes_written: AtomicU64,
+    /// Time spent in `emit`, in seconds, including retries.
+    pub latency: Histogram,
+    health: Mutex<HealthState>,
+}
+
+impl HandlerMetrics {
+    fn new(name: &str) -> Self {
+        HandlerMetrics {
+            name: name.to_string(),
+            emits: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            consecutive_failures: AtomicU64::new(0),
+            retries: AtomicU64::new(0),
+            bytes_written: AtomicU64::new(0),
+            latency: Histogram::latency(),
+            health: Mutex::new(HealthState::default()),
+        }
+    }
+
+    /// Records one call to `emit` of `bytes` that took `elapsed`, retried `retries` times,
+    /// and failed with `error` if any.
+    pub fn record_emit(&self, error: Option<&str>, bytes: usize, elapsed: Duration, retries: u64) {
+        let succeeded = error.is_none();
+        self.emits.fetch_add(1, Ordering::Relaxed);
+        if succeeded {
+            self.bytes_written.fetch_add(bytes as u64, Ordering::Relaxed);
+            self.consecutive_failures.store(0, Ordering::Relaxed);
+        } else {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+            self.consecutive_failures.fetch_add(1, Ordering::Relaxed);
+        }
+        self.retries.fetch_add(retries, Ordering::Relaxed);
+        self.latency.observe(elapsed.as_secs_f64());
+        HealthState::record(&self.health, false, error.map_or(Ok(()), |error| Err(error.to_string())));
+
+        let label = Some(("handler", self.name.as_str()));
+        facade::increment("logengine_handler_emits_total", label, 1);
+        if succeeded {
+            facade::increment("logengine_handler_bytes_written_total", label, bytes as u64);
+        } else {
+            facade::increment("logengine_handler_failures_total", label, 1);
+        }
+        if retries > 0 {
+            facade::increment("logengine_handler_retries_total", label, retries);
+        }
+        facade::histogram("logengine_handler_emit_duration_seconds", label, elapsed.as_secs_f64());
+    }
+
+    /// Records the outcome of a health check.
+    pub fn record_health_check(&self, result: Result<(), String>) {
+        HealthState::record(&self.health, true, result);
+    }
+
+    /// Returns the handlers' current health.
+    pub fn health(&self) -> HandlerHealth {
+        let consecutive_failures = self.consecutive_failures.load(Ordering::Relaxed);
+        self.health.lock().unwrap_or_else(|e| e.into_inner()).health(consecutive_failures)
+    }
+}
+
+/// Reference point for the timestamps kept in atomics.
+fn clock_start() -> Instant {
+    static START: OnceLock<Instant> = OnceLock::new();
+    *START.get_or_init(Instant::now)
+}
+
+/// Nanoseconds since [`clock_start`] plus one, so 0 can stand for "never".
+fn clock_ticks() -> u64 {
+    clock_start().elapsed().as_nanos() as u64 + 1
+}
+
+/// Whole seconds since [`clock_start`].
+fn clock_seconds() -> u64 {
+    clock_start().elapsed().as_secs()
+}
+
+/// Windows of the `logs_per_second` gauge, with their label values.
+const THROUGHPUT_WINDOWS: [(u64, &str); 3] = [(1, "1s"), (10, "10s"), (60, "1m")];
+
+/// Why a record was dropped before reaching any handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropReason {
+    /// Rejected by a global filter, including level thresholds and filter plugins such as
+    /// samplers or rate limiters.
+    Filtered,
+    /// Sanitizing, encrypting or authenticating the record failed.
+    SecurityError,
+}
+
+impl DropReason {
+    pub const ALL: [DropReason; 2] = [DropReason::Filtered, DropReason::SecurityError];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DropReason::Filtered => "filtered",
+            DropReason::SecurityError => "security_error",
+        }
+    }
+}
+
+/// A step of the worker's processing of a record, timed separately so a slowdown can be
+/// attributed to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Redacting denied keys and running the sanitization rules and scrubbers.
+    Sanitize,
+    /// Encrypting, MACing and signing the sanitized record.
+    Encrypt,
+    /// Formatting the sealed record.
+    Format,
+    /// Emitting the formatted record to every handler accepting it.
+    Emit,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 4] = [Stage::Sanitize, Stage::Encrypt, Stage::Format, Stage::Emit];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Sanitize => "sanitize",
+            Stage::Encrypt => "encrypt",
+            Stage::Format => "format",
+            Stage::Emit => "emit",
+        }
+    }
+}
+
+/// Counters, gauges and histograms describing the logger's activity.
+///
+/// With the `metrics` feature, every update is also forwarded to the `metrics` crate
+/// facade under the same names as the Prometheus exposition, so an application's own
+/// recorder picks up the logger's metrics without scraping a second endpoint.
+#[derive(Default)]
+pub struct MetricsManager {
+    pub logs_processed: Arc<AtomicUsize>,
+    pub errors: Arc<AtomicUsize>,
+    pub queue_size: Arc<AtomicUsize>,
+    /// Per-handler statistics, keyed by handler name.
+    handlers: RwLock<BTreeMap<String, Arc<HandlerMetrics>>>,
+    /// Records processed per built-in level, in [`LogLevel::BUILTIN`] order.
+    builtin_levels: [AtomicU64; 6],
+    /// Records processed per custom level, by severity.
+    custom_levels: Mutex<BTreeMap<u16, u64>>,
+    /// Time from enqueueing a record to the end of its last handler emit, in seconds.
+    delivery_latency: Histogram,
+    /// Time spent per record in each pipeline stage, in [`Stage::ALL`] order, in seconds.
+    stages: [Histogram; 4],
+    /// Records written per second.
+    throughput: RateMeter,
+    /// Records dropped, in [`DropReason::ALL`] order.
+    dropped: [AtomicU64; 2],
+    /// Deepest the queue has been since the logger started.
+    queue_high_watermark: AtomicUsize,
+    /// When the oldest message still queued was enqueued, as nanoseconds since
+    /// [`clock_start`] plus one; 0 while nothing is queued.
+    oldest_enqueued: AtomicU64,
+    /// Values redacted by the sanitizer, shared with the logger's
+    /// [`SecurityManager`](crate::security::SecurityManager).
+    pub redactions: Arc<AtomicU64>,
+    /// When the worker last made progress, in the encoding of `oldest_enqueued`; 0 before
+    /// it starts.
+    worker_heartbeat: AtomicU64,
+    /// The worker is running and not shutting down.
+    ready: AtomicBool,
+    /// Pushgateways pushed to periodically and at shutdown.
+    #[cfg(not(target_arch = "wasm32"))]
+    gateways: Mutex<Vec<pushgateway::GatewayTarget>>,
+}
+
+impl MetricsManager {
+    /// Initializes the MetricsManager.
+    pub fn new() -> Self {
+        MetricsManager {
+            logs_processed: Arc::new(AtomicUsize::new(0)),
+            errors: Arc::new(AtomicUsize::new(0)),
+            queue_size: Arc::new(AtomicUsize::new(0)),
+            handlers: RwLock::new(BTreeMap::new()),
+            builtin_levels: Default::default(),
+            custom_levels: Mutex::new(BTreeMap::new()),
+            delivery_latency: Histogram::latency(),
+            stages: Default::default(),
+            throughput: RateMeter::new(),
+            dropped: Default::default(),
+            queue_high_watermark: AtomicUsize::new(0),
+            oldest_enqueued: AtomicU64::new(0),
+            redactions: Arc::new(AtomicU64::new(0)),
+            worker_heartbeat: AtomicU64::new(0),
+            ready: AtomicBool::new(false),
+            #[cfg(not(target_arch = "wasm32"))]
+            gateways: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Zeroes every counter and histogram and forgets the handlers' statistics, e.g.
+    /// between test cases. Gauges describing the current state, such as the queue size
+    /// and readiness, are kept. Use a [`MetricsWindow`] to read recent counts without
+    /// disturbing exporters.
+    pub fn reset(&self) {
+        self.logs_processed.store(0, Ordering::SeqCst);
+        self.errors.store(0, Ordering::SeqCst);
+        for count in self.builtin_levels.iter().chain(&self.dropped) {
+            count.store(0, Ordering::Relaxed);
+        }
+        self.custom_levels.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        self.delivery_latency.reset();
+        for stage in &self.stages {
+            stage.reset();
+        }
+        self.throughput.reset();
+        self.redactions.store(0, Ordering::Relaxed);
+        self.queue_high_watermark
+            .store(self.queue_size.load(Ordering::SeqCst), Ordering::Relaxed);
+        self.handlers.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Counts a processed record at `level`.
+    pub fn increment_level(&self, level: LogLevel) {
+        match LogLevel::BUILTIN.iter().position(|builtin| *builtin == level) {
+            Some(index) => {
+                self.builtin_levels[index].fetch_add(1, Ordering::Relaxed);
+            }
+            None => {
+                *self
+                    .custom_levels
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .entry(level.severity())
+                    .or_default() += 1;
+            }
+        }
+        #[cfg(feature = "metrics")]
+        facade::increment("logengine_logs_total", Some(("level", &level.as_str().to_lowercase())), 1);
+    }
+
+    /// Returns how many records were processed at `level`.
+    pub fn level_count(&self, level: LogLevel) -> u64 {
+        match LogLevel::BUILTIN.iter().position(|builtin| *builtin == level) {
+            Some(index) => self.builtin_levels[index].load(Ordering::Relaxed),
+            None => self
+                .custom_levels
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(&level.severity())
+                .copied()
+                .unwrap_or(0),
+        }
+    }
+
+    /// Records the time a record took from being enqueued to being emitted by its handlers.
+    pub fn observe_delivery(&self, elapsed: Duration) {
+        self.delivery_latency.observe(elapsed.as_secs_f64());
+        facade::histogram("logengine_record_latency_seconds", None, elapsed.as_secs_f64());
+    }
+
+    /// Returns the end-to-end latency distribution, e.g. for
+    /// `delivery_latency().quantile(0.99)`.
+    pub fn delivery_latency(&self) -> HistogramSnapshot {
+        self.delivery_latency.snapshot()
+    }
+
+    /// Records the time one record spent in `stage`.
+    pub fn observe_stage(&self, stage: Stage, elapsed: Duration) {
+        self.stages[stage as usize].observe(elapsed.as_secs_f64());
+        facade::histogram(
+            "logengine_stage_duration_seconds",
+            Some(("stage", stage.as_str())),
+            elapsed.as_secs_f64(),
+        );
+    }
+
+    /// Returns the distribution of the time records spent in `stage`.
+    pub fn stage_duration(&self, stage: Stage) -> HistogramSnapshot {
+        self.stages[stage as usize].snapshot()
+    }
+
+    /// Returns the statistics of the handler with key `name`, its name and
+    /// [`HandlerId`](crate::logger::HandlerId) such as `file#2`, creating them on first use.
+    pub fn handler(&self, name: &str) -> Arc<HandlerMetrics> {
+        if let Some(metrics) = self.handlers.read().unwrap_or_else(|e| e.into_inner()).get(name) {
+            return metrics.clone();
+        }
+        self.handlers
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(HandlerMetrics::new(name)))
+            .clone()
+    }
+
+    /// Increments the log count counter.
+    pub fn increment_log_count(&self) {
+        self.logs_processed.fetch_add(1, Ordering::SeqCst);
+        self.throughput.record(clock_seconds(), 1);
+        facade::increment("logengine_logs_processed_total", None, 1);
+    }
+
+    /// Returns the records written per second, averaged over the last `window` whole
+    /// seconds (1 to 60).
+    pub fn logs_per_second(&self, window: Duration) -> f64 {
+        self.throughput.rate(clock_seconds(), window.as_secs())
+    }
+
+    /// Increments the error counter.
+    pub fn increment_error(&self) {
+        self.errors.fetch_add(1, Ordering::SeqCst);
+        facade::increment("logengine_errors_total", None, 1);
+    }
+
+    /// Counts a record dropped for `reason`.
+    pub fn increment_dropped(&self, reason: DropReason) {
+        self.dropped[reason as usize].fetch_add(1, Ordering::Relaxed);
+        facade::increment("logengine_records_dropped_total", Some(("reason", reason.as_str())), 1);
+    }
+
+    /// Returns how many records were dropped for `reason`.
+    pub fn dropped(&self, reason: DropReason) -> u64 {
+        self.dropped[reason as usize].load(Ordering::Relaxed)
+    }
+
+    /// Sets the current queue size gauge.
+    pub fn set_queue_size(&self, size: usize) {
+        self.queue_size.store(size, Ordering::SeqCst);
+        facade::gauge("logengine_queue_size", size as f64);
+    }
+
+    /// Records that a message was enqueued, leaving the queue `depth` messages deep.
+    pub fn record_enqueue(&self, depth: usize) {
+        let high = self.queue_high_watermark.fetch_max(depth, Ordering::Relaxed).max(depth);
+        facade::gauge("logengine_queue_high_watermark", high as f64);
+        let _ = self
+            .oldest_enqueued
+            .compare_exchange(0, clock_ticks(), Ordering::Relaxed, Ordering::Relaxed);
+    }
+
+    /// Records that the worker is about to drain the queue. Messages enqueued from now on
+    /// start a new oldest-message clock.
+    pub fn record_drain(&self) {
+        self.oldest_enqueued.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns the deepest the queue has been since the logger started.
+    pub fn queue_high_watermark(&self) -> usize {
+        self.queue_high_watermark.load(Ordering::Relaxed)
+    }
+
+    /// Returns how long the oldest queued message has been waiting, or `None` if the queue
+    /// was empty at the last drain and nothing has been enqueued since. A message enqueued
+    /// while the worker drains may be counted until the next drain, so the age errs high.
+    pub fn oldest_queued_age(&self) -> Option<Duration> {
+        match self.oldest_enqueued.load(Ordering::Relaxed) {
+            0 => None,
+            enqueued => Some(clock_start().elapsed().saturating_sub(Duration::from_nanos(enqueued - 1))),
+        }
+    }
+
+    /// Returns the counts of custom levels, by lowercase level name.
+    fn custom_level_counts(&self) -> Vec<(String, u64)> {
+        // Copy the counts first: naming a custom level consults the level registry
+        let custom: Vec<(u16, u64)> = self
+            .custom_levels
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(severity, count)| (*severity, *count))
+            .collect();
+        custom
+            .into_iter()
+            .map(|(severity, count)| (LogLevel::Custom(severity).as_str().to_lowercase(), count))
+            .collect()
+    }
+
+    /// Returns the per-handler statistics, by handler name and id.
+    fn handler_list(&self) -> Vec<(String, Arc<HandlerMetrics>)> {
+        self.handlers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(name, metrics)| (name.clone(), metrics.clone()))
+            .collect()
+    }
+
+    /// Collects every metric into format-independent families, for exporters.
+    pub fn collect(&self) -> Vec<MetricFamily> {
+        use MetricKind::{Counter, Gauge};
+
+        let mut levels: Vec<(String, f64)> = LogLevel::BUILTIN
+            .iter()
+            .map(|level| (level.as_str().to_lowercase(), self.level_count(*level) as f64))
+            .collect();
+        levels.extend(self.custom_level_counts().into_iter().map(|(level, count)| (level, count as f64)));
+        let handlers = self.handler_list();
+        let per_handler = |field: fn(&HandlerMetrics) -> &AtomicU64| {
+            handlers
+                .iter()
+                .map(|(name, metrics)| (name.clone(), field(metrics).load(Ordering::Relaxed) as f64))
+                .collect::<Vec<_>>()
+        };
+
+        vec![
+            MetricFamily::scalar(
+                "logs_processed",
+                "Log records written to the handlers.",
+                Counter,
+                self.logs_processed.load(Ordering::SeqCst) as f64,
+            ),
+            MetricFamily::labeled("logs", "Log records processed, by level.", Counter, "level", levels),
+            MetricFamily::labeled(
+                "logs_per_second",
+                "Records written per second, averaged over a moving window.",
+                Gauge,
+                "window",
+                THROUGHPUT_WINDOWS.iter().map(|(seconds, window)| {
+                    (window.to_string(), self.logs_per_second(Duration::from_secs(*seconds)))
+                }),
+            ),
+            MetricFamily::scalar(
+                "errors",
+                "Records that failed to be secured or emitted.",
+                Counter,
+                self.errors.load(Ordering::SeqCst) as f64,
+            ),
+            MetricFamily::histograms(
+                "record_latency_seconds",
+                "Time from enqueueing a record to the end of its last handler emit.",
+                "",
+                [(String::new(), self.delivery_latency())],
+            ),
+            MetricFamily::histograms(
+                "stage_duration_seconds",
+                "Time a record spent in each stage of the worker's pipeline.",
+                "stage",
+                Stage::ALL
+                    .iter()
+                    .map(|stage| (stage.as_str().to_string(), self.stage_duration(*stage))),
+            ),
+            MetricFamily::labeled(
+                "records_dropped",
+                "Records dropped before reaching any handler, by reason.",
+                Counter,
+                "reason",
+                DropReason::ALL
+                    .iter()
+                    .map(|reason| (reason.as_str().to_string(), self.dropped(*reason) as f64)),
+            ),
+            MetricFamily::scalar(
+                "redactions",
+                "Values redacted or masked by the sanitizer.",
+                Counter,
+                self.redactions.load(Ordering::Relaxed) as f64,
+            ),
+            MetricFamily::scalar(
+                "queue_size",
+                "Records waiting in the queue.",
+                Gauge,
+                self.queue_size.load(Ordering::SeqCst) as f64,
+            ),
+            MetricFamily::scalar(
+                "queue_high_watermark",
+                "Most records waiting in the queue at once since the logger started.",
+                Gauge,
+                self.queue_high_watermark() as f64,
+            ),
+            MetricFamily::scalar(
+                "oldest_queued_age_seconds",
+                "How long the oldest queued record has been waiting.",
+                Gauge,
+                self.oldest_queued_age().unwrap_or_default().as_secs_f64(),
+            ),
+            MetricFamily::labeled(
+                "handler_emits",
+                "Calls to a handler's emit.",
+                Counter,
+                "handler",
+                per_handler(|m| &m.emits),
+            ),
+            MetricFamily::labeled(
+                "handler_failures",
+                "Emits that returned an error.",
+                Counter,
+                "handler",
+                per_handler(|m| &m.failures),
+            ),
+            MetricFamily::labeled(
+                "handler_retries",
+                "Writes retried by a handler.",
+                Counter,
+                "handler",
+                per_handler(|m| &m.retries),
+            ),
+            MetricFamily::labeled(
+                "handler_bytes_written",
+                "Bytes of formatted records emitted successfully.",
+                Counter,
+                "handler",
+                per_handler(|m| &m.bytes_written),
+            ),
+            MetricFamily::labeled(
+                "handler_status",
+                "Handler health: 2 ok, 1 degraded (last emit failed), 0 down (health check failed).",
+                Gauge,
+                "handler",
+                handlers.iter().map(|(name, metrics)| {
+                    let status = match metrics.health().status {
+                        HandlerStatus::Ok => 2.0,
+                        HandlerStatus::Degraded => 1.0,
+                        HandlerStatus::Down => 0.0,
+                    };
+                    (name.clone(), status)
+                }),
+            ),
+            MetricFamily::histograms(
+                "handler_emit_duration_seconds",
+                "Time spent in a handler's emit, including retries.",
+                "handler",
+                handlers
+                    .iter()
+                    .map(|(name, metrics)| (name.clone(), metrics.latency.snapshot())),
+            ),
+        ]
+    }
+
+    /// Records that the worker is alive and making progress.
+    pub fn record_worker_heartbeat(&self) {
+        self.worker_heartbeat.store(clock_ticks(), Ordering::Relaxed);
+    }
+
+    /// Marks the logger as ready to accept records, or not once it is shutting down.
+    pub fn set_ready(&self, ready: bool) {
+        self.ready.store(ready, Ordering::Relaxed);
+    }
+
+    /// Returns the health of the worker and of every handler that has emitted.
+    pub fn health(&self) -> HealthReport {
+        let worker_alive = match self.worker_heartbeat.load(Ordering::Relaxed) {
+            0 => false,
+            beat => clock_ticks().saturating_sub(beat) <= WORKER_STALL_TIMEOUT.as_nanos() as u64,
+        };
+        let handlers = self
+            .handler_list()
+            .into_iter()
+            .map(|(name, metrics)| (name, metrics.health()))
+            .collect();
+        HealthReport { worker_alive, handlers }
+    }
+
+    /// Returns `true` while the worker is running and the logger is not shutting down.
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Relaxed) && self.health().worker_alive
+    }
+
+    /// Renders every metric as JSON, keyed by name without the namespace.
+    pub fn render_json(&self) -> serde_json::Value {
+        let families = self
+            .collect()
+            .into_iter()
+            .map(|family| {
+                let kind = match family.kind {
+                    MetricKind::Counter => "counter",
+                    MetricKind::Gauge => "gauge",
+                    MetricKind::Histogram => "histogram",
+                };
+                let samples: Vec<serde_json::Value> = family
+                    .samples
+                    .into_iter()
+                    .map(|sample| {
+                        let labels: serde_json::Map<String, serde_json::Value> =
+                            sample.labels.into_iter().map(|(key, value)| (key, value.into())).collect();
+                        match sample.value {
+                            MetricValue::Scalar(value) => json!({ "labels": labels, "value": value }),
+                            MetricValue::Histogram(snapshot) => json!({
+                                "labels": labels,
+                                "count": snapshot.count,
+                                "sum": snapshot.sum,
+                                "buckets": snapshot.buckets,
+                            }),
+                        }
+                    })
+                    .collect();
+                (family.name.to_string(), json!({ "type": kind, "help": family.help, "samples": samples }))
+            })
+            .collect();
+        serde_json::Value::Object(families)
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format, with every name
+    /// prefixed by [`NAMESPACE`].
+    pub fn render_prometheus(&self) -> String {
+        let mut page = Exposition::new();
+        for family in self.collect() {
+            page.family(&family);
+        }
+        page.finish()
+    }
+}
@@ -0,0 +1,133 @@
+use super::{HistogramSnapshot, MetricFamily, MetricKind, MetricValue, MetricsManager, NAMESPACE};
+use opentelemetry::metrics::Meter;
+use opentelemetry::KeyValue;
+use std::sync::Arc;
+
+/// Quantiles of each histogram reported through the `.quantile` gauge.
+const QUANTILES: &[f64] = &[0.5, 0.95, 0.99];
+
+impl MetricsManager {
+    /// Registers the logger's metrics as observable instruments on `meter`, so they are
+    /// exported by whatever reader the meter's `MeterProvider` was built with, such as an
+    /// OTLP exporter.
+    ///
+    /// Instruments are named `logengine.<metric>`, and labels become attributes. Counters
+    /// and gauges map to observable counters and gauges. OpenTelemetry has no observable
+    /// histogram, so each histogram is reported as a `<metric>.count` and `<metric>.sum`
+    /// counter plus a `<metric>.quantile` gauge for p50, p95 and p99.
+    pub fn register_otel(self: &Arc<Self>, meter: &Meter) {
+        for family in self.collect() {
+            let name = format!("{}.{}", NAMESPACE, family.name);
+            let metrics = self.clone();
+            let family_name = family.name;
+            match family.kind {
+                MetricKind::Counter => {
+                    meter
+                        .f64_observable_counter(name)
+                        .with_description(family.help)
+                        .with_callback(move |observer| {
+                            for (value, attributes) in scalars(&metrics, family_name) {
+                                observer.observe(value, &attributes);
+                            }
+                        })
+                        .build();
+                }
+                MetricKind::Gauge => {
+                    meter
+                        .f64_observable_gauge(name)
+                        .with_description(family.help)
+                        .with_callback(move |observer| {
+                            for (value, attributes) in scalars(&metrics, family_name) {
+                                observer.observe(value, &attributes);
+                            }
+                        })
+                        .build();
+                }
+                MetricKind::Histogram => self.register_histogram(meter, &name, &family),
+            }
+        }
+    }
+
+    fn register_histogram(self: &Arc<Self>, meter: &Meter, name: &str, family: &MetricFamily) {
+        let family_name = family.name;
+        let metrics = self.clone();
+        meter
+            .u64_observable_counter(format!("{}.count", name))
+            .with_description(family.help)
+            .with_callback(move |observer| {
+                for (snapshot, attributes) in histograms(&metrics, family_name) {
+                    observer.observe(snapshot.count, &attributes);
+                }
+            })
+            .build();
+        let metrics = self.clone();
+        meter
+            .f64_observable_counter(format!("{}.sum", name))
+            .with_description(family.help)
+            .with_callback(move |observer| {
+                for (snapshot, attributes) in histograms(&metrics, family_name) {
+                    observer.observe(snapshot.sum, &attributes);
+                }
+            })
+            .build();
+        let metrics = self.clone();
+        meter
+            .f64_observable_gauge(format!("{}.quantile", name))
+            .with_description(family.help)
+            .with_callback(move |observer| {
+                for (snapshot, attributes) in histograms(&metrics, family_name) {
+                    for q in QUANTILES {
+                        if let Some(value) = snapshot.quantile(*q) {
+                            let mut attributes = attributes.clone();
+                            attributes.push(KeyValue::new("quantile", q.to_string()));
+                            observer.observe(value, &attributes);
+                        }
+                    }
+                }
+            })
+            .build();
+    }
+}
+
+/// Returns the current samples of the family called `name`.
+fn samples(metrics: &MetricsManager, name: &str) -> Vec<(MetricValue, Vec<KeyValue>)> {
+    metrics
+        .collect()
+        .into_iter()
+        .find(|family| family.name == name)
+        .map(|family| {
+            family
+                .samples
+                .into_iter()
+                .map(|sample| {
+                    let attributes = sample
+                        .labels
+                        .into_iter()
+                        .map(|(key, value)| KeyValue::new(key, value))
+                        .collect();
+                    (sample.value, attributes)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn scalars(metrics: &MetricsManager, name: &str) -> Vec<(f64, Vec<KeyValue>)> {
+    samples(metrics, name)
+        .into_iter()
+        .filter_map(|(value, attributes)| match value {
+            MetricValue::Scalar(value) => Some((value, attributes)),
+            MetricValue::Histogram(_) => None,
+        })
+        .collect()
+}
+
+fn histograms(metrics: &MetricsManager, name: &str) -> Vec<(HistogramSnapshot, Vec<KeyValue>)> {
+    samples(metrics, name)
+        .into_iter()
+        .filter_map(|(value, attributes)| match value {
+            MetricValue::Histogram(snapshot) => Some((snapshot, attributes)),
+            MetricValue::Scalar(_) => None,
+        })
+        .collect()
+}
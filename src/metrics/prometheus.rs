@@ -0,0 +1,91 @@
+use super::{HistogramSnapshot, MetricFamily, MetricKind, MetricValue};
+use std::fmt::Write;
+
+/// Prefix of every exported metric name.
+pub const NAMESPACE: &str = "logengine";
+
+/// Content type of the Prometheus text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Builds a page in the Prometheus text exposition format.
+#[derive(Default)]
+pub(crate) struct Exposition {
+    out: String,
+}
+
+impl Exposition {
+    pub(crate) fn new() -> Self {
+        Exposition::default()
+    }
+
+    /// Writes a family, prefixing its name with [`NAMESPACE`] and suffixing counters
+    /// with `_total`.
+    pub(crate) fn family(&mut self, family: &MetricFamily) {
+        let (name, kind) = match family.kind {
+            MetricKind::Counter => (format!("{}_{}_total", NAMESPACE, family.name), "counter"),
+            MetricKind::Gauge => (format!("{}_{}", NAMESPACE, family.name), "gauge"),
+            MetricKind::Histogram => (format!("{}_{}", NAMESPACE, family.name), "histogram"),
+        };
+        self.header(&name, family.help, kind);
+        for sample in &family.samples {
+            let labels: Vec<(&str, &str)> =
+                sample.labels.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect();
+            match &sample.value {
+                MetricValue::Scalar(value) => self.sample(&name, &labels, *value),
+                MetricValue::Histogram(snapshot) => self.histogram(&name, &labels, snapshot),
+            }
+        }
+    }
+
+    pub(crate) fn finish(self) -> String {
+        self.out
+    }
+
+    fn header(&mut self, name: &str, help: &str, kind: &str) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help.replace('\\', "\\\\").replace('\n', "\\n"));
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+    }
+
+    fn histogram(&mut self, name: &str, labels: &[(&str, &str)], snapshot: &HistogramSnapshot) {
+        let bucket_name = format!("{}_bucket", name);
+        for (bound, count) in &snapshot.buckets {
+            let le = format_value(*bound);
+            let bucket_labels: Vec<(&str, &str)> = labels.iter().copied().chain([("le", le.as_str())]).collect();
+            self.sample(&bucket_name, &bucket_labels, *count as f64);
+        }
+        let bucket_labels: Vec<(&str, &str)> = labels.iter().copied().chain([("le", "+Inf")]).collect();
+        self.sample(&bucket_name, &bucket_labels, snapshot.count as f64);
+        self.sample(&format!("{}_sum", name), labels, snapshot.sum);
+        self.sample(&format!("{}_count", name), labels, snapshot.count as f64);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.out.push_str(name);
+        write_labels(&mut self.out, labels);
+        let _ = writeln!(self.out, " {}", format_value(value));
+    }
+}
+
+/// Writes `{key="value",...}`, escaping values; nothing for an empty label set.
+pub(crate) fn write_labels(out: &mut String, labels: &[(&str, &str)]) {
+    if labels.is_empty() {
+        return;
+    }
+    out.push('{');
+    for (index, (key, value)) in labels.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        let value = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+        let _ = write!(out, "{}=\"{}\"", key, value);
+    }
+    out.push('}');
+}
+
+pub(crate) fn format_value(value: f64) -> String {
+    if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,121 @@
+use super::{MetricsError, MetricsManager, CONTENT_TYPE};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+impl MetricsManager {
+    /// Pushes the metrics to a Prometheus Pushgateway at `url` (e.g. `http://gateway:9091`)
+    /// under `job` now and then every `interval`, for processes too short-lived to be
+    /// scraped. The gateway is also pushed to one last time by
+    /// [`Logger::shutdown`](crate::logger::Logger::shutdown), so the final values survive
+    /// the process.
+    ///
+    /// Each push replaces the metrics the gateway holds for `job`. Runs until the returned
+    /// future is dropped; failed pushes are reported on stderr and retried at the next
+    /// interval. Only plain `http://` URLs are supported.
+    pub async fn push_to_gateway(&self, url: &str, job: &str, interval: Duration) -> Result<(), MetricsError> {
+        let target = GatewayTarget::parse(url, job)?;
+        self.gateways.lock().unwrap_or_else(|e| e.into_inner()).push(target.clone());
+
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = target.push(&self.render_prometheus()).await {
+                eprintln!("Failed to push metrics to {}: {}", url, e);
+            }
+        }
+    }
+
+    /// Pushes the current metrics to every gateway registered with
+    /// [`push_to_gateway`](Self::push_to_gateway), returning the first error.
+    pub async fn push_to_gateways(&self) -> Result<(), MetricsError> {
+        let targets = self.gateways.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let body = self.render_prometheus();
+        let mut result = Ok(());
+        for target in targets {
+            if let Err(e) = target.push(&body).await {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+}
+
+/// Where and under which job metrics are pushed.
+#[derive(Debug, Clone)]
+pub(crate) struct GatewayTarget {
+    /// `host:port` to connect to.
+    addr: String,
+    host: String,
+    /// Request path, ending in `/metrics/job/<job>`.
+    path: String,
+}
+
+impl GatewayTarget {
+    fn parse(url: &str, job: &str) -> Result<Self, MetricsError> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| MetricsError::PushError(format!("Unsupported Pushgateway URL: {}", url)))?;
+        let (host, base) = rest.split_once('/').map_or((rest, ""), |(host, base)| (host, base));
+        if host.is_empty() {
+            return Err(MetricsError::PushError(format!("Invalid Pushgateway URL: {}", url)));
+        }
+        let addr = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{}:80", host)
+        };
+        let base = base.trim_end_matches('/');
+        let path = if base.is_empty() {
+            format!("/metrics/job/{}", encode_path_segment(job))
+        } else {
+            format!("/{}/metrics/job/{}", base, encode_path_segment(job))
+        };
+        Ok(GatewayTarget {
+            addr,
+            host: host.to_string(),
+            path,
+        })
+    }
+
+    async fn push(&self, body: &str) -> Result<(), MetricsError> {
+        let mut stream = TcpStream::connect(&self.addr)
+            .await
+            .map_err(|e| MetricsError::IoError(e.to_string()))?;
+        let request = format!(
+            "PUT {} HTTP/1.1\r\nHost: {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path,
+            self.host,
+            CONTENT_TYPE,
+            body.len(),
+            body
+        );
+        stream
+            .write_all(request.as_bytes())
+            .await
+            .map_err(|e| MetricsError::IoError(e.to_string()))?;
+
+        let mut status = String::new();
+        BufReader::new(&mut stream)
+            .read_line(&mut status)
+            .await
+            .map_err(|e| MetricsError::IoError(e.to_string()))?;
+        match status.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(()),
+            _ => Err(MetricsError::PushError(format!("Pushgateway responded: {}", status.trim()))),
+        }
+    }
+}
+
+/// Percent-encodes everything but unreserved characters.
+fn encode_path_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
@@ -0,0 +1,66 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Seconds of history kept, plus the second in progress.
+const SLOTS: usize = 61;
+
+/// Counts events per second over the last minute, for moving-average rates.
+///
+/// Rates are computed over whole seconds that have ended, so the second in progress does
+/// not drag the average down.
+pub struct RateMeter {
+    /// Events counted in each second, indexed by the second modulo [`SLOTS`].
+    counts: [AtomicU64; SLOTS],
+    /// The second each slot currently counts, plus one; 0 for a slot never used.
+    seconds: [AtomicU64; SLOTS],
+}
+
+impl Default for RateMeter {
+    fn default() -> Self {
+        RateMeter {
+            counts: std::array::from_fn(|_| AtomicU64::new(0)),
+            seconds: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+}
+
+impl RateMeter {
+    pub fn new() -> Self {
+        RateMeter::default()
+    }
+
+    /// Forgets every event counted so far.
+    pub fn reset(&self) {
+        for stamp in &self.seconds {
+            stamp.store(0, Ordering::Release);
+        }
+    }
+
+    /// Counts `events` in the second `now`, given as whole seconds since a fixed start.
+    pub(crate) fn record(&self, now: u64, events: u64) {
+        let slot = (now % SLOTS as u64) as usize;
+        let stamp = now + 1;
+        if self.seconds[slot].swap(stamp, Ordering::AcqRel) != stamp {
+            // The slot last counted a second that has left the window
+            self.counts[slot].store(0, Ordering::Release);
+        }
+        self.counts[slot].fetch_add(events, Ordering::AcqRel);
+    }
+
+    /// Returns the average events per second over the `window` seconds (at most 60) that
+    /// ended before the second `now`.
+    pub(crate) fn rate(&self, now: u64, window: u64) -> f64 {
+        let window = window.clamp(1, SLOTS as u64 - 1);
+        let total: u64 = (1..=window)
+            .filter_map(|ago| now.checked_sub(ago))
+            .map(|second| {
+                let slot = (second % SLOTS as u64) as usize;
+                if self.seconds[slot].load(Ordering::Acquire) == second + 1 {
+                    self.counts[slot].load(Ordering::Acquire)
+                } else {
+                    0
+                }
+            })
+            .sum();
+        total as f64 / window as f64
+    }
+}
@@ -0,0 +1,259 @@
+use super::{MetricsError, MetricsManager, CONTENT_TYPE};
+use crate::config::ConfigurationManager;
+use serde_json::json;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::{watch, Semaphore};
+use tokio::task::JoinHandle;
+
+#[cfg(feature = "tls")]
+pub use tokio_rustls::rustls;
+
+const JSON: &str = "application/json";
+
+/// Longest request head read before the request is rejected.
+const MAX_REQUEST_HEAD: u64 = 8 * 1024;
+
+/// HTTP server for the metrics, health and readiness endpoints.
+///
+/// Serves:
+///
+/// - `GET /metrics`: [`MetricsManager::render_prometheus`],
+/// - `GET /metrics.json`: [`MetricsManager::render_json`],
+/// - `GET /healthz`: the [`MetricsManager::health`] report, with status 503 when the
+///   worker has stalled or a handler's last emit failed,
+/// - `GET /readyz`: status 200 once the logger accepts records, 503 otherwise,
+/// - `GET /config`: the [`ConfigurationManager::effective_config`], with secrets
+///   redacted, when a manager was given with [`config`](Self::config).
+///
+/// Connections beyond the limit are answered with 503 and closed, and a client that
+/// does not send its request within the read timeout is disconnected.
+///
+/// ```no_run
+/// # async fn run(logger: std::sync::Arc<log_engine_v1::logger::Logger>) {
+/// use log_engine_v1::metrics::MetricsServer;
+///
+/// let server = MetricsServer::new("127.0.0.1:9100")
+///     .max_connections(16)
+///     .spawn(logger.metrics.clone())
+///     .await
+///     .unwrap();
+/// // ...
+/// server.shutdown().await;
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct MetricsServer {
+    addr: String,
+    max_connections: usize,
+    read_timeout: Duration,
+    config: Option<Arc<ConfigurationManager>>,
+    #[cfg(feature = "tls")]
+    tls: Option<tokio_rustls::TlsAcceptor>,
+}
+
+/// A running [`MetricsServer`], stopped with [`shutdown`](Self::shutdown).
+pub struct MetricsServerHandle {
+    local_addr: SocketAddr,
+    stop: watch::Sender<bool>,
+    task: JoinHandle<Result<(), MetricsError>>,
+}
+
+impl MetricsServer {
+    /// Creates a server listening on `addr` with at most 64 concurrent connections and a
+    /// 5 second read timeout.
+    pub fn new(addr: impl Into<String>) -> Self {
+        MetricsServer {
+            addr: addr.into(),
+            max_connections: 64,
+            read_timeout: Duration::from_secs(5),
+            config: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+
+    /// Sets how many connections are served at once.
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections.max(1);
+        self
+    }
+
+    /// Sets how long a client may take to send its request.
+    pub fn read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
+    /// Serves the effective configuration of `config` on `/config`, e.g. the Logger's
+    /// [`config_manager`](crate::logger::Logger::config_manager).
+    pub fn config(mut self, config: Arc<ConfigurationManager>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Serves HTTPS with `config` instead of plain HTTP.
+    #[cfg(feature = "tls")]
+    pub fn tls(mut self, config: Arc<rustls::ServerConfig>) -> Self {
+        self.tls = Some(tokio_rustls::TlsAcceptor::from(config));
+        self
+    }
+
+    /// Serves `metrics` until `shutdown` completes. Connections already accepted are
+    /// finished in the background.
+    pub async fn serve(
+        self,
+        metrics: Arc<MetricsManager>,
+        shutdown: impl Future<Output = ()>,
+    ) -> Result<(), MetricsError> {
+        let listener = self.bind().await?;
+        self.run(listener, metrics, shutdown).await
+    }
+
+    /// Binds the listener and serves `metrics` in a background task.
+    pub async fn spawn(self, metrics: Arc<MetricsManager>) -> Result<MetricsServerHandle, MetricsError> {
+        let listener = self.bind().await?;
+        let local_addr = listener.local_addr().map_err(|e| MetricsError::IoError(e.to_string()))?;
+        let (stop, mut stopped) = watch::channel(false);
+        let task = tokio::spawn(async move {
+            let shutdown = async move {
+                let _ = stopped.wait_for(|stopped| *stopped).await;
+            };
+            self.run(listener, metrics, shutdown).await
+        });
+        Ok(MetricsServerHandle { local_addr, stop, task })
+    }
+
+    async fn bind(&self) -> Result<TcpListener, MetricsError> {
+        TcpListener::bind(&self.addr)
+            .await
+            .map_err(|e| MetricsError::BindError(e.to_string()))
+    }
+
+    async fn run(
+        self,
+        listener: TcpListener,
+        metrics: Arc<MetricsManager>,
+        shutdown: impl Future<Output = ()>,
+    ) -> Result<(), MetricsError> {
+        let connections = Arc::new(Semaphore::new(self.max_connections));
+        tokio::pin!(shutdown);
+        loop {
+            let (socket, _) = tokio::select! {
+                _ = &mut shutdown => return Ok(()),
+                accepted = listener.accept() => accepted.map_err(|e| MetricsError::IoError(e.to_string()))?,
+            };
+            let Ok(permit) = connections.clone().try_acquire_owned() else {
+                tokio::spawn(respond_busy(socket));
+                continue;
+            };
+            let metrics = metrics.clone();
+            let server = self.clone();
+            tokio::spawn(async move {
+                #[cfg(feature = "tls")]
+                if let Some(acceptor) = &server.tls {
+                    let accept = tokio::time::timeout(server.read_timeout, acceptor.accept(socket));
+                    if let Ok(Ok(stream)) = accept.await {
+                        server.handle_connection(stream, &metrics).await;
+                    }
+                    drop(permit);
+                    return;
+                }
+                server.handle_connection(socket, &metrics).await;
+                drop(permit);
+            });
+        }
+    }
+
+    async fn handle_connection<S: AsyncRead + AsyncWrite + Unpin>(&self, mut stream: S, metrics: &MetricsManager) {
+        let mut request = String::new();
+        let read = {
+            let mut reader = BufReader::new((&mut stream).take(MAX_REQUEST_HEAD));
+            tokio::time::timeout(self.read_timeout, reader.read_line(&mut request)).await
+        };
+        let (status, content_type, body) = match read {
+            Ok(Ok(_)) if request.ends_with('\n') => route(metrics, self.config.as_deref(), &request),
+            Ok(Ok(_)) => ("400 Bad Request", "text/plain", "Bad request\n".into()),
+            // Timed out or failed: nothing useful can be sent back
+            _ => return,
+        };
+        write_response(&mut stream, status, content_type, &body).await;
+    }
+}
+
+impl MetricsServerHandle {
+    /// Returns the address the server is listening on, e.g. to find a port picked by the OS.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops accepting connections and waits for the accept loop to exit.
+    pub async fn shutdown(self) -> Result<(), MetricsError> {
+        let _ = self.stop.send(true);
+        self.task.await.map_err(|e| MetricsError::IoError(e.to_string()))?
+    }
+}
+
+impl MetricsManager {
+    /// Serves the metrics endpoints on `addr` with the default [`MetricsServer`] limits
+    /// until an error occurs. Use [`MetricsServer`] directly to be able to stop it.
+    pub async fn serve_metrics(self: &Arc<Self>, addr: &str) -> Result<(), MetricsError> {
+        println!("Metrics server running on {}", addr);
+        MetricsServer::new(addr)
+            .serve(self.clone(), std::future::pending())
+            .await
+    }
+}
+
+/// Returns the status line, content type and body answering a request line.
+fn route(
+    metrics: &MetricsManager,
+    config: Option<&ConfigurationManager>,
+    request: &str,
+) -> (&'static str, &'static str, String) {
+    let mut parts = request.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    if method != "GET" {
+        return ("405 Method Not Allowed", "text/plain", "Method not allowed\n".into());
+    }
+    let path = target.split('?').next().unwrap_or("");
+    match path {
+        "/metrics" => ("200 OK", CONTENT_TYPE, metrics.render_prometheus()),
+        "/metrics.json" => ("200 OK", JSON, metrics.render_json().to_string()),
+        "/healthz" => {
+            let health = metrics.health();
+            let status = if health.is_healthy() { "200 OK" } else { "503 Service Unavailable" };
+            (status, JSON, json!(health).to_string())
+        }
+        "/readyz" => {
+            let ready = metrics.is_ready();
+            let status = if ready { "200 OK" } else { "503 Service Unavailable" };
+            (status, JSON, json!({ "ready": ready }).to_string())
+        }
+        "/config" => match config {
+            Some(config) => ("200 OK", JSON, config.effective_config().to_string()),
+            None => ("404 Not Found", "text/plain", "Not found\n".into()),
+        },
+        _ => ("404 Not Found", "text/plain", "Not found\n".into()),
+    }
+}
+
+pub(crate) async fn respond_busy<S: AsyncWrite + Unpin>(mut stream: S) {
+    write_response(&mut stream, "503 Service Unavailable", "text/plain", "Too many connections\n").await;
+}
+
+pub(crate) async fn write_response<S: AsyncWrite + Unpin>(stream: &mut S, status: &str, content_type: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
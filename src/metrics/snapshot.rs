@@ -0,0 +1,94 @@
+use super::{DropReason, HandlerHealth, HistogramSnapshot, MetricsManager, Stage, THROUGHPUT_WINDOWS};
+use crate::utils::LogLevel;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// Point-in-time values of every metric, e.g. for an application's own status page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricsSnapshot {
+    pub logs_processed: u64,
+    /// Records processed per level, by lowercase level name.
+    pub logs_per_level: BTreeMap<String, u64>,
+    /// Records written per second, by window (`1s`, `10s`, `1m`).
+    pub logs_per_second: BTreeMap<String, f64>,
+    pub errors: u64,
+    /// Records dropped before reaching any handler, by reason.
+    pub dropped: BTreeMap<String, u64>,
+    pub redactions: u64,
+    pub queue_size: u64,
+    pub queue_high_watermark: u64,
+    /// Age of the oldest queued record in seconds, if any is queued.
+    pub oldest_queued_age_seconds: Option<f64>,
+    /// Time from enqueueing a record to the end of its last handler emit, in seconds.
+    pub record_latency: HistogramSnapshot,
+    /// Time a record spent in each pipeline stage, by stage name, in seconds.
+    pub stage_durations: BTreeMap<String, HistogramSnapshot>,
+    pub handlers: BTreeMap<String, HandlerSnapshot>,
+}
+
+/// Point-in-time statistics of the handlers sharing one name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HandlerSnapshot {
+    pub emits: u64,
+    pub failures: u64,
+    pub retries: u64,
+    pub bytes_written: u64,
+    /// Time spent in `emit`, in seconds.
+    pub latency: HistogramSnapshot,
+    pub health: HandlerHealth,
+}
+
+impl MetricsManager {
+    /// Returns the current value of every metric.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let mut logs_per_level: BTreeMap<String, u64> = LogLevel::BUILTIN
+            .iter()
+            .map(|level| (level.as_str().to_lowercase(), self.level_count(*level)))
+            .collect();
+        logs_per_level.extend(self.custom_level_counts());
+
+        let handlers = self
+            .handler_list()
+            .into_iter()
+            .map(|(name, metrics)| {
+                let snapshot = HandlerSnapshot {
+                    emits: metrics.emits.load(Ordering::Relaxed),
+                    failures: metrics.failures.load(Ordering::Relaxed),
+                    retries: metrics.retries.load(Ordering::Relaxed),
+                    bytes_written: metrics.bytes_written.load(Ordering::Relaxed),
+                    latency: metrics.latency.snapshot(),
+                    health: metrics.health(),
+                };
+                (name, snapshot)
+            })
+            .collect();
+
+        MetricsSnapshot {
+            logs_processed: self.logs_processed.load(Ordering::SeqCst) as u64,
+            logs_per_level,
+            logs_per_second: THROUGHPUT_WINDOWS
+                .iter()
+                .map(|(seconds, window)| {
+                    (window.to_string(), self.logs_per_second(Duration::from_secs(*seconds)))
+                })
+                .collect(),
+            errors: self.errors.load(Ordering::SeqCst) as u64,
+            dropped: DropReason::ALL
+                .iter()
+                .map(|reason| (reason.as_str().to_string(), self.dropped(*reason)))
+                .collect(),
+            redactions: self.redactions.load(Ordering::Relaxed),
+            queue_size: self.queue_size.load(Ordering::SeqCst) as u64,
+            queue_high_watermark: self.queue_high_watermark() as u64,
+            oldest_queued_age_seconds: self.oldest_queued_age().map(|age| age.as_secs_f64()),
+            record_latency: self.delivery_latency(),
+            stage_durations: Stage::ALL
+                .iter()
+                .map(|stage| (stage.as_str().to_string(), self.stage_duration(*stage)))
+                .collect(),
+            handlers,
+        }
+    }
+}
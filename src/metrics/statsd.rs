@@ -0,0 +1,182 @@
+use super::{MetricFamily, MetricKind, MetricValue, MetricsError, MetricsManager, NAMESPACE};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+/// Largest datagram sent, leaving room for IP and UDP headers within a typical MTU.
+const MAX_DATAGRAM: usize = 1432;
+
+/// Quantiles of each histogram sent as gauges, with their name suffixes.
+const QUANTILES: &[(f64, &str)] = &[(0.5, "p50"), (0.95, "p95"), (0.99, "p99")];
+
+/// Periodically pushes the logger's metrics to a StatsD or DogStatsD endpoint over UDP.
+///
+/// Counters are sent as the increase since the previous push, gauges as their current
+/// value, and histograms as a count and sum plus p50/p95/p99 gauges. With DogStatsD,
+/// labels and the exporter's tags are sent as `#key:value` tags; plain StatsD has no tags,
+/// so label values are appended to the metric name instead (`logengine.logs.error`).
+///
+/// ```no_run
+/// # async fn run(logger: std::sync::Arc<log_engine_v1::logger::Logger>) {
+/// use log_engine_v1::metrics::StatsdExporter;
+///
+/// let exporter = StatsdExporter::new("127.0.0.1:8125").dogstatsd(true).tag("service", "api");
+/// tokio::spawn(exporter.run(logger.metrics.clone()));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StatsdExporter {
+    addr: String,
+    prefix: String,
+    tags: Vec<(String, String)>,
+    interval: Duration,
+    dogstatsd: bool,
+}
+
+impl StatsdExporter {
+    /// Creates an exporter sending plain StatsD to `addr` every 10 seconds, with metric
+    /// names prefixed by [`NAMESPACE`].
+    pub fn new(addr: impl Into<String>) -> Self {
+        StatsdExporter {
+            addr: addr.into(),
+            prefix: NAMESPACE.to_string(),
+            tags: Vec::new(),
+            interval: Duration::from_secs(10),
+            dogstatsd: false,
+        }
+    }
+
+    /// Sets the prefix of every metric name.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Adds a tag sent with every metric; only used with DogStatsD.
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets how often metrics are pushed.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sends labels as DogStatsD tags.
+    pub fn dogstatsd(mut self, enabled: bool) -> Self {
+        self.dogstatsd = enabled;
+        self
+    }
+
+    /// Pushes `metrics` every interval until the returned future is dropped. Failed sends
+    /// are reported on stderr and retried at the next interval.
+    pub async fn run(self, metrics: Arc<MetricsManager>) -> Result<(), MetricsError> {
+        let socket = UdpSocket::bind("0.0.0.0:0")
+            .await
+            .map_err(|e| MetricsError::BindError(e.to_string()))?;
+        socket
+            .connect(&self.addr)
+            .await
+            .map_err(|e| MetricsError::IoError(e.to_string()))?;
+
+        let mut previous = HashMap::new();
+        let mut ticker = tokio::time::interval(self.interval);
+        loop {
+            ticker.tick().await;
+            for datagram in self.encode(&metrics.collect(), &mut previous) {
+                if let Err(e) = socket.send(datagram.as_bytes()).await {
+                    eprintln!("Failed to send StatsD metrics to {}: {}", self.addr, e);
+                }
+            }
+        }
+    }
+
+    /// Encodes `families` into datagrams of newline-separated metrics. `previous` holds
+    /// the counter values of the last push, keyed by line prefix, to send increases.
+    pub(crate) fn encode(&self, families: &[MetricFamily], previous: &mut HashMap<String, f64>) -> Vec<String> {
+        let mut lines = Vec::new();
+        for family in families {
+            for sample in &family.samples {
+                let (name, tags) = self.name_and_tags(family.name, &sample.labels);
+                match (&sample.value, family.kind) {
+                    (MetricValue::Scalar(value), MetricKind::Counter) => {
+                        self.counter(&mut lines, previous, &name, *value, &tags);
+                    }
+                    (MetricValue::Scalar(value), _) => lines.push(self.line(&name, *value, "g", &tags)),
+                    (MetricValue::Histogram(snapshot), _) => {
+                        self.counter(&mut lines, previous, &format!("{}.count", name), snapshot.count as f64, &tags);
+                        self.counter(&mut lines, previous, &format!("{}.sum", name), snapshot.sum, &tags);
+                        for (q, suffix) in QUANTILES {
+                            if let Some(value) = snapshot.quantile(*q) {
+                                lines.push(self.line(&format!("{}.{}", name, suffix), value, "g", &tags));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut datagrams: Vec<String> = Vec::new();
+        for line in lines {
+            match datagrams.last_mut() {
+                Some(datagram) if datagram.len() + 1 + line.len() <= MAX_DATAGRAM => {
+                    datagram.push('\n');
+                    datagram.push_str(&line);
+                }
+                _ => datagrams.push(line),
+            }
+        }
+        datagrams
+    }
+
+    /// Adds the increase of a counter since the last push, if any.
+    fn counter(&self, lines: &mut Vec<String>, previous: &mut HashMap<String, f64>, name: &str, value: f64, tags: &str) {
+        let key = format!("{}{}", name, tags);
+        let delta = value - previous.insert(key, value).unwrap_or(0.0);
+        if delta > 0.0 {
+            lines.push(self.line(name, delta, "c", tags));
+        }
+    }
+
+    fn line(&self, name: &str, value: f64, kind: &str, tags: &str) -> String {
+        format!("{}:{}|{}{}", name, value, kind, tags)
+    }
+
+    /// Builds the full metric name and, for DogStatsD, the tag suffix.
+    fn name_and_tags(&self, family: &str, labels: &[(String, String)]) -> (String, String) {
+        let mut name = if self.prefix.is_empty() {
+            family.to_string()
+        } else {
+            format!("{}.{}", self.prefix, family)
+        };
+        if !self.dogstatsd {
+            for (_, value) in labels {
+                name.push('.');
+                name.push_str(&sanitize(value));
+            }
+            return (name, String::new());
+        }
+        let tags: Vec<String> = self
+            .tags
+            .iter()
+            .chain(labels)
+            .map(|(key, value)| format!("{}:{}", sanitize(key), sanitize(value)))
+            .collect();
+        if tags.is_empty() {
+            (name, String::new())
+        } else {
+            (name, format!("|#{}", tags.join(",")))
+        }
+    }
+}
+
+/// Replaces characters with meaning in the StatsD line format.
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if matches!(c, ':' | '|' | '@' | ',' | '#' | '\n') || c.is_whitespace() { '_' } else { c })
+        .collect()
+}
@@ -0,0 +1,105 @@
+use super::MetricsManager;
+use crate::utils::Instant;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+/// Counts accumulated since a window was last taken, from [`MetricsWindow::take`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowSummary {
+    /// Length of the window.
+    #[serde(rename = "window_seconds", serialize_with = "as_seconds")]
+    pub duration: Duration,
+    pub logs_processed: u64,
+    pub errors: u64,
+    /// Records processed per level in the window, by lowercase level name.
+    pub logs_per_level: BTreeMap<String, u64>,
+    /// Records dropped in the window, by reason.
+    pub dropped: BTreeMap<String, u64>,
+}
+
+impl fmt::Display for WindowSummary {
+    /// Formats as `processed 12,402 logs, 3 errors in last 60s`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "processed {} logs, {} errors in last {}s",
+            thousands(self.logs_processed),
+            thousands(self.errors),
+            self.duration.as_secs()
+        )
+    }
+}
+
+/// A reset-on-read view of the metrics: each [`take`](Self::take) returns what happened
+/// since the previous one. The manager's own counters are left untouched, so any number
+/// of windows can be read independently of each other and of the exporters.
+pub struct MetricsWindow {
+    started: Instant,
+    logs_processed: u64,
+    errors: u64,
+    logs_per_level: BTreeMap<String, u64>,
+    dropped: BTreeMap<String, u64>,
+}
+
+impl MetricsManager {
+    /// Opens a window starting now.
+    pub fn window(&self) -> MetricsWindow {
+        let snapshot = self.snapshot();
+        MetricsWindow {
+            started: Instant::now(),
+            logs_processed: snapshot.logs_processed,
+            errors: snapshot.errors,
+            logs_per_level: snapshot.logs_per_level,
+            dropped: snapshot.dropped,
+        }
+    }
+}
+
+impl MetricsWindow {
+    /// Returns the counts since the window was opened or last taken, and starts a new one.
+    pub fn take(&mut self, metrics: &MetricsManager) -> WindowSummary {
+        let snapshot = metrics.snapshot();
+        let now = Instant::now();
+        let summary = WindowSummary {
+            duration: now.duration_since(self.started),
+            logs_processed: snapshot.logs_processed.saturating_sub(self.logs_processed),
+            errors: snapshot.errors.saturating_sub(self.errors),
+            logs_per_level: delta(&snapshot.logs_per_level, &self.logs_per_level),
+            dropped: delta(&snapshot.dropped, &self.dropped),
+        };
+        *self = MetricsWindow {
+            started: now,
+            logs_processed: snapshot.logs_processed,
+            errors: snapshot.errors,
+            logs_per_level: snapshot.logs_per_level,
+            dropped: snapshot.dropped,
+        };
+        summary
+    }
+}
+
+fn delta(current: &BTreeMap<String, u64>, previous: &BTreeMap<String, u64>) -> BTreeMap<String, u64> {
+    current
+        .iter()
+        .map(|(key, value)| (key.clone(), value.saturating_sub(previous.get(key).copied().unwrap_or(0))))
+        .collect()
+}
+
+fn as_seconds<S: serde::Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+/// Formats `value` with comma thousands separators.
+fn thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
@@ -0,0 +1,240 @@
+use crate::config::{HandlerConfig, HandlerKind, LogConfig, LEVEL_OFF};
+use crate::enrichers::{build_enricher, LogEnricher};
+use crate::filters::{build_filter, LevelFilter, LogFilter, ModuleLevelFilter};
+use crate::formatters::{Formatter, JsonFormatter, TextFormatter};
+use crate::handlers::LogHandler;
+use crate::logger::{HandlerId, LogMessage, LoggerError};
+use crate::plugins::{HandlerDecorator, LogProcessor, Plugin, PluginRegistry};
+use crate::security::Classification;
+use crate::utils::LogLevel;
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A handler paired with its filters, before an id is assigned.
+pub(crate) type FilteredHandler = (Arc<dyn LogHandler>, Vec<Arc<dyn LogFilter>>);
+
+/// A handler together with the filters gating what it receives.
+#[derive(Clone)]
+pub(crate) struct HandlerEntry {
+    pub(crate) id: HandlerId,
+    pub(crate) handler: Arc<dyn LogHandler>,
+    pub(crate) filters: Vec<Arc<dyn LogFilter>>,
+    /// Most sensitive classification the handler may receive.
+    pub(crate) clearance: Classification,
+    /// The configuration entry the handler was built from, if any.
+    pub(crate) source: Option<HandlerConfig>,
+    /// MAC of the last record the handler received, with hash chaining.
+    pub(crate) chain: Arc<Mutex<String>>,
+}
+
+impl HandlerEntry {
+    pub(crate) fn accepts(&self, log: &LogMessage) -> bool {
+        Classification::of(log) <= self.clearance && self.filters.iter().all(|f| f.allow(log))
+    }
+
+    /// Key of the handler's statistics: its name and id, e.g. `file#2`, so that handlers
+    /// of the same type are reported apart.
+    pub(crate) fn metrics_key(&self) -> String {
+        format!("{}#{}", self.handler.name(), self.id)
+    }
+}
+
+/// Components supplied through the builder, kept across configuration reloads.
+pub(crate) struct Extensions {
+    pub(crate) filters: Vec<Arc<dyn LogFilter>>,
+    pub(crate) enrichers: Vec<Arc<dyn LogEnricher>>,
+    pub(crate) plugins: PluginRegistry,
+}
+
+/// The configuration-derived stages every message goes through.
+pub(crate) struct Pipeline {
+    pub(crate) filters: Vec<Arc<dyn LogFilter>>,
+    pub(crate) enrichers: Vec<Arc<dyn LogEnricher>>,
+    pub(crate) processors: Vec<Arc<dyn LogProcessor>>,
+    pub(crate) decorators: Vec<Arc<dyn HandlerDecorator>>,
+    pub(crate) formatter: Arc<dyn Formatter>,
+    /// Lowest level any message can pass at.
+    pub(crate) min_level: LogLevel,
+    /// Whether ERROR and FATAL messages capture a backtrace by default.
+    pub(crate) backtrace: bool,
+}
+
+impl Pipeline {
+    /// Builds the pipeline from a configuration plus the builder-provided extensions.
+    pub(crate) fn from_config(config: &LogConfig, extensions: &Extensions) -> Result<Self, LoggerError> {
+        // Register custom levels first so thresholds below can refer to them
+        for (name, severity) in config.custom_levels.iter().flatten() {
+            LogLevel::register(name, *severity).map_err(|e| LoggerError::ConfigError(e.to_string()))?;
+        }
+
+        // Global filters: configured level with its per-module overrides, declared filters,
+        // then builder filters
+        let overrides = config
+            .filters
+            .iter()
+            .flatten()
+            .map(|(prefix, level)| Ok((prefix.clone(), parse_level(level)?)))
+            .collect::<Result<Vec<_>, LoggerError>>()?;
+        let levels = ModuleLevelFilter::new(parse_level(&config.level)?, overrides);
+        let min_level = levels.most_verbose();
+        let mut filters: Vec<Arc<dyn LogFilter>> = vec![Arc::new(levels)];
+        for filter_cfg in config.global_filters.iter().flatten() {
+            filters.push(build_filter(filter_cfg).map_err(|e| LoggerError::ConfigError(e.to_string()))?);
+        }
+        filters.extend(extensions.filters.iter().cloned());
+
+        // Enrichers: declared in config, then builder enrichers
+        let mut enrichers: Vec<Arc<dyn LogEnricher>> = Vec::new();
+        for enricher_cfg in config.enrichers.iter().flatten() {
+            enrichers
+                .push(build_enricher(enricher_cfg).map_err(|e| LoggerError::ConfigError(e.to_string()))?);
+        }
+        enrichers.extend(extensions.enrichers.iter().cloned());
+
+        // Plugins: instantiate the plugins named in config, each of which must be registered
+        let mut processors: Vec<Arc<dyn LogProcessor>> = Vec::new();
+        let mut decorators: Vec<Arc<dyn HandlerDecorator>> = Vec::new();
+        for plugin_cfg in config.plugins.iter().flatten() {
+            match extensions
+                .plugins
+                .instantiate(plugin_cfg)
+                .map_err(|e| LoggerError::ConfigError(e.to_string()))?
+            {
+                Plugin::Filter(filter) => filters.push(filter),
+                Plugin::Enricher(enricher) => enrichers.push(enricher),
+                Plugin::Processor(processor) => processors.push(processor),
+                Plugin::HandlerDecorator(decorator) => decorators.push(decorator),
+            }
+        }
+
+        // Initialize formatter
+        let formatter: Arc<dyn Formatter> = match &config.formatter {
+            Some(options) if options.type_ == "json" => {
+                Arc::new(JsonFormatter::new().with_timestamp_format(options.timestamp.clone()))
+            }
+            Some(options) => Arc::new(
+                TextFormatter::new(options.pattern.clone()).with_timestamp_format(options.timestamp.clone()),
+            ),
+            None => Arc::new(TextFormatter::new(None)),
+        };
+
+        Ok(Pipeline {
+            filters,
+            enrichers,
+            processors,
+            decorators,
+            formatter,
+            min_level,
+            backtrace: config.backtrace.unwrap_or(false),
+        })
+    }
+
+    /// Returns `true` if every global filter accepts the message.
+    pub(crate) fn accepts(&self, log: &LogMessage) -> bool {
+        self.filters.iter().all(|f| f.allow(log))
+    }
+
+    /// Runs the enrichers over the message metadata.
+    pub(crate) fn enrich(&self, log: &mut LogMessage) {
+        if let Value::Object(fields) = &mut log.metadata {
+            for enricher in &self.enrichers {
+                enricher.enrich(fields);
+            }
+        }
+    }
+
+    /// Wraps a handler with every decorator plugin, in registration order.
+    pub(crate) fn decorate(&self, handler: Arc<dyn LogHandler>) -> Arc<dyn LogHandler> {
+        self.decorators
+            .iter()
+            .fold(handler, |handler, decorator| decorator.decorate(handler))
+    }
+
+    /// Wraps and registers a handler that did not come from the configuration.
+    pub(crate) fn entry(
+        &self,
+        (handler, filters): FilteredHandler,
+        clearance: Classification,
+        next_id: &AtomicU64,
+    ) -> HandlerEntry {
+        HandlerEntry {
+            id: HandlerId(next_id.fetch_add(1, Ordering::SeqCst)),
+            handler: self.decorate(handler),
+            filters,
+            clearance,
+            source: None,
+            chain: Arc::new(Mutex::new(String::new())),
+        }
+    }
+
+    /// Builds the handlers declared in the configuration, reusing entries from `previous`
+    /// whose configuration is unchanged. Handlers not built from configuration are kept as-is.
+    pub(crate) fn reconcile_handlers(
+        &self,
+        config: &LogConfig,
+        previous: &[HandlerEntry],
+        next_id: &AtomicU64,
+    ) -> Result<Vec<HandlerEntry>, LoggerError> {
+        let mut reusable: Vec<&HandlerEntry> = previous.iter().filter(|e| e.source.is_some()).collect();
+        let mut handlers = Vec::new();
+        for handler_cfg in &config.handlers {
+            if let Some(pos) = reusable.iter().position(|e| e.source.as_ref() == Some(handler_cfg)) {
+                handlers.push(reusable.remove(pos).clone());
+                continue;
+            }
+            let handler = build_handler(&handler_cfg.kind)?;
+            let mut handler_filters: Vec<Arc<dyn LogFilter>> = Vec::new();
+            if let Some(level) = handler_cfg.level.as_deref() {
+                handler_filters.push(Arc::new(LevelFilter::at_least(parse_level(level)?)));
+            }
+            for filter_cfg in handler_cfg.filters.iter().flatten() {
+                handler_filters
+                    .push(build_filter(filter_cfg).map_err(|e| LoggerError::ConfigError(e.to_string()))?);
+            }
+            let clearance = handler_cfg.clearance.unwrap_or_default();
+            let mut entry = self.entry((handler, handler_filters), clearance, next_id);
+            entry.source = Some(handler_cfg.clone());
+            handlers.push(entry);
+        }
+        handlers.extend(previous.iter().filter(|e| e.source.is_none()).cloned());
+        Ok(handlers)
+    }
+}
+
+/// Parses a level string from the configuration.
+/// `OFF` is [`LogLevel::OFF`], a threshold no record reaches.
+pub(crate) fn parse_level(level: &str) -> Result<LogLevel, LoggerError> {
+    if level.eq_ignore_ascii_case(LEVEL_OFF) {
+        return Ok(LogLevel::OFF);
+    }
+    LogLevel::from_str(level).ok_or_else(|| LoggerError::ConfigError(format!("Invalid level: {}", level)))
+}
+
+/// Instantiates a handler from its configuration entry. File and remote handlers are not
+/// available on `wasm32`.
+fn build_handler(kind: &HandlerKind) -> Result<Arc<dyn LogHandler>, LoggerError> {
+    let handler: Arc<dyn LogHandler> = match kind {
+        HandlerKind::Console { .. } => Arc::new(crate::handlers::ConsoleHandler::new()),
+        #[cfg(not(target_arch = "wasm32"))]
+        HandlerKind::File { config } => Arc::new(crate::handlers::FileHandler::new(
+            config.file_path.clone().into(),
+            config.max_size,
+        )),
+        #[cfg(not(target_arch = "wasm32"))]
+        HandlerKind::Remote { config } => Arc::new(crate::handlers::RemoteHandler::new(
+            config.address.clone(),
+            config.port,
+            config.retries,
+        )),
+        #[cfg(target_arch = "wasm32")]
+        HandlerKind::File { .. } | HandlerKind::Remote { .. } => {
+            return Err(LoggerError::ConfigError(format!(
+                "The {} handler is not available on wasm32",
+                kind.type_name()
+            )))
+        }
+        HandlerKind::Memory { config } => Arc::new(crate::handlers::MemoryHandler::new(config.capacity)),
+    };
+    Ok(handler)
+}
@@ -0,0 +1,78 @@
+use crate::config::PluginConfig;
+use crate::enrichers::LogEnricher;
+use crate::filters::LogFilter;
+use crate::handlers::LogHandler;
+use crate::logger::LogMessage;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PluginError {
+    #[error("Unknown plugin: {0}")]
+    UnknownPlugin(String),
+    #[error("Plugin initialization error: {0}")]
+    InitError(String),
+}
+
+/// Trait for plugins that transform log messages in the worker before they are sanitized and formatted.
+pub trait LogProcessor: Send + Sync {
+    /// Transforms the log message in place.
+    fn process(&self, log: &mut LogMessage);
+}
+
+/// Trait for plugins that wrap every handler, e.g. to add buffering or retries.
+pub trait HandlerDecorator: Send + Sync {
+    /// Returns a handler wrapping `handler`.
+    fn decorate(&self, handler: Arc<dyn LogHandler>) -> Arc<dyn LogHandler>;
+}
+
+/// A plugin instance produced by a registered factory.
+pub enum Plugin {
+    Filter(Arc<dyn LogFilter>),
+    Enricher(Arc<dyn LogEnricher>),
+    Processor(Arc<dyn LogProcessor>),
+    HandlerDecorator(Arc<dyn HandlerDecorator>),
+}
+
+/// Factory building a plugin from its JSON options in the `plugins:` config section.
+pub type PluginFactory = Box<dyn Fn(&Value) -> Result<Plugin, PluginError> + Send + Sync>;
+
+/// Registry of named plugin factories, consulted when the Logger is built.
+#[derive(Default)]
+pub struct PluginRegistry {
+    factories: HashMap<String, PluginFactory>,
+}
+
+impl PluginRegistry {
+    /// Initializes an empty PluginRegistry.
+    pub fn new() -> Self {
+        PluginRegistry {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Registers a factory under `name`, replacing any previous registration.
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&Value) -> Result<Plugin, PluginError> + Send + Sync + 'static,
+    {
+        self.factories.insert(name.to_string(), Box::new(factory));
+    }
+
+    /// Returns `true` if a plugin is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// Instantiates the plugin described by a configuration entry.
+    pub fn instantiate(&self, cfg: &PluginConfig) -> Result<Plugin, PluginError> {
+        let factory = self
+            .factories
+            .get(&cfg.name)
+            .ok_or_else(|| PluginError::UnknownPlugin(cfg.name.clone()))?;
+        let options = cfg.config.clone().unwrap_or(serde_json::json!({}));
+        factory(&options)
+    }
+}
@@ -0,0 +1,156 @@
+//! Python bindings, built into the crate's `cdylib` with the `python` feature as the module
+//! `log_engine_v1`, e.g. with `maturin build --features python,pyo3/extension-module`:
+//!
+//! ```python
+//! from log_engine_v1 import Logger
+//!
+//! logger = Logger("config/config.yaml", "env:LOG_KEY")
+//! logger.info("asset bundle built", bundle="levels", size=1024)
+//! logger.shutdown()
+//! ```
+//!
+//! Records go through the same pipeline as those of Rust code: the configured handlers,
+//! security rules, encryption and file rotation.
+
+use crate::logger::Logger;
+use crate::utils::LogLevel;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A Logger owned by Python code.
+#[pyclass(name = "Logger", module = "log_engine_v1", frozen)]
+struct PyLogger {
+    logger: Arc<Logger>,
+}
+
+#[pymethods]
+impl PyLogger {
+    /// Starts a Logger, as `Logger::new` does. `key` is a key descriptor such as
+    /// `env:LOG_KEY` or `file:/run/secrets/logkey`.
+    #[new]
+    #[pyo3(signature = (config_path = None, key = "env:LOG_KEY"))]
+    fn new(py: Python<'_>, config_path: Option<&str>, key: &str) -> PyResult<Self> {
+        let runtime = crate::utils::runtime().map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let logger = py
+            .detach(|| runtime.block_on(Logger::new(config_path, key)))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(PyLogger { logger })
+    }
+
+    /// Logs `message` at the level named `level`, with the keyword arguments as metadata.
+    #[pyo3(signature = (level, message, **metadata))]
+    fn log(&self, level: &str, message: &str, metadata: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+        self.emit(parse_level(level).map_err(PyValueError::new_err)?, message, metadata)
+    }
+
+    #[pyo3(signature = (message, **metadata))]
+    fn trace(&self, message: &str, metadata: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+        self.emit(LogLevel::TRACE, message, metadata)
+    }
+
+    #[pyo3(signature = (message, **metadata))]
+    fn debug(&self, message: &str, metadata: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+        self.emit(LogLevel::DEBUG, message, metadata)
+    }
+
+    #[pyo3(signature = (message, **metadata))]
+    fn info(&self, message: &str, metadata: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+        self.emit(LogLevel::INFO, message, metadata)
+    }
+
+    #[pyo3(signature = (message, **metadata))]
+    fn warn(&self, message: &str, metadata: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+        self.emit(LogLevel::WARN, message, metadata)
+    }
+
+    #[pyo3(signature = (message, **metadata))]
+    fn error(&self, message: &str, metadata: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+        self.emit(LogLevel::ERROR, message, metadata)
+    }
+
+    #[pyo3(signature = (message, **metadata))]
+    fn fatal(&self, message: &str, metadata: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+        self.emit(LogLevel::FATAL, message, metadata)
+    }
+
+    /// Waits up to `timeout` seconds for every record logged so far to be written.
+    /// Returns `False` if it timed out or the Logger has been shut down.
+    #[pyo3(signature = (timeout = 5.0))]
+    fn flush(&self, py: Python<'_>, timeout: f64) -> PyResult<bool> {
+        let timeout = Duration::try_from_secs_f64(timeout).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(py.detach(|| self.logger.flush_blocking(timeout)))
+    }
+
+    /// Writes the queued records and stops the Logger.
+    fn shutdown(&self, py: Python<'_>) -> PyResult<()> {
+        let runtime = crate::utils::runtime().map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        py.detach(|| runtime.block_on(self.logger.shutdown()));
+        Ok(())
+    }
+}
+
+impl PyLogger {
+    fn emit(&self, level: LogLevel, message: &str, metadata: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+        self.logger.log(level, message, metadata.map(dict_to_json).transpose()?);
+        Ok(())
+    }
+}
+
+/// The level named by `Logger.log`, as in a configuration file: a built-in or registered
+/// custom level, in any case.
+pub(crate) fn parse_level(name: &str) -> Result<LogLevel, String> {
+    LogLevel::from_str(name).ok_or_else(|| format!("unknown level: {}", name))
+}
+
+/// Converts keyword arguments to record metadata.
+pub(crate) fn dict_to_json(dict: &Bound<'_, PyDict>) -> PyResult<Value> {
+    let mut map = Map::new();
+    for (key, value) in dict.iter() {
+        map.insert(key.str()?.to_string(), to_json(&value)?);
+    }
+    Ok(Value::Object(map))
+}
+
+/// Converts a Python value to JSON; values without a JSON counterpart become their `str()`.
+fn to_json(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if value.is_none() {
+        return Ok(Value::Null);
+    }
+    // bool is a subclass of int, so it is checked first
+    if let Ok(value) = value.cast::<PyBool>() {
+        return Ok(Value::Bool(value.is_true()));
+    }
+    if value.is_instance_of::<PyInt>() {
+        if let Ok(value) = value.extract::<i64>() {
+            return Ok(Value::from(value));
+        }
+        if let Ok(value) = value.extract::<u64>() {
+            return Ok(Value::from(value));
+        }
+    }
+    if let Ok(value) = value.cast::<PyFloat>() {
+        return Ok(Value::from(value.value()));
+    }
+    if let Ok(value) = value.cast::<PyString>() {
+        return Ok(Value::String(value.to_str()?.to_string()));
+    }
+    if let Ok(list) = value.cast::<PyList>() {
+        return list.iter().map(|item| to_json(&item)).collect::<PyResult<_>>().map(Value::Array);
+    }
+    if let Ok(tuple) = value.cast::<PyTuple>() {
+        return tuple.iter().map(|item| to_json(&item)).collect::<PyResult<_>>().map(Value::Array);
+    }
+    if let Ok(dict) = value.cast::<PyDict>() {
+        return dict_to_json(dict);
+    }
+    Ok(Value::String(value.str()?.to_string()))
+}
+
+#[pymodule]
+fn log_engine_v1(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyLogger>()
+}
@@ -0,0 +1,327 @@
+//! Reading log files back into records, for verification, replay and tooling.
+
+use crate::security::{SecurityError, SecurityManager};
+use crate::utils::LogLevel;
+use chrono::{DateTime, FixedOffset, Utc};
+use flate2::read::MultiGzDecoder;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ReaderError {
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+    #[error("{file}:{line}: {reason}")]
+    Malformed { file: String, line: usize, reason: String },
+}
+
+/// A record parsed back from a log file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogRecord {
+    /// When the record was logged, as written in its metadata, or the formatter's
+    /// timestamp for records without one.
+    pub timestamp: String,
+    pub level: LogLevel,
+    /// The message as written: ciphertext for encrypted records.
+    pub message: String,
+    /// The fields logged with the record.
+    pub metadata: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Metadata fields written encrypted, for records using field-level encryption.
+    pub encrypted_fields: Vec<String>,
+    /// Fields the JSON formatter adds, such as `sequence`, `thread` and `module`.
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub extra: Value,
+    /// The line the record was parsed from.
+    #[serde(skip)]
+    pub raw: String,
+}
+
+impl LogRecord {
+    /// Parses a line written by the JSON formatter or by the text formatter's default
+    /// pattern, `{timestamp} [{level}] - {message} - {metadata}`.
+    pub fn parse(line: &str) -> Result<LogRecord, String> {
+        match serde_json::from_str::<Value>(line) {
+            Ok(Value::Object(mut record)) => {
+                let text = |value: Option<Value>, field: &str| match value {
+                    Some(Value::String(text)) => Ok(text),
+                    _ => Err(format!("record lacks a {}", field)),
+                };
+                let formatted_at = text(record.remove("timestamp"), "timestamp")?;
+                let level = text(record.remove("level"), "level")?;
+                let message = text(record.remove("message"), "message")?;
+                let envelope = record.remove("metadata").unwrap_or(Value::Null);
+                let extra = if record.is_empty() { Value::Null } else { Value::Object(record) };
+                LogRecord::from_parts(formatted_at, &level, message, envelope, extra, line)
+            }
+            _ => {
+                let (formatted_at, rest) = line.split_once(" [").ok_or("not a JSON or text record")?;
+                let (level, rest) = rest.split_once("] - ").ok_or("not a JSON or text record")?;
+                // The metadata JSON follows the last separator it parses after
+                let (message, envelope) = rest
+                    .rmatch_indices(" - {")
+                    .find_map(|(at, _)| {
+                        let envelope: Value = serde_json::from_str(&rest[at + 3..]).ok()?;
+                        Some((rest[..at].to_string(), envelope))
+                    })
+                    .ok_or("record lacks metadata")?;
+                LogRecord::from_parts(formatted_at.to_string(), level, message, envelope, Value::Null, line)
+            }
+        }
+    }
+
+    /// Builds a record from its formatted parts, `envelope` being the metadata written by
+    /// the [`SecurityManager`]: the logged fields with `mac`, `timestamp` and `signature`.
+    fn from_parts(
+        formatted_at: String,
+        level: &str,
+        message: String,
+        mut envelope: Value,
+        extra: Value,
+        raw: &str,
+    ) -> Result<LogRecord, String> {
+        let level = LogLevel::from_str(level).ok_or_else(|| format!("unknown level: {}", level))?;
+        let mut take = |key: &str| match envelope.get_mut(key).map(Value::take) {
+            Some(Value::String(text)) => Some(text),
+            _ => None,
+        };
+        let timestamp = take("timestamp").unwrap_or(formatted_at);
+        let mac = take("mac");
+        let signature = take("signature");
+        let encrypted_fields = match envelope.get("encrypted_fields") {
+            Some(Value::Array(paths)) => paths.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+            _ => Vec::new(),
+        };
+        Ok(LogRecord {
+            timestamp,
+            level,
+            message,
+            metadata: envelope.get_mut("metadata").map(Value::take).unwrap_or(Value::Null),
+            mac,
+            signature,
+            encrypted_fields,
+            extra,
+            raw: raw.to_string(),
+        })
+    }
+
+    /// Returns the parsed [`timestamp`](Self::timestamp), if it is RFC 3339.
+    pub fn time(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(&self.timestamp).ok()
+    }
+
+    /// Returns `true` if the message or some metadata fields are encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.mac.is_some()
+    }
+
+    /// Returns the record with its message, or its encrypted metadata fields, decrypted.
+    pub fn decrypt(&self, security: &SecurityManager) -> Result<LogRecord, SecurityError> {
+        let mut record = self.clone();
+        if !self.is_encrypted() {
+            return Ok(record);
+        }
+        let aad = SecurityManager::associated_data(self.level.as_str(), &self.timestamp);
+        if self.encrypted_fields.is_empty() {
+            record.message = security.decrypt_with_aad(&self.message, &aad)?;
+        } else {
+            security.decrypt_fields(&mut record.metadata, &self.encrypted_fields, &aad)?;
+        }
+        Ok(record)
+    }
+}
+
+/// Iterates over the records of log files, oldest first. Files compressed with gzip, such
+/// as the [`FileHandler`](crate::handlers::FileHandler)'s rotation archives, are
+/// decompressed as they are read.
+///
+/// ```ignore
+/// let since = Utc::now() - chrono::Duration::hours(1);
+/// for record in LogReader::open_rotated("logs/app.log")?.since(since) {
+///     let record = record?;
+///     println!("{} {}", record.level, record.message);
+/// }
+/// ```
+///
+/// Lines that cannot be parsed are returned as [`ReaderError::Malformed`], and reading
+/// continues with the next line.
+pub struct LogReader {
+    pending: VecDeque<PathBuf>,
+    current: Option<(String, Box<dyn BufRead>)>,
+    line: usize,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    done: bool,
+}
+
+impl LogReader {
+    /// Reads a single file.
+    pub fn open(path: impl AsRef<Path>) -> Result<LogReader, ReaderError> {
+        LogReader::open_all(vec![path.as_ref().to_path_buf()])
+    }
+
+    /// Reads the rotation archives of `path` (files named after it with a suffix, such as
+    /// `app.log.20240101120000.gz`), oldest first, then `path` itself if it exists.
+    pub fn open_rotated(path: impl AsRef<Path>) -> Result<LogReader, ReaderError> {
+        let path = path.as_ref();
+        let mut files = rotated_files(path)?;
+        if path.exists() {
+            files.push(path.to_path_buf());
+        }
+        LogReader::open_all(files)
+    }
+
+    /// Reads the given files in order.
+    pub fn open_all(files: Vec<PathBuf>) -> Result<LogReader, ReaderError> {
+        let mut reader = LogReader::new(None);
+        reader.pending = files.into();
+        reader.next_file()?;
+        Ok(reader)
+    }
+
+    /// Reads records from any source, e.g. a pipe.
+    pub fn from_reader(reader: impl BufRead + 'static) -> LogReader {
+        LogReader::new(Some(("<reader>".to_string(), Box::new(reader))))
+    }
+
+    fn new(current: Option<(String, Box<dyn BufRead>)>) -> LogReader {
+        LogReader {
+            pending: VecDeque::new(),
+            current,
+            line: 0,
+            since: None,
+            until: None,
+            done: false,
+        }
+    }
+
+    /// Skips records logged before `time`.
+    pub fn since(mut self, time: DateTime<Utc>) -> Self {
+        self.since = Some(time);
+        self
+    }
+
+    /// Stops at the first record logged after `time`; records are assumed to be in order.
+    pub fn until(mut self, time: DateTime<Utc>) -> Self {
+        self.until = Some(time);
+        self
+    }
+
+    /// Opens the next pending file, leaving no current reader when none is left.
+    fn next_file(&mut self) -> Result<(), ReaderError> {
+        self.current = None;
+        self.line = 0;
+        if let Some(path) = self.pending.pop_front() {
+            let reader = open_file(&path)?;
+            self.current = Some((path.display().to_string(), reader));
+        }
+        Ok(())
+    }
+
+    /// Reads the next non-blank line, moving on to the next file at the end of one.
+    fn next_line(&mut self) -> Option<Result<(String, String, usize), ReaderError>> {
+        let mut line = String::new();
+        loop {
+            let (source, reader) = self.current.as_mut()?;
+            line.clear();
+            match reader.read_line(&mut line) {
+                Ok(0) => {
+                    if let Err(e) = self.next_file() {
+                        return Some(Err(e));
+                    }
+                }
+                Ok(_) => {
+                    self.line += 1;
+                    if !line.trim().is_empty() {
+                        let line = line.trim_end_matches(['\r', '\n']).to_string();
+                        return Some(Ok((line, source.clone(), self.line)));
+                    }
+                }
+                Err(e) => {
+                    let source = source.clone();
+                    // Skip the rest of an unreadable file, e.g. a truncated archive
+                    let _ = self.next_file();
+                    return Some(Err(ReaderError::Malformed {
+                        file: source,
+                        line: self.line + 1,
+                        reason: e.to_string(),
+                    }));
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for LogReader {
+    type Item = Result<LogRecord, ReaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let (line, source, number) = match self.next_line()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            let record = match LogRecord::parse(&line) {
+                Ok(record) => record,
+                Err(reason) => {
+                    return Some(Err(ReaderError::Malformed {
+                        file: source,
+                        line: number,
+                        reason,
+                    }))
+                }
+            };
+            if let Some(time) = record.time() {
+                if self.until.is_some_and(|until| time > until) {
+                    self.done = true;
+                    return None;
+                }
+                if self.since.is_some_and(|since| time < since) {
+                    continue;
+                }
+            }
+            return Some(Ok(record));
+        }
+        None
+    }
+}
+
+/// Opens a file, decompressing it if it starts with the gzip magic bytes.
+pub fn open_file(path: &Path) -> Result<Box<dyn BufRead>, ReaderError> {
+    let mut file = BufReader::new(File::open(path)?);
+    if file.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(file))))
+    } else {
+        Ok(Box::new(file))
+    }
+}
+
+/// Lists the rotation archives of `path`, oldest first.
+pub fn rotated_files(path: &Path) -> Result<Vec<PathBuf>, ReaderError> {
+    let name = match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => format!("{}.", name),
+        None => return Ok(Vec::new()),
+    };
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let mut archives = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let is_archive = entry.file_name().to_str().is_some_and(|file| file.starts_with(&name));
+        if is_archive && entry.file_type()?.is_file() {
+            archives.push((entry.metadata()?.modified()?, entry.path()));
+        }
+    }
+    archives.sort();
+    Ok(archives.into_iter().map(|(_, path)| path).collect())
+}
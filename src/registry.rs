@@ -0,0 +1,60 @@
+use crate::logger::Logger;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum RegistryError {
+    #[error("A global logger has already been initialized")]
+    AlreadyInitialized,
+}
+
+static GLOBAL: OnceLock<Arc<Logger>> = OnceLock::new();
+static NAMED: OnceLock<RwLock<HashMap<String, Arc<Logger>>>> = OnceLock::new();
+
+fn named() -> &'static RwLock<HashMap<String, Arc<Logger>>> {
+    NAMED.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Installs the global logger. Can only be called once per process.
+pub fn init(logger: Arc<Logger>) -> Result<(), RegistryError> {
+    GLOBAL
+        .set(logger)
+        .map_err(|_| RegistryError::AlreadyInitialized)
+}
+
+/// Returns the global logger, if one has been installed.
+pub fn global() -> Option<Arc<Logger>> {
+    GLOBAL.get().cloned()
+}
+
+/// Returns the global logger without cloning it, for the global logging macros.
+pub fn global_ref() -> Option<&'static Arc<Logger>> {
+    GLOBAL.get()
+}
+
+/// Registers a named logger, replacing any logger previously registered under `name`.
+pub fn register(name: &str, logger: Arc<Logger>) {
+    named()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name.to_string(), logger);
+}
+
+/// Removes a named logger, returning it if it was registered.
+pub fn unregister(name: &str) -> Option<Arc<Logger>> {
+    named()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(name)
+}
+
+/// Returns the logger registered under `name`, falling back to the global logger.
+pub fn get(name: &str) -> Option<Arc<Logger>> {
+    named()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name)
+        .cloned()
+        .or_else(global)
+}
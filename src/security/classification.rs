@@ -0,0 +1,40 @@
+use crate::logger::LogMessage;
+use serde::{Deserialize, Serialize};
+
+/// Metadata key holding a record's classification.
+pub const CLASSIFICATION_KEY: &str = "classification";
+
+/// Sensitivity of a record, ordered from least to most sensitive.
+///
+/// Records are tagged through their `classification` metadata field, e.g.
+/// `{"classification": "confidential"}` or `log_info!(logger, Confidential; "...")`, and
+/// only reach handlers whose clearance is at least as high. Untagged records are
+/// `internal`; an unrecognized tag is treated as `confidential`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Classification {
+    Public,
+    #[default]
+    Internal,
+    Confidential,
+}
+
+impl Classification {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Classification::Public => "public",
+            Classification::Internal => "internal",
+            Classification::Confidential => "confidential",
+        }
+    }
+
+    /// Returns the classification a record is tagged with.
+    pub fn of(log: &LogMessage) -> Self {
+        match log.metadata.get(CLASSIFICATION_KEY).map(|tag| tag.as_str()) {
+            None => Classification::default(),
+            Some(Some("public")) => Classification::Public,
+            Some(Some("internal")) => Classification::Internal,
+            Some(_) => Classification::Confidential,
+        }
+    }
+}
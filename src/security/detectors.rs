@@ -0,0 +1,120 @@
+use super::masking::Validator;
+use super::{MaskStrategy, SanitizationRule, SecurityError};
+use std::net::Ipv6Addr;
+
+/// Names of the built-in PII detectors. Detectors enabled first take priority on overlapping
+/// matches, and this order lets the more specific ones (e.g. IBANs) win over phone numbers.
+pub const PII_DETECTORS: &[&str] = &["credit_card", "iban", "ssn", "phone", "ipv4", "ipv6"];
+
+/// Names of the built-in credential detectors, in their recommended order.
+pub const SECRET_DETECTORS: &[&str] = &[
+    "pem_private_key",
+    "gcp_service_account",
+    "aws_access_key",
+    "bearer_token",
+    "jwt",
+];
+
+/// Builds the built-in detector called `name`, masking its matches with `mask`, or with
+/// the detector's default (`keep_last: 4` for credit cards, `redact` otherwise).
+pub fn detector(name: &str, mask: Option<MaskStrategy>) -> Result<SanitizationRule, SecurityError> {
+    let (pattern, default_mask, validator): (&str, MaskStrategy, Option<Validator>) = match name {
+        "credit_card" => (r"\b(?:\d[ -]?){12,18}\d\b", MaskStrategy::KeepLast(4), Some(luhn_valid)),
+        "ssn" => (r"\b\d{3}-\d{2}-\d{4}\b", MaskStrategy::Redact, Some(ssn_valid)),
+        "phone" => (
+            r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{2,4}\)[ .-]?|\b\d{2,4}[ .-])\d{3,4}[ .-]\d{4}\b",
+            MaskStrategy::Redact,
+            None,
+        ),
+        "ipv4" => (
+            r"\b(?:(?:25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(?:25[0-5]|2[0-4]\d|1?\d?\d)\b",
+            MaskStrategy::Redact,
+            None,
+        ),
+        "ipv6" => (
+            r"(?i)(?:[0-9a-f]{0,4}:){2,7}[0-9a-f]{0,4}",
+            MaskStrategy::Redact,
+            Some(|value: &str| value.parse::<Ipv6Addr>().is_ok()),
+        ),
+        "iban" => (
+            r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,3})?\b",
+            MaskStrategy::Redact,
+            Some(iban_valid),
+        ),
+        "pem_private_key" => (
+            r"(?s)-----BEGIN [A-Z ]*PRIVATE KEY-----.*?-----END [A-Z ]*PRIVATE KEY-----",
+            MaskStrategy::Redact,
+            None,
+        ),
+        "gcp_service_account" => (
+            r#"(?i)"private_key_id"\s*:\s*"[0-9a-f]{40}""#,
+            MaskStrategy::Redact,
+            None,
+        ),
+        "aws_access_key" => (r"\b(?:AKIA|ASIA|ABIA|ACCA)[0-9A-Z]{16}\b", MaskStrategy::Redact, None),
+        "bearer_token" => (r"(?i)\bbearer\s+[A-Za-z0-9._~+/-]+=*", MaskStrategy::Redact, None),
+        "jwt" => (
+            r"\beyJ[A-Za-z0-9_-]*\.eyJ[A-Za-z0-9_-]*\.[A-Za-z0-9_-]*",
+            MaskStrategy::Redact,
+            None,
+        ),
+        _ => return Err(SecurityError::SanitizationError(format!("Unknown detector: {}", name))),
+    };
+    let rule = SanitizationRule::new(pattern, mask.unwrap_or(default_mask))?;
+    Ok(match validator {
+        Some(validator) => rule.with_validator(validator),
+        None => rule,
+    })
+}
+
+/// Checks the Luhn checksum of a card number, ignoring separators.
+fn luhn_valid(value: &str) -> bool {
+    let digits: Vec<u32> = value.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2, d * 2) {
+            (1, doubled) if doubled > 9 => doubled - 9,
+            (1, doubled) => doubled,
+            _ => d,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Rejects SSNs with area 000, 666 or 9xx, group 00, or serial 0000, which are never issued.
+fn ssn_valid(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    match parts.as_slice() {
+        [area, group, serial] => {
+            *area != "000" && *area != "666" && !area.starts_with('9') && *group != "00" && *serial != "0000"
+        }
+        _ => false,
+    }
+}
+
+/// Checks the ISO 7064 mod-97 checksum of an IBAN.
+fn iban_valid(value: &str) -> bool {
+    let compact: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    if compact.len() < 15 || compact.len() > 34 {
+        return false;
+    }
+    let (head, tail) = compact.split_at(4);
+    let mut remainder = 0u32;
+    for c in tail.chars().chain(head.chars()) {
+        let value = match c.to_digit(36) {
+            Some(value) => value,
+            None => return false,
+        };
+        remainder = if value >= 10 {
+            (remainder * 100 + value) % 97
+        } else {
+            (remainder * 10 + value) % 97
+        };
+    }
+    remainder == 1
+}
@@ -0,0 +1,165 @@
+use super::{SecurityError, REDACTED};
+use hmac::{Hmac, Mac};
+use regex::{Regex, RegexSet};
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+
+/// How a matched value is masked.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaskStrategy {
+    /// Replaces the value with `[REDACTED]`.
+    #[default]
+    Redact,
+    /// Masks all but the last N characters with `*`, e.g. `************1234`.
+    KeepLast(usize),
+    /// Replaces the value with N asterisks, hiding its length.
+    Asterisks(usize),
+    /// Replaces the value with a fixed string, e.g. `<email>`.
+    Replace(String),
+    /// Replaces the value with a token derived from it by HMAC, so equal values map to
+    /// equal tokens and can still be correlated across records.
+    Pseudonymize,
+}
+
+/// Predicate deciding whether a match should be masked.
+pub type Validator = fn(&str) -> bool;
+
+/// A sanitization pattern together with the way its matches are masked.
+pub struct SanitizationRule {
+    regex: Regex,
+    mask: MaskStrategy,
+    validator: Option<Validator>,
+}
+
+impl SanitizationRule {
+    /// Compiles `pattern` into a rule masking its matches with `mask`.
+    pub fn new(pattern: &str, mask: MaskStrategy) -> Result<Self, SecurityError> {
+        let regex = Regex::new(pattern).map_err(|e| SecurityError::SanitizationError(e.to_string()))?;
+        Ok(SanitizationRule {
+            regex,
+            mask,
+            validator: None,
+        })
+    }
+
+    /// Only masks matches for which `validator` returns `true`, e.g. a checksum test.
+    pub fn with_validator(mut self, validator: Validator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    fn accepts(&self, value: &str) -> bool {
+        self.validator.is_none_or(|validator| validator(value))
+    }
+}
+
+/// Consecutive rules compiled into one [`RegexSet`], so text matching none of them is
+/// scanned once and left untouched, and text that does match is rewritten in a single pass.
+///
+/// Where matches of different rules overlap, the rule added first wins.
+pub(crate) struct RuleSet {
+    set: RegexSet,
+    rules: Vec<SanitizationRule>,
+}
+
+impl RuleSet {
+    pub(crate) fn new(rule: SanitizationRule) -> Result<Self, SecurityError> {
+        let mut rule_set = RuleSet {
+            set: RegexSet::empty(),
+            rules: Vec::new(),
+        };
+        rule_set.push(rule)?;
+        Ok(rule_set)
+    }
+
+    /// Adds a rule with the lowest priority so far.
+    pub(crate) fn push(&mut self, rule: SanitizationRule) -> Result<(), SecurityError> {
+        self.rules.push(rule);
+        self.set = RegexSet::new(self.rules.iter().map(|rule| rule.regex.as_str()))
+            .map_err(|e| SecurityError::SanitizationError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Masks every match of the rules in `text`, returning the number of values masked.
+    pub(crate) fn apply(&self, text: &mut String, pseudonym_key: &[u8]) -> usize {
+        let matched = self.set.matches(text);
+        if !matched.matched_any() {
+            return 0;
+        }
+
+        // Matches as (rule index, start, end), in priority order
+        let mut candidates = Vec::new();
+        for index in matched.iter() {
+            let rule = &self.rules[index];
+            candidates.extend(
+                rule.regex
+                    .find_iter(text)
+                    .filter(|m| rule.accepts(m.as_str()))
+                    .map(|m| (index, m.start(), m.end())),
+            );
+        }
+        // Keep each match unless a higher-priority one already covers part of it
+        let mut accepted: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+        for (index, start, end) in candidates {
+            let overlaps = accepted.range(..end).next_back().is_some_and(|(_, (e, _))| *e > start);
+            if !overlaps {
+                accepted.insert(start, (end, index));
+            }
+        }
+        if accepted.is_empty() {
+            return 0;
+        }
+
+        let masked = accepted.len();
+        let mut sanitized = String::with_capacity(text.len());
+        let mut last = 0;
+        for (start, (end, index)) in accepted {
+            sanitized.push_str(&text[last..start]);
+            sanitized.push_str(&mask(&text[start..end], &self.rules[index].mask, pseudonym_key));
+            last = end;
+        }
+        sanitized.push_str(&text[last..]);
+        *text = sanitized;
+        masked
+    }
+
+    /// Masks matches in every string inside `value`, recursing through objects and arrays.
+    /// Object keys are left as they are. Returns the number of values masked.
+    pub(crate) fn apply_to_value(&self, value: &mut Value, pseudonym_key: &[u8]) -> usize {
+        match value {
+            Value::String(text) => self.apply(text, pseudonym_key),
+            Value::Object(fields) => fields
+                .values_mut()
+                .map(|value| self.apply_to_value(value, pseudonym_key))
+                .sum(),
+            Value::Array(values) => values
+                .iter_mut()
+                .map(|value| self.apply_to_value(value, pseudonym_key))
+                .sum(),
+            _ => 0,
+        }
+    }
+}
+
+/// Masks a single value.
+pub(crate) fn mask(value: &str, strategy: &MaskStrategy, pseudonym_key: &[u8]) -> String {
+    match strategy {
+        MaskStrategy::Redact => REDACTED.to_string(),
+        MaskStrategy::KeepLast(keep) => {
+            let keep = *keep;
+            let chars: Vec<char> = value.chars().collect();
+            let hidden = chars.len().saturating_sub(keep);
+            "*".repeat(hidden) + &chars[hidden..].iter().collect::<String>()
+        }
+        MaskStrategy::Asterisks(length) => "*".repeat(*length),
+        MaskStrategy::Replace(replacement) => replacement.clone(),
+        MaskStrategy::Pseudonymize => {
+            let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(pseudonym_key).expect("HMAC accepts any key length");
+            mac.update(value.as_bytes());
+            format!("pseudo_{}", &hex::encode(mac.finalize().into_bytes())[..16])
+        }
+    }
+}
@@ -0,0 +1,894 @@
+mod classification;
+mod detectors;
+mod masking;
+mod presets;
+mod scrubber;
+mod verify;
+
+pub use classification::{Classification, CLASSIFICATION_KEY};
+pub use detectors::{detector, PII_DETECTORS, SECRET_DETECTORS};
+pub use masking::{MaskStrategy, SanitizationRule, Validator};
+pub use presets::CompliancePreset;
+pub use scrubber::Scrubber;
+pub use verify::IntegrityReport;
+
+use scrubber::ScrubStage;
+
+use crate::config::SecurityConfig;
+use crate::keys::{KeyProvider, KeySource};
+use crate::logger::LogMessage;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::Aes256Gcm;
+use arc_swap::ArcSwap;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde_json::Value;
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+#[derive(Error, Debug)]
+pub enum SecurityError {
+    #[error("Encryption error: {0}")]
+    EncryptionError(String),
+    #[error("Decryption error: {0}")]
+    DecryptionError(String),
+    #[error("Signature error: {0}")]
+    SignatureError(String),
+    #[error("Integrity error: {0}")]
+    IntegrityError(String),
+    #[error("Hash chain broken at line {0}")]
+    ChainBroken(usize),
+    #[error("Sanitization error: {0}")]
+    SanitizationError(String),
+}
+
+/// A record encrypted and signed by [`SecurityManager::encrypt_record`], waiting for its
+/// MAC from [`SecurityManager::mac_record`].
+pub struct EncryptedRecord {
+    /// The message to write.
+    pub message: String,
+    /// The record metadata, without the `mac`.
+    pub metadata: Value,
+    /// The id of the key to make the MAC with and what it covers; `None` in plaintext mode.
+    mac: Option<(u32, String)>,
+}
+
+/// Replacement written in place of sanitized or redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Metadata keys redacted by default, compared case-insensitively.
+pub const DEFAULT_DENIED_KEYS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "access_token",
+    "refresh_token",
+    "api_key",
+    "apikey",
+    "authorization",
+    "cookie",
+    "set-cookie",
+    "ssn",
+    "credit_card",
+];
+
+/// Pattern of the default email rule.
+pub(crate) const EMAIL_PATTERN: &str = r"(?i)(\b[A-Z0-9._%+-]+@[A-Z0-9.-]+\.[A-Z]{2,}\b)";
+
+/// Version byte leading every encrypted frame:
+/// `version || key id (4 bytes, big-endian) || nonce (12 bytes) || ciphertext+tag`.
+///
+/// Version 1 frames carry no key id and are decrypted with the initial key (id 1).
+pub const FRAME_VERSION: u8 = 2;
+const NONCE_LEN: usize = 12;
+const KEY_ID_LEN: usize = 4;
+
+/// Id of the key passed to [`SecurityManager::new`].
+pub const INITIAL_KEY_ID: u32 = 1;
+
+/// HKDF info strings deriving the MAC and pseudonymization keys from a record key.
+const MAC_KEY_INFO: &[u8] = b"log-engine-v1 record mac";
+const PSEUDONYM_KEY_INFO: &[u8] = b"log-engine-v1 pseudonym";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The cipher and the separate MAC and pseudonymization keys derived from one master key.
+#[derive(Clone)]
+struct RecordKey {
+    cipher: Aes256Gcm,
+    mac_key: Zeroizing<[u8; 32]>,
+    pseudonym_key: Zeroizing<[u8; 32]>,
+}
+
+/// Encryption keys by id, with the id of the key used for new records.
+#[derive(Clone)]
+struct Keyring {
+    keys: BTreeMap<u32, RecordKey>,
+    /// Wrapped forms of keys obtained through a [`KeyProvider`].
+    wrapped: BTreeMap<u32, Vec<u8>>,
+    active: u32,
+}
+
+pub struct SecurityManager {
+    keyring: ArcSwap<Keyring>,
+    /// Regex rules, detectors, and custom scrubbers, in the order they run.
+    stages: Vec<ScrubStage>,
+    /// Lowercased metadata keys whose values are redacted.
+    denied_keys: Vec<String>,
+    signing_key: Option<SigningKey>,
+    /// MAC of the previous record when chaining is enabled.
+    chain: Option<Mutex<String>>,
+    /// Dotted metadata paths encrypted instead of the message, when set.
+    encrypted_fields: Option<Vec<String>>,
+    preset: Option<CompliancePreset>,
+    /// Records are written unencrypted and without MACs.
+    plaintext: bool,
+    /// Values redacted or masked so far, possibly shared with the logger's metrics.
+    redactions: Arc<AtomicU64>,
+}
+
+/// Builds the cipher and MAC key from the first 32 bytes of `key`.
+fn record_key(key: &[u8]) -> Result<RecordKey, SecurityError> {
+    if key.len() < 32 {
+        return Err(SecurityError::EncryptionError(
+            "Encryption key must be at least 32 bytes.".into(),
+        ));
+    }
+    let cipher =
+        Aes256Gcm::new_from_slice(&key[..32]).map_err(|e| SecurityError::EncryptionError(e.to_string()))?;
+    let hkdf = Hkdf::<Sha256>::new(None, &key[..32]);
+    let mut mac_key = Zeroizing::new([0u8; 32]);
+    let mut pseudonym_key = Zeroizing::new([0u8; 32]);
+    hkdf.expand(MAC_KEY_INFO, mac_key.as_mut())
+        .and_then(|_| hkdf.expand(PSEUDONYM_KEY_INFO, pseudonym_key.as_mut()))
+        .map_err(|e| SecurityError::IntegrityError(e.to_string()))?;
+    Ok(RecordKey {
+        cipher,
+        mac_key,
+        pseudonym_key,
+    })
+}
+
+impl SecurityManager {
+    /// Initializes the SecurityManager with a 32-byte encryption key and optional sanitization patterns.
+    pub fn new(key: &[u8], patterns: Option<Vec<String>>) -> Result<Self, SecurityError> {
+        let record_key = record_key(key)?;
+
+        // Initialize sanitization rules
+        let mut stages = Vec::new();
+        if let Some(patterns) = patterns {
+            for pattern in patterns {
+                ScrubStage::push_rule(&mut stages, SanitizationRule::new(&pattern, MaskStrategy::Redact)?)?;
+            }
+        } else {
+            // Default rule to mask email addresses
+            ScrubStage::push_rule(&mut stages, SanitizationRule::new(EMAIL_PATTERN, MaskStrategy::Redact)?)?;
+        }
+
+        Ok(SecurityManager {
+            keyring: ArcSwap::from_pointee(Keyring {
+                keys: BTreeMap::from([(INITIAL_KEY_ID, record_key)]),
+                wrapped: BTreeMap::new(),
+                active: INITIAL_KEY_ID,
+            }),
+            stages,
+            denied_keys: DEFAULT_DENIED_KEYS.iter().map(|k| k.to_string()).collect(),
+            signing_key: None,
+            chain: None,
+            encrypted_fields: None,
+            preset: None,
+            plaintext: false,
+            redactions: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Replaces the metadata key deny-list.
+    pub fn with_denied_keys<S: AsRef<str>>(mut self, keys: &[S]) -> Self {
+        self.denied_keys = keys.iter().map(|k| k.as_ref().to_lowercase()).collect();
+        self
+    }
+
+    /// Adds a sanitization rule applied after the existing ones.
+    pub fn with_rule(mut self, pattern: &str, mask: MaskStrategy) -> Result<Self, SecurityError> {
+        ScrubStage::push_rule(&mut self.stages, SanitizationRule::new(pattern, mask)?)?;
+        Ok(self)
+    }
+
+    /// Adds the built-in detector called `name` (see [`PII_DETECTORS`] and [`SECRET_DETECTORS`])
+    /// with its default mask.
+    pub fn with_detector(mut self, name: &str) -> Result<Self, SecurityError> {
+        ScrubStage::push_rule(&mut self.stages, detector(name, None)?)?;
+        Ok(self)
+    }
+
+    /// Adds a custom scrubber run after the rules and scrubbers installed so far.
+    pub fn with_scrubber(mut self, scrubber: Arc<dyn Scrubber>) -> Self {
+        self.stages.push(ScrubStage::Custom(scrubber));
+        self
+    }
+
+    /// Applies the `security:` section of the configuration. Its `patterns` replace the default
+    /// rules and are applied by the constructors instead, see [`from_key_source`](Self::from_key_source).
+    pub fn with_config(mut self, config: &SecurityConfig) -> Result<Self, SecurityError> {
+        if let Some(keys) = &config.denied_keys {
+            self = self.with_denied_keys(keys);
+        }
+        if let Some(preset) = config.preset {
+            self = self.with_preset(preset)?;
+        }
+        for name in config.detectors.iter().flatten() {
+            self = self.with_detector(name)?;
+        }
+        for rule in config.rules.iter().flatten() {
+            let mask = match (&rule.replacement, &rule.mask) {
+                (Some(replacement), _) => MaskStrategy::Replace(replacement.clone()),
+                (None, mask) => mask.clone().unwrap_or_default(),
+            };
+            self = self.with_rule(&rule.pattern, mask)?;
+        }
+        if let Some(paths) = &config.encrypted_fields {
+            self = self.with_encrypted_fields(paths);
+        }
+        if config.encryption == Some(false) {
+            self = self.with_plaintext()?;
+        }
+        Ok(self)
+    }
+
+    /// Writes records sanitized but unencrypted and without MACs, so hash chaining has no
+    /// effect; signatures are still added if enabled. Fails if a compliance preset
+    /// requiring encryption is in effect.
+    pub fn with_plaintext(mut self) -> Result<Self, SecurityError> {
+        if let Some(preset) = self.preset.filter(CompliancePreset::requires_encryption) {
+            return Err(SecurityError::EncryptionError(format!(
+                "The {} preset requires encryption",
+                preset.as_str()
+            )));
+        }
+        self.plaintext = true;
+        Ok(self)
+    }
+
+    /// Returns `true` if records are written unencrypted.
+    pub fn is_plaintext(&self) -> bool {
+        self.plaintext
+    }
+
+    /// Applies a compliance preset: adds its rules after the existing ones, extends the
+    /// deny-list, and enables hash chaining if the preset requires it.
+    pub fn with_preset(mut self, preset: CompliancePreset) -> Result<Self, SecurityError> {
+        for rule in preset.rules()? {
+            ScrubStage::push_rule(&mut self.stages, rule)?;
+        }
+        for key in preset.denied_keys() {
+            if !self.denied_keys.iter().any(|k| k == key) {
+                self.denied_keys.push(key.to_string());
+            }
+        }
+        if preset.requires_encryption() && self.plaintext {
+            return Err(SecurityError::EncryptionError(format!(
+                "The {} preset requires encryption",
+                preset.as_str()
+            )));
+        }
+        if preset.requires_hash_chain() && self.chain.is_none() {
+            self = self.with_hash_chain();
+        }
+        self.preset = Some(preset);
+        Ok(self)
+    }
+
+    /// Returns the compliance preset in effect, if any.
+    pub fn preset(&self) -> Option<CompliancePreset> {
+        self.preset
+    }
+
+    /// Encrypts only the metadata fields at the given dotted paths (e.g. `user.email`),
+    /// leaving the message and the other fields readable.
+    pub fn with_encrypted_fields<S: AsRef<str>>(mut self, paths: &[S]) -> Self {
+        self.encrypted_fields = Some(paths.iter().map(|p| p.as_ref().to_string()).collect());
+        self
+    }
+
+    /// Returns `true` if records are encrypted field by field rather than as a whole.
+    pub fn encrypts_fields(&self) -> bool {
+        self.encrypted_fields.is_some()
+    }
+
+    /// Signs every record with the Ed25519 key derived from `secret`.
+    pub fn with_signing_key(mut self, secret: &[u8; 32]) -> Self {
+        self.signing_key = Some(SigningKey::from_bytes(secret));
+        self
+    }
+
+    /// Chains record MACs: each MAC produced by [`record_mac`](Self::record_mac) also covers
+    /// the previous record's MAC, so [`verify_chain`](Self::verify_chain) detects records
+    /// inserted, deleted, or reordered in a file holding the full stream. Truncating the
+    /// end of a file is not detected.
+    pub fn with_hash_chain(mut self) -> Self {
+        self.chain = Some(Mutex::new(String::new()));
+        self
+    }
+
+    /// Returns `true` if record MACs are chained. A Logger then keeps one chain per
+    /// handler, over the records that handler receives.
+    pub fn is_hash_chained(&self) -> bool {
+        self.chain.is_some()
+    }
+
+    /// Counts redactions into `counter` instead of a private counter, e.g. to expose them
+    /// through [`MetricsManager`](crate::metrics::MetricsManager).
+    pub fn with_redaction_counter(mut self, counter: Arc<AtomicU64>) -> Self {
+        self.redactions = counter;
+        self
+    }
+
+    /// Returns how many values have been redacted or masked: denied metadata keys plus
+    /// matches of the sanitization rules and detectors. Custom scrubbers are not counted.
+    pub fn redaction_count(&self) -> u64 {
+        self.redactions.load(Ordering::Relaxed)
+    }
+
+    /// Generates a random Ed25519 secret key for [`with_signing_key`](Self::with_signing_key).
+    pub fn generate_signing_key() -> [u8; 32] {
+        Aes256Gcm::generate_key(&mut OsRng).into()
+    }
+
+    /// Returns the public key verifiers need to check record signatures, if signing is enabled.
+    pub fn verifying_key(&self) -> Option<[u8; 32]> {
+        self.signing_key.as_ref().map(|key| key.verifying_key().to_bytes())
+    }
+
+    /// Returns the base64-encoded Ed25519 signature of `log`, if signing is enabled. Sealed
+    /// records are signed over their [`record_content`](Self::record_content).
+    pub fn sign(&self, log: &str) -> Option<String> {
+        self.signing_key
+            .as_ref()
+            .map(|key| STANDARD.encode(key.sign(log.as_bytes()).to_bytes()))
+    }
+
+    /// Checks a signature produced by [`sign`](Self::sign) against an exported public key.
+    /// For a record, `log` is its [`record_content`](Self::record_content).
+    pub fn verify_signature(public_key: &[u8; 32], log: &str, signature: &str) -> Result<bool, SecurityError> {
+        let key = VerifyingKey::from_bytes(public_key).map_err(|e| SecurityError::SignatureError(e.to_string()))?;
+        let signature = match STANDARD.decode(signature).ok().and_then(|s| Signature::from_slice(&s).ok()) {
+            Some(signature) => signature,
+            None => return Ok(false),
+        };
+        Ok(key.verify(log.as_bytes(), &signature).is_ok())
+    }
+
+    /// Initializes the SecurityManager with a data key from `provider`: `wrapped_key` is
+    /// unwrapped if given, otherwise a fresh data key is generated. The wrapped form to
+    /// persist is available from [`wrapped_key`](Self::wrapped_key).
+    pub async fn from_key_provider(
+        provider: &dyn KeyProvider,
+        wrapped_key: Option<&[u8]>,
+        patterns: Option<Vec<String>>,
+    ) -> Result<Self, SecurityError> {
+        let (plaintext, wrapped) = match wrapped_key {
+            Some(wrapped) => (provider.unwrap(wrapped).await, wrapped.to_vec()),
+            None => match provider.generate_data_key().await {
+                Ok(key) => (Ok(key.plaintext), key.wrapped),
+                Err(e) => (Err(e), Vec::new()),
+            },
+        };
+        let plaintext = plaintext.map_err(|e| SecurityError::EncryptionError(e.to_string()))?;
+        let manager = SecurityManager::new(&plaintext, patterns)?;
+        manager.keyring.rcu(|keyring| {
+            let mut keyring = Keyring::clone(keyring);
+            keyring.wrapped.insert(INITIAL_KEY_ID, wrapped.clone());
+            keyring
+        });
+        Ok(manager)
+    }
+
+    /// Initializes the SecurityManager with the key from `source`.
+    pub async fn from_key_source(source: &KeySource, patterns: Option<Vec<String>>) -> Result<Self, SecurityError> {
+        match source {
+            KeySource::Provider(provider, wrapped_key) => {
+                Self::from_key_provider(provider.as_ref(), wrapped_key.as_deref(), patterns).await
+            }
+            source => {
+                let key = source.load().map_err(|e| SecurityError::EncryptionError(e.to_string()))?;
+                Self::new(&key, patterns)
+            }
+        }
+    }
+
+    /// Returns the wrapped form of key `id`, if it was obtained through a [`KeyProvider`].
+    pub fn wrapped_key(&self, id: u32) -> Option<Vec<u8>> {
+        self.keyring.load().wrapped.get(&id).cloned()
+    }
+
+    /// Makes `new_key` the active key for new records and returns its id. Older keys are
+    /// kept so records encrypted with them can still be decrypted.
+    pub fn rotate_key(&self, new_key: &[u8]) -> Result<u32, SecurityError> {
+        let record_key = record_key(new_key)?;
+        let mut id = 0;
+        self.keyring.rcu(|keyring| {
+            let mut keyring = Keyring::clone(keyring);
+            id = keyring.keys.keys().next_back().copied().unwrap_or(0) + 1;
+            keyring.keys.insert(id, record_key.clone());
+            keyring.active = id;
+            keyring
+        });
+        Ok(id)
+    }
+
+    /// Registers a key under `id` for decryption only, e.g. a retired key loaded by tooling.
+    /// Replaces any key already registered under that id, unless it is the active key.
+    pub fn add_key(&self, id: u32, key: &[u8]) -> Result<(), SecurityError> {
+        let record_key = record_key(key)?;
+        if self.active_key_id() == id {
+            return Err(SecurityError::EncryptionError(format!(
+                "Key {} is the active key",
+                id
+            )));
+        }
+        self.keyring.rcu(|keyring| {
+            let mut keyring = Keyring::clone(keyring);
+            keyring.keys.insert(id, record_key.clone());
+            keyring
+        });
+        Ok(())
+    }
+
+    /// Returns the id of the key used to encrypt new records.
+    pub fn active_key_id(&self) -> u32 {
+        self.keyring.load().active
+    }
+
+    /// Sanitizes the log message by applying all rules and scrubbers in order.
+    pub fn sanitize(&self, log: &str) -> String {
+        self.scrub(log, &mut Value::Object(Default::default()))
+    }
+
+    /// Sanitizes a record: redacts denied metadata keys, then runs the rules and scrubbers
+    /// in order. Rules apply to the message and to every string value in the metadata.
+    /// Returns the sanitized message.
+    pub fn scrub(&self, message: &str, metadata: &mut Value) -> String {
+        let mut redacted = self.redact_denied_keys(metadata);
+        let keyring = self.keyring.load();
+        let pseudonym_key = &keyring.keys[&keyring.active].pseudonym_key;
+        let mut sanitized = message.to_string();
+        for stage in &self.stages {
+            redacted += stage.apply(&mut sanitized, metadata, pseudonym_key.as_slice());
+        }
+        self.count_redactions(redacted);
+        sanitized
+    }
+
+    /// Redacts the values of denied keys anywhere in the metadata, including nested objects
+    /// and objects inside arrays.
+    pub fn sanitize_metadata(&self, metadata: &mut Value) {
+        let redacted = self.redact_denied_keys(metadata);
+        self.count_redactions(redacted);
+    }
+
+    fn count_redactions(&self, redacted: usize) {
+        self.redactions.fetch_add(redacted as u64, Ordering::Relaxed);
+        if redacted > 0 {
+            crate::metrics::facade::increment("logengine_redactions_total", None, redacted as u64);
+        }
+    }
+
+    /// Redacts denied keys, returning the number of values redacted.
+    fn redact_denied_keys(&self, metadata: &mut Value) -> usize {
+        match metadata {
+            Value::Object(fields) => {
+                let mut redacted = 0;
+                for (key, value) in fields.iter_mut() {
+                    if self.denied_keys.iter().any(|denied| denied.eq_ignore_ascii_case(key)) {
+                        *value = Value::String(REDACTED.to_string());
+                        redacted += 1;
+                    } else {
+                        redacted += self.redact_denied_keys(value);
+                    }
+                }
+                redacted
+            }
+            Value::Array(values) => values.iter_mut().map(|value| self.redact_denied_keys(value)).sum(),
+            _ => 0,
+        }
+    }
+
+    /// Encrypts the sanitized log message using AES-256-GCM without associated data.
+    pub fn encrypt(&self, log: &str) -> Result<String, SecurityError> {
+        self.encrypt_with_aad(log, &[])
+    }
+
+    /// Encrypts the log message using AES-256-GCM with a random nonce, authenticating `aad`
+    /// alongside it. Returns the base64-encoded versioned frame.
+    pub fn encrypt_with_aad(&self, log: &str, aad: &[u8]) -> Result<String, SecurityError> {
+        let keyring = self.keyring.load();
+        let cipher = &keyring.keys[&keyring.active].cipher;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, Payload { msg: log.as_bytes(), aad })
+            .map_err(|e| SecurityError::EncryptionError(e.to_string()))?;
+
+        let mut frame = Vec::with_capacity(1 + KEY_ID_LEN + NONCE_LEN + ciphertext.len());
+        frame.push(FRAME_VERSION);
+        frame.extend_from_slice(&keyring.active.to_be_bytes());
+        frame.extend_from_slice(&nonce);
+        frame.extend(ciphertext);
+        Ok(STANDARD.encode(&frame))
+    }
+
+    /// Builds the associated data binding a record's level and timestamp to its ciphertext.
+    pub fn associated_data(level: &str, timestamp: &str) -> Vec<u8> {
+        format!("{}\n{}", level, timestamp).into_bytes()
+    }
+
+    /// Returns what a record's MAC and signature cover: its level, its message as written,
+    /// and its metadata (timestamp, logged fields, key id, encrypted field paths) without
+    /// the `mac` and `signature` themselves, encoded as JSON with sorted keys.
+    pub fn record_content(level: &str, message: &str, metadata: &Value) -> String {
+        let mut metadata = metadata.clone();
+        if let Some(fields) = metadata.as_object_mut() {
+            fields.remove("mac");
+            fields.remove("signature");
+        }
+        canonical_json(&serde_json::json!([level, message, metadata]))
+    }
+
+    /// Sanitizes, encrypts and authenticates a record, returning the message to write and
+    /// the record metadata (`mac`, the `key_id` of the key it was made with, `timestamp`,
+    /// the sanitized `metadata`, and `signature` or `encrypted_fields` when enabled). The
+    /// MAC and signature cover the whole [`record_content`](Self::record_content). In plaintext mode the record is only sanitized.
+    pub fn seal(&self, log: &mut LogMessage) -> Result<(String, Value), SecurityError> {
+        let sanitized = self.scrub(&log.message, &mut log.metadata);
+        self.seal_sanitized(log, sanitized)
+    }
+
+    /// The second half of [`seal`](Self::seal): encrypts and authenticates a record whose
+    /// metadata was already scrubbed, `sanitized` being its scrubbed message.
+    pub fn seal_sanitized(&self, log: &mut LogMessage, sanitized: String) -> Result<(String, Value), SecurityError> {
+        let record = self.encrypt_record(log, sanitized)?;
+        let mac = self.mac_record(&record, self.chain.as_ref())?;
+        let EncryptedRecord { message, mut metadata, .. } = record;
+        if let Some(mac) = mac {
+            metadata["mac"] = Value::String(mac);
+        }
+        Ok((message, metadata))
+    }
+
+    /// [`seal_sanitized`](Self::seal_sanitized) without the MAC, for records written to
+    /// several chains: encrypts and signs the record, leaving its MAC to
+    /// [`mac_record`](Self::mac_record).
+    pub fn encrypt_record(&self, log: &mut LogMessage, sanitized: String) -> Result<EncryptedRecord, SecurityError> {
+        if self.plaintext {
+            let mut metadata = serde_json::json!({
+                "timestamp": log.timestamp,
+                "metadata": log.metadata,
+            });
+            if let Some(signature) = self.sign(&Self::record_content(log.level.as_str(), &sanitized, &metadata)) {
+                metadata["signature"] = Value::String(signature);
+            }
+            return Ok(EncryptedRecord {
+                message: sanitized,
+                metadata,
+                mac: None,
+            });
+        }
+        let aad = Self::associated_data(log.level.as_str(), &log.timestamp);
+        let (message, encrypted_fields) = if self.encrypts_fields() {
+            (sanitized, Some(self.encrypt_fields(&mut log.metadata, &aad)?))
+        } else {
+            (self.encrypt_with_aad(&sanitized, &aad)?, None)
+        };
+        let mut metadata = serde_json::json!({
+            "timestamp": log.timestamp,
+            "metadata": log.metadata,
+        });
+        if let Some(paths) = encrypted_fields {
+            metadata["encrypted_fields"] = serde_json::json!(paths);
+        }
+        let key_id = self.active_key_id();
+        metadata["key_id"] = Value::from(key_id);
+        let content = Self::record_content(log.level.as_str(), &message, &metadata);
+        if let Some(signature) = self.sign(&content) {
+            metadata["signature"] = Value::String(signature);
+        }
+        Ok(EncryptedRecord {
+            message,
+            metadata,
+            mac: Some((key_id, content)),
+        })
+    }
+
+    /// Computes the MAC of a record from [`encrypt_record`](Self::encrypt_record), chained
+    /// to the previous MAC held by `chain` when given, or `None` in plaintext mode.
+    pub fn mac_record(&self, record: &EncryptedRecord, chain: Option<&Mutex<String>>) -> Result<Option<String>, SecurityError> {
+        match &record.mac {
+            Some((key_id, content)) => self.chained_mac(chain, *key_id, content).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Decrypts a frame produced by [`encrypt`](Self::encrypt).
+    pub fn decrypt(&self, ciphertext: &str) -> Result<String, SecurityError> {
+        self.decrypt_with_aad(ciphertext, &[])
+    }
+
+    /// Decrypts a frame produced by [`encrypt_with_aad`](Self::encrypt_with_aad); fails if
+    /// the frame or `aad` was tampered with.
+    pub fn decrypt_with_aad(&self, ciphertext: &str, aad: &[u8]) -> Result<String, SecurityError> {
+        let frame = STANDARD
+            .decode(ciphertext.trim())
+            .map_err(|e| SecurityError::DecryptionError(e.to_string()))?;
+        let (key_id, body) = match frame.first() {
+            Some(1) => (INITIAL_KEY_ID, &frame[1..]),
+            Some(&FRAME_VERSION) if frame.len() > KEY_ID_LEN => {
+                let (id, body) = frame[1..].split_at(KEY_ID_LEN);
+                (u32::from_be_bytes([id[0], id[1], id[2], id[3]]), body)
+            }
+            Some(&FRAME_VERSION) | None => {
+                return Err(SecurityError::DecryptionError("Frame is too short".into()))
+            }
+            Some(version) => {
+                return Err(SecurityError::DecryptionError(format!(
+                    "Unsupported frame version: {}",
+                    version
+                )))
+            }
+        };
+        if body.len() < NONCE_LEN {
+            return Err(SecurityError::DecryptionError("Frame is too short".into()));
+        }
+        let keyring = self.keyring.load();
+        let cipher = &keyring
+            .keys
+            .get(&key_id)
+            .ok_or_else(|| SecurityError::DecryptionError(format!("Unknown key id: {}", key_id)))?
+            .cipher;
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        let plaintext = cipher
+            .decrypt(nonce.into(), Payload { msg: ciphertext, aad })
+            .map_err(|_| SecurityError::DecryptionError("Authentication failed".into()))?;
+        String::from_utf8(plaintext).map_err(|e| SecurityError::DecryptionError(e.to_string()))
+    }
+
+    /// Replaces each configured metadata field with an encrypted frame of its JSON value,
+    /// returning the paths that were present. The path is bound to `aad`, so encrypted
+    /// values cannot be moved between fields.
+    pub fn encrypt_fields(&self, metadata: &mut Value, aad: &[u8]) -> Result<Vec<String>, SecurityError> {
+        let mut encrypted = Vec::new();
+        for path in self.encrypted_fields.iter().flatten() {
+            if let Some(value) = field_mut(metadata, path) {
+                let frame = self.encrypt_with_aad(&value.to_string(), &field_aad(aad, path))?;
+                *value = Value::String(frame);
+                encrypted.push(path.clone());
+            }
+        }
+        Ok(encrypted)
+    }
+
+    /// Reverses [`encrypt_fields`](Self::encrypt_fields) for the given paths.
+    pub fn decrypt_fields<S: AsRef<str>>(
+        &self,
+        metadata: &mut Value,
+        paths: &[S],
+        aad: &[u8],
+    ) -> Result<(), SecurityError> {
+        for path in paths {
+            let path = path.as_ref();
+            let value = field_mut(metadata, path)
+                .ok_or_else(|| SecurityError::DecryptionError(format!("Missing encrypted field: {}", path)))?;
+            let frame = value
+                .as_str()
+                .ok_or_else(|| SecurityError::DecryptionError(format!("Field is not encrypted: {}", path)))?;
+            let plaintext = self.decrypt_with_aad(frame, &field_aad(aad, path))?;
+            *value =
+                serde_json::from_str(&plaintext).map_err(|e| SecurityError::DecryptionError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Decrypts a record written by the JSON formatter. The `message` is replaced by its
+    /// plaintext, or, for records listing `encrypted_fields`, those metadata fields are.
+    pub fn decrypt_record(&self, record: &str) -> Result<String, SecurityError> {
+        let mut record: Value =
+            serde_json::from_str(record).map_err(|e| SecurityError::DecryptionError(e.to_string()))?;
+        let field = |value: &Value| value.as_str().map(str::to_string);
+        let (message, level, timestamp) = match (
+            field(&record["message"]),
+            field(&record["level"]),
+            field(&record["metadata"]["timestamp"]),
+        ) {
+            (Some(message), Some(level), Some(timestamp)) => (message, level, timestamp),
+            _ => {
+                return Err(SecurityError::DecryptionError(
+                    "Record lacks message, level or timestamp".into(),
+                ))
+            }
+        };
+        let aad = Self::associated_data(&level, &timestamp);
+        if let Some(paths) = record["metadata"]["encrypted_fields"].as_array().cloned() {
+            let paths: Vec<&str> = paths.iter().filter_map(Value::as_str).collect();
+            self.decrypt_fields(&mut record["metadata"]["metadata"], &paths, &aad)?;
+        } else {
+            record["message"] = Value::String(self.decrypt_with_aad(&message, &aad)?);
+        }
+        Ok(record.to_string())
+    }
+
+    /// Decrypts every record of a JSON log file, one record per line. Blank lines are skipped.
+    pub fn decrypt_file(&self, path: &Path) -> Result<Vec<String>, SecurityError> {
+        let file = File::open(path).map_err(|e| SecurityError::DecryptionError(e.to_string()))?;
+        let mut records = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| SecurityError::DecryptionError(e.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = self.decrypt_record(&line).map_err(|e| {
+                SecurityError::DecryptionError(format!("line {}: {}", index + 1, e))
+            })?;
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    /// Computes the hex-encoded HMAC-SHA256 of the encrypted log message, keyed with a MAC
+    /// key derived from the active encryption key.
+    pub fn mac(&self, log: &str) -> Result<String, SecurityError> {
+        let keyring = self.keyring.load();
+        let mac = Self::keyed_mac(keyring.keys[&keyring.active].mac_key.as_slice(), None, log)?;
+        Ok(hex::encode(mac.finalize().into_bytes()))
+    }
+
+    /// Computes the MAC stored with a record: chained to the previous record's MAC when
+    /// [`with_hash_chain`](Self::with_hash_chain) is enabled, a plain [`mac`](Self::mac) otherwise.
+    /// Records must be passed in the order they are written.
+    pub fn record_mac(&self, log: &str) -> Result<String, SecurityError> {
+        self.chained_mac(self.chain.as_ref(), self.active_key_id(), log)
+    }
+
+    /// Computes the MAC of `content` with the key `key_id`, chained to and then replacing
+    /// the previous MAC held by `chain` when given.
+    fn chained_mac(&self, chain: Option<&Mutex<String>>, key_id: u32, content: &str) -> Result<String, SecurityError> {
+        let mut previous = chain.map(|chain| chain.lock().unwrap_or_else(|e| e.into_inner()));
+        let keyring = self.keyring.load();
+        let mac_key = keyring
+            .keys
+            .get(&key_id)
+            .ok_or_else(|| SecurityError::IntegrityError(format!("Unknown key id: {}", key_id)))?
+            .mac_key
+            .as_slice();
+        let mac = Self::keyed_mac(mac_key, previous.as_deref().map(String::as_str), content)?;
+        let mac = hex::encode(mac.finalize().into_bytes());
+        if let Some(previous) = previous.as_deref_mut() {
+            previous.clone_from(&mac);
+        }
+        Ok(mac)
+    }
+
+    /// Verifies a MAC produced by [`mac`](Self::mac). Encrypted frames are checked with the
+    /// key they were encrypted with, anything else with the active key. The comparison runs
+    /// in constant time.
+    pub fn verify_integrity(&self, log: &str, mac: &str) -> Result<bool, SecurityError> {
+        let key_id = frame_key_id(log).unwrap_or_else(|| self.active_key_id());
+        self.verify_mac(None, log, mac, key_id)
+    }
+
+    /// Verifies the MAC of a sealed record, given as its level, message and metadata.
+    fn verify_record(&self, previous: Option<&str>, level: &str, message: &str, metadata: &Value) -> Result<bool, SecurityError> {
+        let Some(mac) = metadata["mac"].as_str() else {
+            return Ok(false);
+        };
+        // Records written before key ids were stored name their key in the frame, if at all
+        let key_id = envelope_key_id(metadata)
+            .or_else(|| frame_key_id(message))
+            .unwrap_or_else(|| self.active_key_id());
+        self.verify_mac(previous, &Self::record_content(level, message, metadata), mac, key_id)
+    }
+
+    /// Verifies the hash chain of a JSON log file, one record per line, returning the
+    /// number of records checked. Blank lines are skipped.
+    pub fn verify_chain<R: BufRead>(&self, reader: R) -> Result<usize, SecurityError> {
+        let mut previous = String::new();
+        let mut records = 0;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| SecurityError::IntegrityError(e.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: Value = serde_json::from_str(&line).map_err(|_| SecurityError::ChainBroken(index + 1))?;
+            let (level, message) = match (record["level"].as_str(), record["message"].as_str()) {
+                (Some(level), Some(message)) => (level, message),
+                _ => return Err(SecurityError::ChainBroken(index + 1)),
+            };
+            if !self.verify_record(Some(&previous), level, message, &record["metadata"])? {
+                return Err(SecurityError::ChainBroken(index + 1));
+            }
+            previous = record["metadata"]["mac"].as_str().unwrap_or_default().to_string();
+            records += 1;
+        }
+        Ok(records)
+    }
+
+    fn verify_mac(&self, previous: Option<&str>, log: &str, mac: &str, key_id: u32) -> Result<bool, SecurityError> {
+        let expected = match hex::decode(mac) {
+            Ok(expected) => expected,
+            Err(_) => return Ok(false),
+        };
+        let keyring = self.keyring.load();
+        let record_key = keyring
+            .keys
+            .get(&key_id)
+            .ok_or_else(|| SecurityError::IntegrityError(format!("Unknown key id: {}", key_id)))?;
+        Ok(Self::keyed_mac(record_key.mac_key.as_slice(), previous, log)?
+            .verify_slice(&expected)
+            .is_ok())
+    }
+
+    fn keyed_mac(mac_key: &[u8], previous: Option<&str>, log: &str) -> Result<HmacSha256, SecurityError> {
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(mac_key)
+            .map_err(|e| SecurityError::IntegrityError(e.to_string()))?;
+        if let Some(previous) = previous {
+            mac.update(previous.as_bytes());
+            mac.update(b"\n");
+        }
+        mac.update(log.as_bytes());
+        Ok(mac)
+    }
+}
+
+/// Returns the metadata value at a dotted path, if every segment exists.
+fn field_mut<'a>(metadata: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('.').try_fold(metadata, |value, key| value.get_mut(key))
+}
+
+/// Associated data for a field-level frame: the record's data plus the field path.
+fn field_aad(aad: &[u8], path: &str) -> Vec<u8> {
+    let mut field_aad = aad.to_vec();
+    field_aad.push(0);
+    field_aad.extend_from_slice(path.as_bytes());
+    field_aad
+}
+
+/// Encodes `value` as JSON with the keys of every object sorted, whatever the map order.
+fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(fields) => {
+            let mut keys: Vec<&String> = fields.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys
+                .into_iter()
+                .map(|key| format!("{}:{}", Value::from(key.as_str()), canonical_json(&fields[key])))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => format!("[{}]", items.iter().map(canonical_json).collect::<Vec<_>>().join(",")),
+        value => value.to_string(),
+    }
+}
+
+/// Returns the id of the key a record's MAC was made with, as stored in its metadata.
+fn envelope_key_id(envelope: &Value) -> Option<u32> {
+    envelope["key_id"].as_u64().and_then(|id| u32::try_from(id).ok())
+}
+
+/// Returns the key id of an encrypted frame, if `log` is one.
+fn frame_key_id(log: &str) -> Option<u32> {
+    let frame = STANDARD.decode(log.trim()).ok()?;
+    match frame.first()? {
+        1 => Some(INITIAL_KEY_ID),
+        &FRAME_VERSION if frame.len() > KEY_ID_LEN => {
+            Some(u32::from_be_bytes([frame[1], frame[2], frame[3], frame[4]]))
+        }
+        _ => None,
+    }
+}
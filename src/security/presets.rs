@@ -0,0 +1,90 @@
+use super::{detector, MaskStrategy, SanitizationRule, SecurityError, EMAIL_PATTERN};
+use serde::{Deserialize, Serialize};
+
+/// A named bundle of security settings giving a defensible default for a regulation,
+/// selected with `security: { preset: pci }`. Settings from the rest of the `security:`
+/// section are applied on top of the preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompliancePreset {
+    /// PCI DSS: card numbers keep their last four digits, card security data is redacted,
+    /// and records are hash-chained.
+    Pci,
+    /// HIPAA: identifiers such as SSNs, phone numbers and IPs are redacted, common PHI
+    /// fields are denied, and records are hash-chained.
+    Hipaa,
+    /// GDPR: emails and IPs are pseudonymized so records stay correlatable, other personal
+    /// data is redacted.
+    Gdpr,
+}
+
+impl CompliancePreset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompliancePreset::Pci => "pci",
+            CompliancePreset::Hipaa => "hipaa",
+            CompliancePreset::Gdpr => "gdpr",
+        }
+    }
+
+    /// Sanitization rules the preset installs, in order.
+    pub fn rules(&self) -> Result<Vec<SanitizationRule>, SecurityError> {
+        match self {
+            CompliancePreset::Pci => Ok(vec![
+                detector("credit_card", Some(MaskStrategy::KeepLast(4)))?,
+                detector("pem_private_key", None)?,
+            ]),
+            CompliancePreset::Hipaa => ["ssn", "phone", "ipv4", "ipv6"]
+                .iter()
+                .map(|name| detector(name, None))
+                .chain([SanitizationRule::new(EMAIL_PATTERN, MaskStrategy::Redact)])
+                .collect(),
+            CompliancePreset::Gdpr => Ok(vec![
+                SanitizationRule::new(EMAIL_PATTERN, MaskStrategy::Pseudonymize)?,
+                detector("iban", None)?,
+                detector("phone", None)?,
+                detector("ipv4", Some(MaskStrategy::Pseudonymize))?,
+                detector("ipv6", Some(MaskStrategy::Pseudonymize))?,
+            ]),
+        }
+    }
+
+    /// Metadata keys added to the deny-list.
+    pub fn denied_keys(&self) -> &'static [&'static str] {
+        match self {
+            CompliancePreset::Pci => &["card_number", "pan", "cvv", "cvc", "pin", "track_data", "expiry"],
+            CompliancePreset::Hipaa => &[
+                "patient_name",
+                "mrn",
+                "date_of_birth",
+                "dob",
+                "diagnosis",
+                "insurance_id",
+            ],
+            CompliancePreset::Gdpr => &["name", "full_name", "address", "date_of_birth", "dob", "national_id"],
+        }
+    }
+
+    /// Whether the preset requires records to be encrypted, whole or field by field.
+    pub fn requires_encryption(&self) -> bool {
+        true
+    }
+
+    /// Whether records must be tamper-evident, i.e. hash-chained.
+    pub fn requires_hash_chain(&self) -> bool {
+        matches!(self, CompliancePreset::Pci | CompliancePreset::Hipaa)
+    }
+
+    /// Suggested minimum retention for the written logs, in days. This is only a hint for
+    /// whatever archives the files; the logger does not delete records.
+    pub fn retention_days(&self) -> u32 {
+        match self {
+            // PCI DSS 10.7: one year, three months immediately available
+            CompliancePreset::Pci => 365,
+            // HIPAA documentation retention: six years
+            CompliancePreset::Hipaa => 6 * 365,
+            // GDPR storage limitation: keep personal data no longer than needed
+            CompliancePreset::Gdpr => 30,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use super::masking::RuleSet;
+use super::{SanitizationRule, SecurityError};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Domain-specific redaction applied to every record before it is encrypted.
+///
+/// Scrubbers run in the order they are installed, interleaved with the built-in regex
+/// rules and detectors, after the metadata key deny-list has been applied.
+pub trait Scrubber: Send + Sync {
+    /// Redacts sensitive data in place.
+    fn scrub(&self, message: &mut String, metadata: &mut Value);
+}
+
+/// One step of the sanitization chain.
+pub(crate) enum ScrubStage {
+    Rules(RuleSet),
+    Custom(Arc<dyn Scrubber>),
+}
+
+impl ScrubStage {
+    /// Appends `rule` to the chain, merging it into the preceding rule set if there is one.
+    pub(crate) fn push_rule(stages: &mut Vec<ScrubStage>, rule: SanitizationRule) -> Result<(), SecurityError> {
+        match stages.last_mut() {
+            Some(ScrubStage::Rules(rules)) => rules.push(rule),
+            _ => {
+                stages.push(ScrubStage::Rules(RuleSet::new(rule)?));
+                Ok(())
+            }
+        }
+    }
+
+    /// Runs the stage, returning the number of values the built-in rules masked. Custom
+    /// scrubbers are not counted.
+    pub(crate) fn apply(&self, message: &mut String, metadata: &mut Value, pseudonym_key: &[u8]) -> usize {
+        match self {
+            ScrubStage::Rules(rules) => {
+                rules.apply(message, pseudonym_key) + rules.apply_to_value(metadata, pseudonym_key)
+            }
+            ScrubStage::Custom(scrubber) => {
+                scrubber.scrub(message, metadata);
+                0
+            }
+        }
+    }
+}
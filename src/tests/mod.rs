@@ -1,2 +1,4 @@
+#[allow(clippy::module_inception)]
 pub mod integration_tests;
+#[allow(clippy::module_inception)]
 pub mod unit_tests;
@@ -0,0 +1,94 @@
+use crate::logger::{Location, Logger};
+use crate::utils::{Instant, LogLevel};
+use serde_json::{json, Map, Value};
+use std::future::Future;
+use std::time::Duration;
+
+pub use log_engine_derive::log_instrument;
+
+/// Logs the time elapsed since its creation when dropped. Created by [`Logger::time`].
+#[must_use = "the duration is logged when the guard is dropped"]
+pub struct TimerGuard<'a> {
+    logger: &'a Logger,
+    label: String,
+    level: LogLevel,
+    metadata: Map<String, Value>,
+    location: Option<Location>,
+    start: Instant,
+    armed: bool,
+}
+
+impl<'a> TimerGuard<'a> {
+    pub(crate) fn new(logger: &'a Logger, label: &str) -> Self {
+        TimerGuard {
+            logger,
+            label: label.to_string(),
+            level: LogLevel::DEBUG,
+            metadata: Map::new(),
+            location: None,
+            start: Instant::now(),
+            armed: true,
+        }
+    }
+
+    /// Sets the level the duration is logged at (DEBUG by default).
+    pub fn level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Adds extra metadata to the timing record.
+    pub fn with_metadata(mut self, metadata: Value) -> Self {
+        if let Value::Object(fields) = metadata {
+            self.metadata.extend(fields);
+        }
+        self
+    }
+
+    /// Records `location` as the source of the timing record.
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Returns the time elapsed so far.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Drops the guard without logging.
+    pub fn cancel(mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for TimerGuard<'_> {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        let elapsed_ms = self.start.elapsed().as_secs_f64() * 1000.0;
+        let mut metadata = std::mem::take(&mut self.metadata);
+        metadata.insert("label".to_string(), json!(self.label));
+        metadata.insert("elapsed_ms".to_string(), json!(elapsed_ms));
+        self.logger.log_at(
+            self.level,
+            &format!("{} finished in {:.3} ms", self.label, elapsed_ms),
+            Some(Value::Object(metadata)),
+            self.location,
+        );
+    }
+}
+
+impl Logger {
+    /// Starts timing `label`; the elapsed duration is logged when the returned guard is dropped.
+    pub fn time(&self, label: &str) -> TimerGuard<'_> {
+        TimerGuard::new(self, label)
+    }
+
+    /// Awaits `future` and logs how long it took under `label`.
+    pub async fn time_future<F: Future>(&self, label: &str, future: F) -> F::Output {
+        let _timer = self.time(label);
+        future.await
+    }
+}
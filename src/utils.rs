@@ -1,14 +1,125 @@
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt;
-use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[derive(Error, Debug)]
+pub enum LevelError {
+    #[error("Level name is already in use: {0}")]
+    NameInUse(String),
+    #[error("Severity {0} is already in use by {1}")]
+    SeverityInUse(u16, String),
+}
+
+/// Log severity. Besides the built-in levels, custom levels can be registered with
+/// [`LogLevel::register`] and slot anywhere between them by severity.
+///
+/// Levels are ordered by severity, so `LogLevel::DEBUG < LogLevel::ERROR`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum LogLevel {
-    TRACE = 0,
+    TRACE,
     DEBUG,
     INFO,
     WARN,
     ERROR,
     FATAL,
+    /// A registered custom level, identified by its severity.
+    Custom(u16),
+}
+
+/// Severity below which logging macro calls are compiled out, chosen with the
+/// `max_level_*` features, or the `release_max_level_*` features in builds without debug
+/// assertions. The most restrictive enabled feature wins; `max_level_off` is above every
+/// severity, and without any of the features every call is kept.
+pub const STATIC_MIN_SEVERITY: u32 = {
+    let release = !cfg!(debug_assertions);
+    if cfg!(feature = "max_level_off") || (release && cfg!(feature = "release_max_level_off")) {
+        u16::MAX as u32 + 1
+    } else if cfg!(feature = "max_level_fatal") || (release && cfg!(feature = "release_max_level_fatal")) {
+        LogLevel::FATAL.severity() as u32
+    } else if cfg!(feature = "max_level_error") || (release && cfg!(feature = "release_max_level_error")) {
+        LogLevel::ERROR.severity() as u32
+    } else if cfg!(feature = "max_level_warn") || (release && cfg!(feature = "release_max_level_warn")) {
+        LogLevel::WARN.severity() as u32
+    } else if cfg!(feature = "max_level_info") || (release && cfg!(feature = "release_max_level_info")) {
+        LogLevel::INFO.severity() as u32
+    } else if cfg!(feature = "max_level_debug") || (release && cfg!(feature = "release_max_level_debug")) {
+        LogLevel::DEBUG.severity() as u32
+    } else if cfg!(feature = "max_level_trace") || (release && cfg!(feature = "release_max_level_trace")) {
+        LogLevel::TRACE.severity() as u32
+    } else {
+        0
+    }
+};
+
+/// Returns `true` if logging at `level` is compiled in; see [`STATIC_MIN_SEVERITY`].
+#[inline]
+#[allow(clippy::absurd_extreme_comparisons)] // always true without a max_level feature
+pub const fn static_enabled(level: LogLevel) -> bool {
+    level.severity() as u32 >= STATIC_MIN_SEVERITY
+}
+
+/// Returns the current time for record timestamps. In the browser (the `wasm` feature on
+/// `wasm32`), where `std` has no clock, it is read from `js_sys::Date`.
+pub fn now() -> DateTime<Utc> {
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    {
+        DateTime::from_timestamp_millis(js_sys::Date::now() as i64).unwrap_or_default()
+    }
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    {
+        Utc::now()
+    }
+}
+
+/// Monotonic clock for measuring durations. In the browser, where `std::time::Instant`
+/// panics, it is read from `performance.now()`.
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub use wasmtimer::std::Instant;
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub use std::time::Instant;
+
+/// Timers of the background tasks: tokio's, or in the browser those of `wasmtimer`, which
+/// need no tokio runtime.
+pub(crate) mod time {
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    pub(crate) use tokio::time::{interval, sleep, timeout};
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    pub(crate) use wasmtimer::tokio::{interval, sleep, timeout};
+}
+
+/// Spawns a background task on the current tokio runtime, or in the browser on the JS
+/// event loop.
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub(crate) fn spawn(task: impl std::future::Future<Output = ()> + Send + 'static) {
+    tokio::spawn(task);
+}
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub(crate) fn spawn(task: impl std::future::Future<Output = ()> + 'static) {
+    wasm_bindgen_futures::spawn_local(task);
+}
+
+/// Runtime for the background tasks of Loggers created from outside Rust, through the C
+/// API or the Python module, kept for the life of the process.
+#[cfg(any(feature = "ffi", feature = "python"))]
+pub(crate) fn runtime() -> std::io::Result<&'static tokio::runtime::Runtime> {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = tokio::runtime::Runtime::new()?;
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+/// Custom levels by severity, with their names leaked so `as_str` can stay `'static`.
+static CUSTOM_LEVELS: OnceLock<RwLock<HashMap<u16, &'static str>>> = OnceLock::new();
+
+fn custom_levels() -> &'static RwLock<HashMap<u16, &'static str>> {
+    CUSTOM_LEVELS.get_or_init(|| RwLock::new(HashMap::new()))
 }
 
 impl fmt::Display for LogLevel {
@@ -18,6 +129,66 @@ impl fmt::Display for LogLevel {
 }
 
 impl LogLevel {
+    /// Built-in levels, in increasing severity.
+    pub const BUILTIN: [LogLevel; 6] = [
+        LogLevel::TRACE,
+        LogLevel::DEBUG,
+        LogLevel::INFO,
+        LogLevel::WARN,
+        LogLevel::ERROR,
+        LogLevel::FATAL,
+    ];
+
+    /// Threshold no record reaches, from a configured level of `OFF`. It renders as `OFF`
+    /// and its severity, `u16::MAX`, cannot be registered; it is not a level to log at.
+    pub const OFF: LogLevel = LogLevel::Custom(u16::MAX);
+
+    /// Registers a custom level. Built-in severities are TRACE=100, DEBUG=200, INFO=300,
+    /// WARN=400, ERROR=500 and FATAL=600, so e.g. an AUDIT level at 350 sorts between
+    /// INFO and WARN. Registering the same name and severity again is a no-op.
+    pub fn register(name: &str, severity: u16) -> Result<LogLevel, LevelError> {
+        let name = name.to_uppercase();
+        let mut levels = custom_levels().write().unwrap_or_else(|e| e.into_inner());
+        if let Some(existing) = Self::BUILTIN.iter().chain([&Self::OFF]).find(|l| l.severity() == severity) {
+            return Err(LevelError::SeverityInUse(severity, existing.as_str().to_string()));
+        }
+        match levels.get(&severity) {
+            Some(existing) if *existing == name => return Ok(LogLevel::Custom(severity)),
+            Some(existing) => return Err(LevelError::SeverityInUse(severity, existing.to_string())),
+            None => {}
+        }
+        if Self::BUILTIN.iter().chain([&Self::OFF]).any(|l| l.as_str() == name) || levels.values().any(|n| *n == name) {
+            return Err(LevelError::NameInUse(name));
+        }
+        levels.insert(severity, Box::leak(name.into_boxed_str()));
+        Ok(LogLevel::Custom(severity))
+    }
+
+    /// Returns `true` if a message at this level passes the given threshold,
+    /// i.e. this level is at least as severe as `threshold`.
+    pub fn enabled_at(&self, threshold: LogLevel) -> bool {
+        *self >= threshold
+    }
+
+    /// Returns `true` if this level is one of the built-in levels.
+    pub fn is_builtin(&self) -> bool {
+        !matches!(self, LogLevel::Custom(_))
+    }
+
+    /// Returns the numeric severity used to order levels.
+    pub const fn severity(&self) -> u16 {
+        match self {
+            LogLevel::TRACE => 100,
+            LogLevel::DEBUG => 200,
+            LogLevel::INFO => 300,
+            LogLevel::WARN => 400,
+            LogLevel::ERROR => 500,
+            LogLevel::FATAL => 600,
+            LogLevel::Custom(severity) => *severity,
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(level: &str) -> Option<Self> {
         match level.to_uppercase().as_str() {
             "TRACE" => Some(LogLevel::TRACE),
@@ -26,18 +197,67 @@ impl LogLevel {
             "WARN" => Some(LogLevel::WARN),
             "ERROR" => Some(LogLevel::ERROR),
             "FATAL" => Some(LogLevel::FATAL),
-            _ => None,
+            name => custom_levels()
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .find(|(_, n)| **n == name)
+                .map(|(severity, _)| LogLevel::Custom(*severity)),
         }
     }
 
+    /// Returns the built-in or registered custom level with this severity.
+    pub fn from_severity(severity: u16) -> Option<Self> {
+        if let Some(level) = Self::BUILTIN.into_iter().find(|l| l.severity() == severity) {
+            return Some(level);
+        }
+        let levels = custom_levels().read().unwrap_or_else(|e| e.into_inner());
+        levels.contains_key(&severity).then_some(LogLevel::Custom(severity))
+    }
+
     pub fn as_str(&self) -> &'static str {
-        match self {
+        match *self {
             LogLevel::TRACE => "TRACE",
             LogLevel::DEBUG => "DEBUG",
             LogLevel::INFO => "INFO",
             LogLevel::WARN => "WARN",
             LogLevel::ERROR => "ERROR",
             LogLevel::FATAL => "FATAL",
+            LogLevel::OFF => "OFF",
+            LogLevel::Custom(severity) => custom_levels()
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(&severity)
+                .copied()
+                .unwrap_or("CUSTOM"),
         }
     }
-}
\ No newline at end of file
+}
+
+impl Ord for LogLevel {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // A hand-built Custom level may share a built-in's severity; keep Ord consistent with Eq
+        self.severity()
+            .cmp(&other.severity())
+            .then_with(|| other.is_builtin().cmp(&self.is_builtin()))
+    }
+}
+
+impl PartialOrd for LogLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Serialize for LogLevel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for LogLevel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        LogLevel::from_str(&name).ok_or_else(|| serde::de::Error::custom(format!("unknown level: {}", name)))
+    }
+}